@@ -4,68 +4,50 @@ use super::ffi::*;
 use std::ptr;
 use std::os::raw::c_void;
 use std::fmt::{self, Debug};
-use cumath::*;
 
 
 
 
 
 pub struct CuActivationDescriptor {
-    data: *mut _ActivationDescriptorStruct,
+    pub(crate) data: *mut _ActivationDescriptorStruct,
 }
 
 impl Drop for CuActivationDescriptor {
     fn drop(&mut self) {
-        cudnn_destroy_activation_descriptor(self.data)
+        let _ = cudnn_destroy_activation_descriptor(self.data);
     }
 }
 
 impl CuActivationDescriptor {
 
-    pub fn new(mode: CudnnActivationMode, coef: f64) -> CuActivationDescriptor {
+    pub fn new(mode: CudnnActivationMode, coef: f64) -> Result<CuActivationDescriptor, CudnnError> {
         let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, mode, CudnnNanPropagation::Propagate, coef);
-        CuActivationDescriptor { data }
+        cudnn_create_activation_descriptor(&mut data)?;
+        let output = CuActivationDescriptor { data };
+        cudnn_set_activation_descriptor(output.data, mode, CudnnNanPropagation::Propagate, coef)?;
+        Ok(output)
     }
-    pub fn sigmoid() -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::Sigmoid, CudnnNanPropagation::Propagate, 1.0);
-        CuActivationDescriptor { data }
+    pub fn sigmoid() -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::Sigmoid, 1.0)
     }
-    pub fn relu() -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::Relu, CudnnNanPropagation::Propagate, 1.0);
-        CuActivationDescriptor { data }
+    pub fn relu() -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::Relu, 1.0)
     }
-    pub fn tanh() -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::Tanh, CudnnNanPropagation::Propagate, 1.0);
-        CuActivationDescriptor { data }
+    pub fn tanh() -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::Tanh, 1.0)
     }
-    pub fn clipped_relu(threshold: f64) -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::ClippedRelu, CudnnNanPropagation::Propagate, threshold);
-        CuActivationDescriptor { data }
+    pub fn clipped_relu(threshold: f64) -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::ClippedRelu, threshold)
     }
-    pub fn elu(alpha: f64) -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::Elu, CudnnNanPropagation::Propagate, alpha);
-        CuActivationDescriptor { data }
+    pub fn elu(alpha: f64) -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::Elu, alpha)
     }
-    /*pub fn identity() -> CuActivationDescriptor {
-        let mut data = ptr::null_mut();
-        cudnn_create_activation_descriptor(&mut data);
-        cudnn_set_activation_descriptor(data, CudnnActivationMode::Identity, CudnnNanPropagation::Propagate, 1.0);
-        CuActivationDescriptor { data }
+    /*pub fn identity() -> Result<CuActivationDescriptor, CudnnError> {
+        Self::new(CudnnActivationMode::Identity, 1.0)
     }*/
 
-    pub fn get_info(&self) -> CuActivationDescriptorInfo {
+    pub fn get_info(&self) -> Result<CuActivationDescriptorInfo, CudnnError> {
         let mut mode = CudnnActivationMode::Elu;
         let mut relu_nan_opt = CudnnNanPropagation::NotPropagate;
         let mut coef = -9999.0;
@@ -74,43 +56,43 @@ impl CuActivationDescriptor {
             &mut mode,
             &mut relu_nan_opt,
             &mut coef,
-        );
-        CuActivationDescriptorInfo { mode, relu_nan_opt, coef }
+        )?;
+        Ok(CuActivationDescriptorInfo { mode, relu_nan_opt, coef })
     }
 
-    pub fn forward<T: CuDataType>(&self, cudnn: &Cudnn, input: &CuTensorDeref<T>, input_scale: T, output: &mut CuTensorDeref<T>, output_scale: T) {
+    pub fn forward<T: CudnnType>(&self, cudnn: &Cudnn, input: &CuTensorDeref<T>, input_scale: T::Scaling, output: &mut CuTensorDeref<T>, output_scale: T::Scaling) -> Result<(), CudnnError> {
         cudnn_activation_forward(cudnn.handle, self.data,
-                                 &input_scale as *const T as *const c_void, input.descriptor.data, input.data as *const c_void,
-                                 &output_scale as *const T as *const c_void, output.descriptor.data, output.data as *mut c_void)
+                                 &input_scale as *const T::Scaling as *const c_void, input.descriptor.data, input.data as *const c_void,
+                                 &output_scale as *const T::Scaling as *const c_void, output.descriptor.data, output.data as *mut c_void)
     }
-    pub fn forward_inplace<T: CuDataType>(&self, cudnn: &Cudnn, vector: &mut CuTensorDeref<T>, input_scale: T, output_scale: T) {
+    pub fn forward_inplace<T: CudnnType>(&self, cudnn: &Cudnn, vector: &mut CuTensorDeref<T>, input_scale: T::Scaling, output_scale: T::Scaling) -> Result<(), CudnnError> {
         cudnn_activation_forward(cudnn.handle, self.data,
-                                 &input_scale as *const T as *const c_void, vector.descriptor.data, vector.data as *const c_void,
-                                 &output_scale as *const T as *const c_void, vector.descriptor.data, vector.data as *mut c_void)
+                                 &input_scale as *const T::Scaling as *const c_void, vector.descriptor.data, vector.data as *const c_void,
+                                 &output_scale as *const T::Scaling as *const c_void, vector.descriptor.data, vector.data as *mut c_void)
     }
-    pub fn backward<T: CuDataType>(&self, cudnn: &Cudnn, alpha: T, beta: T,
+    pub fn backward<T: CudnnType>(&self, cudnn: &Cudnn, alpha: T::Scaling, beta: T::Scaling,
                                    output: &CuTensorDeref<T>,
                                    output_signal: &CuTensorDeref<T>,
-                                   input_signal: &mut CuTensorDeref<T>) {
+                                   input_signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
         cudnn_activation_backward(cudnn.handle, self.data,
-                                  (&alpha) as *const T as *const c_void,
+                                  (&alpha) as *const T::Scaling as *const c_void,
                                   output.descriptor.data, output.data as *const c_void,
                                   output_signal.descriptor.data, output_signal.data as *const c_void,
                                   output.descriptor.data, output.data as *const c_void,
                                   //input.descriptor.data, input.data as *mut c_void,
-                                  (&beta) as *const T as *const c_void,
+                                  (&beta) as *const T::Scaling as *const c_void,
                                   input_signal.descriptor.data, input_signal.data as *mut c_void)
     }
-    pub fn backward_inplace<T: CuDataType>(&self, cudnn: &Cudnn, alpha: T, beta: T,
+    pub fn backward_inplace<T: CudnnType>(&self, cudnn: &Cudnn, alpha: T::Scaling, beta: T::Scaling,
                                            output: &CuTensorDeref<T>,
-                                           signal: &mut CuTensorDeref<T>) {
+                                           signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
         cudnn_activation_backward(cudnn.handle, self.data,
-                                  (&alpha) as *const T as *const c_void,
+                                  (&alpha) as *const T::Scaling as *const c_void,
                                   output.descriptor.data, output.data as *const c_void,
                                   signal.descriptor.data, signal.data as *const c_void,
                                   output.descriptor.data, output.data as *const c_void,
                                   //input.descriptor.data, input.data as *mut c_void,
-                                  (&beta) as *const T as *const c_void,
+                                  (&beta) as *const T::Scaling as *const c_void,
                                   signal.descriptor.data, signal.data as *mut c_void)
     }
 
@@ -135,47 +117,56 @@ impl Debug for CuActivationDescriptorInfo {
 mod tests {
 
     use super::*;
+    use cumath::*;
 
     fn test_activation(name: &str, activation: CuActivationDescriptor) {
-        let cudnn = Cudnn::new();
+        let cudnn = Cudnn::new().unwrap();
 
         let input_data = [-0.75, -0.5, 0.0, 1.0, 0.6666, 0.12];
         let output_signal_data = [1.0; 6];
         let mut buffer = [0.0; 6];
 
 
-        let tensor_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 1]);
+        let tensor_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 1]).unwrap();
         let input = CuVector::<f32>::from_host_data(&input_data);
         let output_signal = CuVector::<f32>::from_host_data(&output_signal_data);
 
         let mut forward_output = CuVector::<f32>::zero(tensor_descriptor.data_len());
-        activation.forward(&cudnn, &tensor_descriptor.link(&input), 1.0, &mut tensor_descriptor.link_mut(&mut forward_output), 0.0);
+        activation.forward(&cudnn, &tensor_descriptor.link(&input).unwrap(), 1.0, &mut tensor_descriptor.link_mut(&mut forward_output).unwrap(), 0.0).unwrap();
         input.dev_assert_equals(&input_data);
 
         let mut forward_inplace_output = input.clone();
-        activation.forward_inplace(&cudnn, &mut tensor_descriptor.link_mut(&mut forward_inplace_output), 1.0, 0.0);
+        activation.forward_inplace(&cudnn, &mut tensor_descriptor.link_mut(&mut forward_inplace_output).unwrap(), 1.0, 0.0).unwrap();
         forward_inplace_output.clone_to_host(&mut buffer);
         forward_output.dev_assert_equals(&buffer);
 
         println!("{} forward : Output[{:?}]", name, forward_output);
 
+        let info = activation.get_info().unwrap();
+        let mut expected = [0.0; 6];
+        reference::activation_forward(info.mode, info.coef, 1.0, &input_data, 0.0, &mut expected);
+        forward_output.clone_to_host(&mut buffer);
+        for i in 0..6 {
+            assert!((buffer[i] - expected[i]).abs() < 1e-5, "{} forward : {:?} != {:?}", name, buffer, expected);
+        }
+
         let mut curand = CurandGenerator::new(CurandRngType::PseudoDefault).unwrap();
         let mut input = CuVector::<f32>::zero(tensor_descriptor.data_len());
         curand.generate_uniform_range(&mut input, -10000.0, 10000.0, &DEFAULT_STREAM);
 
         let mut backward_output = CuVector::<f32>::zero(tensor_descriptor.data_len());
         activation.backward(&cudnn, 1.0, 0.0,
-                            &tensor_descriptor.link(&forward_output),
-                            &tensor_descriptor.link(&output_signal),
-                            &mut tensor_descriptor.link_mut(&mut backward_output));
+                            &tensor_descriptor.link(&forward_output).unwrap(),
+                            &tensor_descriptor.link(&output_signal).unwrap(),
+                            &mut tensor_descriptor.link_mut(&mut backward_output).unwrap()).unwrap();
         output_signal.dev_assert_equals(&output_signal_data);
         forward_inplace_output.clone_to_host(&mut buffer);
         forward_output.dev_assert_equals(&buffer);
 
         let mut backward_inplace_output = output_signal.clone();
         activation.backward_inplace(&cudnn, 1.0, 0.0,
-                                    &tensor_descriptor.link(&forward_output),
-                                    &mut tensor_descriptor.link_mut(&mut backward_inplace_output));
+                                    &tensor_descriptor.link(&forward_output).unwrap(),
+                                    &mut tensor_descriptor.link_mut(&mut backward_inplace_output).unwrap()).unwrap();
         output_signal.dev_assert_equals(&output_signal_data);
         backward_inplace_output.clone_to_host(&mut buffer);
         backward_output.dev_assert_equals(&buffer);
@@ -185,27 +176,27 @@ mod tests {
 
     #[test]
     fn sigmoid_forward() {
-        test_activation("sigmoid", CuActivationDescriptor::sigmoid());
+        test_activation("sigmoid", CuActivationDescriptor::sigmoid().unwrap());
     }
 
     #[test]
     fn relu_forward() {
-        test_activation("relu", CuActivationDescriptor::relu());
+        test_activation("relu", CuActivationDescriptor::relu().unwrap());
     }
 
     #[test]
     fn tanh_forward() {
-        test_activation("tanh", CuActivationDescriptor::tanh());
+        test_activation("tanh", CuActivationDescriptor::tanh().unwrap());
     }
 
     #[test]
     fn clipped_relu_forward() {
-        test_activation("clippedRelu", CuActivationDescriptor::clipped_relu(0.5));
+        test_activation("clippedRelu", CuActivationDescriptor::clipped_relu(0.5).unwrap());
     }
 
     #[test]
     fn elu_forward() {
-        test_activation("elu", CuActivationDescriptor::elu(0.5));
+        test_activation("elu", CuActivationDescriptor::elu(0.5).unwrap());
     }
 
     /*#[test]
@@ -228,14 +219,14 @@ mod tests {
         println!("Finished in {}.{}", dt.as_secs(), dt.subsec_nanos());
 
 
-        let cudnn = Cudnn::new();
-        let activation = CuActivationDescriptor::sigmoid();
-        let tensor_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]);
+        let cudnn = Cudnn::new().unwrap();
+        let activation = CuActivationDescriptor::sigmoid().unwrap();
+        let tensor_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
         let mut vector = CuVector::<f32>::zero(tensor_descriptor.data_len());
         println!("Input = {:?}", vector);
         let t0 = Instant::now();
         for _ in 0..10000 {
-            activation.forward_inplace(&cudnn, &mut tensor_descriptor.link_mut(&mut vector), 1.0, 0.0);
+            activation.forward_inplace(&cudnn, &mut tensor_descriptor.link_mut(&mut vector).unwrap(), 1.0, 0.0).unwrap();
         }
         let dt = t0.elapsed();
         println!("Finished in {}.{}", dt.as_secs(), dt.subsec_nanos());
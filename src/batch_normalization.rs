@@ -0,0 +1,270 @@
+
+use super::*;
+use super::ffi::*;
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+
+
+
+/// Batch normalization over tensors shaped like the input descriptor it was derived from.
+/// Scale, bias, means and variances are all described by `param_descriptor()`, and are made of `T::Scaling`:
+/// floats for half tensors, and the type of the tensors otherwise.
+pub struct CuBatchNormalizationDescriptor<T: CudnnType> {
+    _phantom: PhantomData<T>,
+    mode: CudnnBatchNormMode,
+    param_desc: CuTensorDescriptor<T::Scaling>,
+}
+
+impl<T: CudnnType> CuBatchNormalizationDescriptor<T> {
+
+    pub fn new(input_desc: &CuTensorDescriptor<T>, mode: CudnnBatchNormMode) -> Result<CuBatchNormalizationDescriptor<T>, CudnnError> {
+        let mut param_desc = CuTensorDescriptor::create(input_desc.get_info()?.nb_dims)?;
+        cudnn_derive_bn_tensor_descriptor(param_desc.data, input_desc.data, mode)?;
+        param_desc.update_data_len()?;
+        Ok(CuBatchNormalizationDescriptor { _phantom: PhantomData, mode, param_desc })
+    }
+
+    pub fn mode(&self) -> CudnnBatchNormMode {
+        self.mode
+    }
+
+    /// Descriptor of the scale, bias, mean and variance tensors (1xCx1x1 in spatial mode, 1xCxHxW per activation)
+    pub fn param_descriptor(&self) -> &CuTensorDescriptor<T::Scaling> {
+        &self.param_desc
+    }
+
+    #[cfg(not(feature = "disable_checks"))]
+    fn check_params(&self, function: &'static str, params: &[(&str, &CuTensorDescriptor<T::Scaling>)]) -> Result<(), CudnnError> {
+        let dimensions = self.param_desc.get_info()?.dimensions;
+        for &(name, descriptor) in params {
+            let param_dimensions = descriptor.get_info()?.dimensions;
+            if param_dimensions != dimensions {
+                return Err(CudnnError::bad_param(function, format!("{} dimensions {:?} don't match {:?}", name, param_dimensions, dimensions)))
+            }
+        }
+        Ok(())
+    }
+
+    /// Normalizes `input` with the statistics of the batch, and updates the running statistics with
+    /// `running = (1 - exponential_average_factor) * running + exponential_average_factor * batch`.
+    /// The batch mean and inverse standard deviation are saved for `backward`.
+    pub fn forward_training(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling,
+                            input: &CuTensorDeref<T>, output: &mut CuTensorDeref<T>,
+                            scale: &CuTensorDeref<T::Scaling>, bias: &CuTensorDeref<T::Scaling>,
+                            exponential_average_factor: f64,
+                            running_mean: &mut CuTensorDeref<T::Scaling>, running_variance: &mut CuTensorDeref<T::Scaling>,
+                            epsilon: f64,
+                            saved_mean: &mut CuTensorDeref<T::Scaling>, saved_inv_variance: &mut CuTensorDeref<T::Scaling>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_params("CuBatchNormalizationDescriptor::forward_training", &[
+                ("scale", scale.descriptor()), ("bias", bias.descriptor()),
+                ("running_mean", running_mean.descriptor()), ("running_variance", running_variance.descriptor()),
+                ("saved_mean", saved_mean.descriptor()), ("saved_inv_variance", saved_inv_variance.descriptor()),
+            ])?;
+        }
+        cudnn_batch_normalization_forward_training(cudnn.handle, self.mode,
+                                                   &alpha as *const T::Scaling as *const c_void,
+                                                   &beta as *const T::Scaling as *const c_void,
+                                                   input.descriptor.data, input.data as *const c_void,
+                                                   output.descriptor.data, output.data as *mut c_void,
+                                                   self.param_desc.data,
+                                                   scale.data as *const c_void, bias.data as *const c_void,
+                                                   exponential_average_factor,
+                                                   running_mean.data as *mut c_void, running_variance.data as *mut c_void,
+                                                   epsilon,
+                                                   saved_mean.data as *mut c_void, saved_inv_variance.data as *mut c_void)
+    }
+
+    /// Normalizes `input` with previously estimated statistics.
+    pub fn forward_inference(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling,
+                             input: &CuTensorDeref<T>, output: &mut CuTensorDeref<T>,
+                             scale: &CuTensorDeref<T::Scaling>, bias: &CuTensorDeref<T::Scaling>,
+                             estimated_mean: &CuTensorDeref<T::Scaling>, estimated_variance: &CuTensorDeref<T::Scaling>,
+                             epsilon: f64) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_params("CuBatchNormalizationDescriptor::forward_inference", &[
+                ("scale", scale.descriptor()), ("bias", bias.descriptor()),
+                ("estimated_mean", estimated_mean.descriptor()), ("estimated_variance", estimated_variance.descriptor()),
+            ])?;
+        }
+        cudnn_batch_normalization_forward_inference(cudnn.handle, self.mode,
+                                                    &alpha as *const T::Scaling as *const c_void,
+                                                    &beta as *const T::Scaling as *const c_void,
+                                                    input.descriptor.data, input.data as *const c_void,
+                                                    output.descriptor.data, output.data as *mut c_void,
+                                                    self.param_desc.data,
+                                                    scale.data as *const c_void, bias.data as *const c_void,
+                                                    estimated_mean.data as *const c_void, estimated_variance.data as *const c_void,
+                                                    epsilon)
+    }
+
+    /// Computes the gradients of the input, of the scale and of the bias, from the statistics saved by `forward_training`.
+    /// `alpha_data`/`beta_data` blend `input_signal`, `alpha_param`/`beta_param` blend the parameter gradients.
+    pub fn backward(&self, cudnn: &mut Cudnn, alpha_data: T::Scaling, beta_data: T::Scaling, alpha_param: T::Scaling, beta_param: T::Scaling,
+                    input: &CuTensorDeref<T>, output_signal: &CuTensorDeref<T>, input_signal: &mut CuTensorDeref<T>,
+                    scale: &CuTensorDeref<T::Scaling>, scale_signal: &mut CuTensorDeref<T::Scaling>, bias_signal: &mut CuTensorDeref<T::Scaling>,
+                    epsilon: f64,
+                    saved_mean: &CuTensorDeref<T::Scaling>, saved_inv_variance: &CuTensorDeref<T::Scaling>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_params("CuBatchNormalizationDescriptor::backward", &[
+                ("scale", scale.descriptor()), ("scale_signal", scale_signal.descriptor()), ("bias_signal", bias_signal.descriptor()),
+                ("saved_mean", saved_mean.descriptor()), ("saved_inv_variance", saved_inv_variance.descriptor()),
+            ])?;
+        }
+        cudnn_batch_normalization_backward(cudnn.handle, self.mode,
+                                           &alpha_data as *const T::Scaling as *const c_void,
+                                           &beta_data as *const T::Scaling as *const c_void,
+                                           &alpha_param as *const T::Scaling as *const c_void,
+                                           &beta_param as *const T::Scaling as *const c_void,
+                                           input.descriptor.data, input.data as *const c_void,
+                                           output_signal.descriptor.data, output_signal.data as *const c_void,
+                                           input_signal.descriptor.data, input_signal.data as *mut c_void,
+                                           self.param_desc.data,
+                                           scale.data as *const c_void,
+                                           scale_signal.data as *mut c_void, bias_signal.data as *mut c_void,
+                                           epsilon,
+                                           saved_mean.data as *const c_void, saved_inv_variance.data as *const c_void)
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::*;
+    use test_utils::{host_data, assert_close};
+
+    #[test]
+    fn derived_descriptor() {
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 5, 2]).unwrap();
+        let spatial = CuBatchNormalizationDescriptor::new(&input_desc, CudnnBatchNormMode::Spatial).unwrap();
+        assert_eq!(spatial.param_descriptor().get_info().unwrap().dimensions, vec![1, 3, 1, 1]);
+        assert_eq!(spatial.param_descriptor().data_len(), 3);
+        let per_activation = CuBatchNormalizationDescriptor::new(&input_desc, CudnnBatchNormMode::PerActivation).unwrap();
+        assert_eq!(per_activation.param_descriptor().get_info().unwrap().dimensions, vec![1, 3, 5, 2]);
+        assert_eq!(per_activation.param_descriptor().data_len(), 30);
+
+        // Parameters of half tensors are floats, those of double tensors are doubles
+        let half_desc = CuTensorDescriptor::<Half>::fully_packed(&[4, 3, 5, 2]).unwrap();
+        let half = CuBatchNormalizationDescriptor::new(&half_desc, CudnnBatchNormMode::Spatial).unwrap();
+        assert_eq!(half.param_descriptor().get_info().unwrap().data_type, CudnnDataType::Float);
+        let double_desc = CuTensorDescriptor::<f64>::fully_packed(&[4, 3, 5, 2]).unwrap();
+        let double = CuBatchNormalizationDescriptor::new(&double_desc, CudnnBatchNormMode::Spatial).unwrap();
+        assert_eq!(double.param_descriptor().get_info().unwrap().data_type, CudnnDataType::Double);
+        assert_eq!(double.param_descriptor().data_len(), 3);
+    }
+
+    fn test_batch_normalization(mode: CudnnBatchNormMode) {
+        let mut cudnn = Cudnn::new().unwrap();
+        let dims = [4, 3, 2, 3];
+        let epsilon = 1e-4;
+
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&dims).unwrap();
+        let batch_normalization = CuBatchNormalizationDescriptor::new(&input_desc, mode).unwrap();
+        let param_desc = batch_normalization.param_descriptor();
+        let nb_params = param_desc.data_len();
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let output_signal_host = host_data(input_desc.data_len(), 2);
+        let scale_host = host_data(nb_params, 3).iter().map(|x| x + 1.5).collect::<Vec<_>>();
+        let bias_host = host_data(nb_params, 4);
+        let input = CuVector::<f32>::from_host_data(&input_host);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_host);
+        let scale = CuVector::<f32>::from_host_data(&scale_host);
+        let bias = CuVector::<f32>::from_host_data(&bias_host);
+        let mut output = CuVector::<f32>::zero(input_desc.data_len());
+        let mut running_mean = CuVector::<f32>::zero(nb_params);
+        let mut running_variance = CuVector::<f32>::new(1.0, nb_params);
+        let mut saved_mean = CuVector::<f32>::zero(nb_params);
+        let mut saved_inv_variance = CuVector::<f32>::zero(nb_params);
+
+        batch_normalization.forward_training(&mut cudnn, 1.0, 0.0,
+                                             &input_desc.link(&input).unwrap(), &mut input_desc.link_mut(&mut output).unwrap(),
+                                             &param_desc.link(&scale).unwrap(), &param_desc.link(&bias).unwrap(), 0.25,
+                                             &mut param_desc.link_mut(&mut running_mean).unwrap(),
+                                             &mut param_desc.link_mut(&mut running_variance).unwrap(), epsilon,
+                                             &mut param_desc.link_mut(&mut saved_mean).unwrap(),
+                                             &mut param_desc.link_mut(&mut saved_inv_variance).unwrap()).unwrap();
+        let mut expected_output = vec![0.0; input_desc.data_len()];
+        let mut expected_running_mean = vec![0.0; nb_params];
+        let mut expected_running_variance = vec![1.0; nb_params];
+        let mut expected_saved_mean = vec![0.0; nb_params];
+        let mut expected_saved_inv_variance = vec![0.0; nb_params];
+        reference::batch_normalization_forward_training(mode, 1.0, 0.0, &dims, &input_host, &mut expected_output,
+                                                        &scale_host, &bias_host, 0.25,
+                                                        &mut expected_running_mean, &mut expected_running_variance, epsilon,
+                                                        &mut expected_saved_mean, &mut expected_saved_inv_variance);
+        assert_close("forward_training", &output, &expected_output);
+        assert_close("running_mean", &running_mean, &expected_running_mean);
+        assert_close("running_variance", &running_variance, &expected_running_variance);
+        assert_close("saved_mean", &saved_mean, &expected_saved_mean);
+        assert_close("saved_inv_variance", &saved_inv_variance, &expected_saved_inv_variance);
+
+        batch_normalization.forward_inference(&mut cudnn, 1.0, 0.0,
+                                              &input_desc.link(&input).unwrap(), &mut input_desc.link_mut(&mut output).unwrap(),
+                                              &param_desc.link(&scale).unwrap(), &param_desc.link(&bias).unwrap(),
+                                              &param_desc.link(&running_mean).unwrap(),
+                                              &param_desc.link(&running_variance).unwrap(), epsilon).unwrap();
+        reference::batch_normalization_forward_inference(mode, 1.0, 0.0, &dims, &input_host, &mut expected_output,
+                                                         &scale_host, &bias_host,
+                                                         &expected_running_mean, &expected_running_variance, epsilon);
+        assert_close("forward_inference", &output, &expected_output);
+
+        let mut input_signal = CuVector::<f32>::zero(input_desc.data_len());
+        let mut scale_signal = CuVector::<f32>::new(1.0, nb_params);
+        let mut bias_signal = CuVector::<f32>::new(1.0, nb_params);
+        batch_normalization.backward(&mut cudnn, 1.0, 0.0, 1.0, 1.0,
+                                     &input_desc.link(&input).unwrap(),
+                                     &input_desc.link(&output_signal).unwrap(),
+                                     &mut input_desc.link_mut(&mut input_signal).unwrap(),
+                                     &param_desc.link(&scale).unwrap(),
+                                     &mut param_desc.link_mut(&mut scale_signal).unwrap(),
+                                     &mut param_desc.link_mut(&mut bias_signal).unwrap(), epsilon,
+                                     &param_desc.link(&saved_mean).unwrap(),
+                                     &param_desc.link(&saved_inv_variance).unwrap()).unwrap();
+        let mut expected_input_signal = vec![0.0; input_desc.data_len()];
+        let mut expected_scale_signal = vec![1.0; nb_params];
+        let mut expected_bias_signal = vec![1.0; nb_params];
+        reference::batch_normalization_backward(mode, 1.0, 0.0, 1.0, 1.0, &dims, &input_host, &output_signal_host,
+                                                &mut expected_input_signal, &scale_host,
+                                                &mut expected_scale_signal, &mut expected_bias_signal,
+                                                &expected_saved_mean, &expected_saved_inv_variance);
+        assert_close("backward input", &input_signal, &expected_input_signal);
+        assert_close("backward scale", &scale_signal, &expected_scale_signal);
+        assert_close("backward bias", &bias_signal, &expected_bias_signal);
+    }
+
+    #[test]
+    fn per_activation() {
+        test_batch_normalization(CudnnBatchNormMode::PerActivation);
+    }
+
+    #[test]
+    fn spatial() {
+        test_batch_normalization(CudnnBatchNormMode::Spatial);
+    }
+
+    #[test]
+    fn wrong_param_dimensions() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 2, 3]).unwrap();
+        let batch_normalization = CuBatchNormalizationDescriptor::new(&input_desc, CudnnBatchNormMode::Spatial).unwrap();
+        let param_desc = batch_normalization.param_descriptor();
+        let wrong_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 2, 3]).unwrap();
+
+        let input = CuVector::<f32>::zero(input_desc.data_len());
+        let mut output = CuVector::<f32>::zero(input_desc.data_len());
+        let params = CuVector::<f32>::new(1.0, 3);
+        let wrong = CuVector::<f32>::new(1.0, wrong_desc.data_len());
+
+        let error = batch_normalization.forward_inference(&mut cudnn, 1.0, 0.0,
+                                                          &input_desc.link(&input).unwrap(), &mut input_desc.link_mut(&mut output).unwrap(),
+                                                          &param_desc.link(&params).unwrap(), &wrong_desc.link(&wrong).unwrap(),
+                                                          &param_desc.link(&params).unwrap(), &param_desc.link(&params).unwrap(), 1e-4).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use super::ffi::*;
+use super::*;
+
+
+
+/// One entry of the ranked list returned by the convolution algorithm queries.
+/// `time` is in milliseconds, and is negative when the algorithm wasn't benchmarked.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CuConvolutionAlgoPerf<A> {
+    pub algo: A,
+    pub time: f32,
+    pub memory: usize,
+    pub determinism: CudnnDeterminism,
+    pub math_type: CudnnMathType,
+}
+
+/// Runs a cuDNN algorithm query and keeps the algorithms that can actually be used, in the order returned by cuDNN.
+pub(crate) fn query_algorithms<A, F>(placeholder: A, max_count: i32, query: F) -> Result<Vec<CuConvolutionAlgoPerf<A>>, CudnnError>
+    where A: Copy, F: FnOnce(i32, &mut i32, *mut _ConvolutionAlgoPerfStruct<A>) -> Result<(), CudnnError> {
+    let mut results = vec![_ConvolutionAlgoPerfStruct::new(placeholder); max_count as usize];
+    let mut returned_count = 0;
+    query(max_count, &mut returned_count, results.as_mut_ptr())?;
+    Ok(results.iter().take(returned_count as usize)
+        .filter(|x| x.status == CudnnStatus::Success)
+        .map(|x| CuConvolutionAlgoPerf { algo: x.algo, time: x.time, memory: x.memory, determinism: x.determinism, math_type: x.math_type })
+        .collect())
+}
+
+
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+struct CuConvolutionAlgoKey {
+    input: CuTensorDescriptorInfo,
+    kernel: CuFilterDescriptorInfo,
+    convolution: CuConvolutionDescriptorInfo,
+    output: CuTensorDescriptorInfo,
+}
+
+impl CuConvolutionAlgoKey {
+    fn new<T: CudnnType>(convolution: &CuConvolutionDescriptor<T>, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>,
+                         output_desc: &CuTensorDescriptor<T>) -> Result<CuConvolutionAlgoKey, CudnnError> {
+        let convolution = convolution.get_info()?;
+        Ok(CuConvolutionAlgoKey {
+            input: input_desc.get_info()?,
+            kernel: kernel_desc.get_info(convolution.array_length + 2)?,
+            convolution,
+            output: output_desc.get_info()?,
+        })
+    }
+}
+
+/// Remembers the result of the benchmarking ("find") algorithm queries, so that layers with the same configuration only run the search once.
+/// Results are keyed on the whole descriptors: shapes, data types, and the compute and math types of the convolution.
+/// Only the "find" queries are cached, the heuristic "get" queries being cheap enough to run every time.
+pub struct CuConvolutionAlgoCache {
+    forward: HashMap<CuConvolutionAlgoKey, Vec<CuConvolutionAlgoPerf<CudnnConvolutionFwdAlgo>>>,
+    backward_data: HashMap<CuConvolutionAlgoKey, Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdDataAlgo>>>,
+    backward_filter: HashMap<CuConvolutionAlgoKey, Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdFilterAlgo>>>,
+}
+
+impl CuConvolutionAlgoCache {
+
+    pub fn new() -> CuConvolutionAlgoCache {
+        CuConvolutionAlgoCache {
+            forward: HashMap::new(),
+            backward_data: HashMap::new(),
+            backward_filter: HashMap::new(),
+        }
+    }
+
+    /// Number of cached searches
+    pub fn len(&self) -> usize {
+        self.forward.len() + self.backward_data.len() + self.backward_filter.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.forward.clear();
+        self.backward_data.clear();
+        self.backward_filter.clear();
+    }
+
+    pub fn forward<T: CudnnType>(&mut self, cudnn: &Cudnn, convolution: &CuConvolutionDescriptor<T>, input_desc: &CuTensorDescriptor<T>,
+                                 kernel_desc: &CuFilterDescriptor<T>, output_desc: &CuTensorDescriptor<T>) -> Result<&[CuConvolutionAlgoPerf<CudnnConvolutionFwdAlgo>], CudnnError> {
+        let key = CuConvolutionAlgoKey::new(convolution, input_desc, kernel_desc, output_desc)?;
+        if !self.forward.contains_key(&key) {
+            let results = convolution.find_forward_algorithms(cudnn, input_desc, kernel_desc, output_desc)?;
+            self.forward.insert(key.clone(), results);
+        }
+        Ok(&self.forward[&key])
+    }
+
+    pub fn backward_data<T: CudnnType>(&mut self, cudnn: &Cudnn, convolution: &CuConvolutionDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>,
+                                       output_desc: &CuTensorDescriptor<T>, input_desc: &CuTensorDescriptor<T>) -> Result<&[CuConvolutionAlgoPerf<CudnnConvolutionBwdDataAlgo>], CudnnError> {
+        let key = CuConvolutionAlgoKey::new(convolution, input_desc, kernel_desc, output_desc)?;
+        if !self.backward_data.contains_key(&key) {
+            let results = convolution.find_backward_data_algorithms(cudnn, kernel_desc, output_desc, input_desc)?;
+            self.backward_data.insert(key.clone(), results);
+        }
+        Ok(&self.backward_data[&key])
+    }
+
+    pub fn backward_filter<T: CudnnType>(&mut self, cudnn: &Cudnn, convolution: &CuConvolutionDescriptor<T>, input_desc: &CuTensorDescriptor<T>,
+                                         output_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>) -> Result<&[CuConvolutionAlgoPerf<CudnnConvolutionBwdFilterAlgo>], CudnnError> {
+        let key = CuConvolutionAlgoKey::new(convolution, input_desc, kernel_desc, output_desc)?;
+        if !self.backward_filter.contains_key(&key) {
+            let results = convolution.find_backward_filter_algorithms(cudnn, input_desc, output_desc, kernel_desc)?;
+            self.backward_filter.insert(key.clone(), results);
+        }
+        Ok(&self.backward_filter[&key])
+    }
+
+}
+
+impl Default for CuConvolutionAlgoCache {
+    fn default() -> CuConvolutionAlgoCache {
+        CuConvolutionAlgoCache::new()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn cache() {
+        let cudnn = Cudnn::new().unwrap();
+        let mut cache = CuConvolutionAlgoCache::default();
+        assert!(cache.is_empty());
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+        let other_input_desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 2, 4, 5]).unwrap();
+        let other_output_desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 4, 5]).unwrap();
+
+        let first = cache.forward(&cudnn, &convolution, &input_desc, &kernel_desc, &output_desc).unwrap().to_vec();
+        assert!(!first.is_empty());
+        assert_eq!(cache.len(), 1);
+
+        // Same shapes with new descriptors must hit the cache
+        let input_desc = input_desc.clone();
+        let second = cache.forward(&cudnn, &convolution, &input_desc, &kernel_desc, &output_desc).unwrap().to_vec();
+        assert_eq!(first, second);
+        assert_eq!(cache.len(), 1);
+
+        cache.forward(&cudnn, &convolution, &other_input_desc, &kernel_desc, &other_output_desc).unwrap();
+        cache.backward_data(&cudnn, &convolution, &kernel_desc, &output_desc, &input_desc).unwrap();
+        cache.backward_filter(&cudnn, &convolution, &input_desc, &output_desc, &kernel_desc).unwrap();
+        assert_eq!(cache.len(), 4);
+
+        // Same shapes with another data type or math type must not
+        let f64_convolution = CuConvolutionDescriptor::<f64>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let f64_input_desc = CuTensorDescriptor::<f64>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let f64_kernel_desc = CuFilterDescriptor::<f64>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let f64_output_desc = CuTensorDescriptor::<f64>::fully_packed(&[2, 3, 4, 5]).unwrap();
+        cache.forward(&cudnn, &f64_convolution, &f64_input_desc, &f64_kernel_desc, &f64_output_desc).unwrap();
+        assert_eq!(cache.len(), 5);
+        let tensor_op_convolution = CuConvolutionDescriptorBuilder::new(2).paddings(&[1, 1]).math_type(CudnnMathType::TensorOp).build::<f32>().unwrap();
+        cache.forward(&cudnn, &tensor_op_convolution, &input_desc, &kernel_desc, &output_desc).unwrap();
+        assert_eq!(cache.len(), 6);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+}
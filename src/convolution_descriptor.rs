@@ -8,75 +8,241 @@ use super::*;
 
 
 
-pub struct CuConvolutionDescriptor<T: CuDataType> {
+pub struct CuConvolutionDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
     data: *mut _ConvolutionDescriptorStruct,
     array_len: i32,
 }
 
-impl<T: CuDataType> Drop for CuConvolutionDescriptor<T> {
+impl<T: CudnnType> Drop for CuConvolutionDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_convolution_descriptor(self.data)
+        let _ = cudnn_destroy_convolution_descriptor(self.data);
     }
 }
 
-impl<T: CuDataType> CuConvolutionDescriptor<T> {
+impl<T: CudnnType> CuConvolutionDescriptor<T> {
 
-    pub fn get_forward_workspace_size(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<f32>, kernel_desc: &CuFilterDescriptor<f32>,
-                                      output_desc: &CuTensorDescriptor<f32>, algo: CudnnConvolutionFwdAlgo) -> usize {
+    fn create(array_len: i32) -> Result<CuConvolutionDescriptor<T>, CudnnError> {
+        let mut data = ptr::null_mut();
+        cudnn_create_convolution_descriptor(&mut data)?;
+        Ok(CuConvolutionDescriptor { _phantom: PhantomData, data, array_len })
+    }
+
+    /// Dimensions of the output of a forward convolution, as computed by cuDNN.
+    pub fn get_forward_output_dims(&self, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>) -> Result<Vec<i32>, CudnnError> {
+        let nb_dims = self.array_len + 2;
+        let mut output = vec![-1; nb_dims as usize];
+        cudnn_get_convolution_nd_forward_output_dim(self.data, input_desc.data, kernel_desc.data, nb_dims, output.as_mut_ptr())?;
+        Ok(output)
+    }
+
+    /// Fully packed descriptor of the output of a forward convolution.
+    pub fn get_forward_output_descriptor(&self, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>) -> Result<CuTensorDescriptor<T>, CudnnError> {
+        CuTensorDescriptor::<T>::fully_packed(&self.get_forward_output_dims(input_desc, kernel_desc)?)
+    }
+
+    pub fn get_forward_workspace_size(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>,
+                                      output_desc: &CuTensorDescriptor<T>, algo: CudnnConvolutionFwdAlgo) -> Result<usize, CudnnError> {
         let mut output = 0;
         cudnn_get_convolution_forward_workspace_size(cudnn.handle, input_desc.data, kernel_desc.data,
-                                                     self.data, output_desc.data, algo, &mut output);
-        output
+                                                     self.data, output_desc.data, algo, &mut output)?;
+        Ok(output)
     }
 
-    pub fn forward(&self, cudnn: &mut Cudnn, alpha: f32, beta: f32, input: &CuTensorDeref<f32>, kernel_desc: &CuFilterDescriptor<f32>, kernel_data: &CuVectorDeref<f32>,
-                   workspace: &mut CuVectorDeref<f32>, output: &mut CuTensorDeref<f32>, algo: CudnnConvolutionFwdAlgo) {
+    pub fn forward(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling, input: &CuTensorDeref<T>, kernel_desc: &CuFilterDescriptor<T>, kernel_data: &CuVectorDeref<T>,
+                   workspace: &mut CuVectorDeref<f32>, output: &mut CuTensorDeref<T>, algo: CudnnConvolutionFwdAlgo) -> Result<(), CudnnError> where T: CuDataType {
         cudnn_convolution_forward(cudnn.handle,
-                                  &alpha as *const f32 as *const c_void,
+                                  &alpha as *const T::Scaling as *const c_void,
                                   input.descriptor.data, input.data as *const c_void,
                                   kernel_desc.data, kernel_data.as_ptr() as *const c_void,
                                   self.data, algo,
                                   workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
-                                  &beta as *const f32 as *const c_void,
-                                  output.descriptor.data, output.data as *mut c_void);
+                                  &beta as *const T::Scaling as *const c_void,
+                                  output.descriptor.data, output.data as *mut c_void)
+    }
+
+    /// Computes `activation(alpha1 * convolution(input) + alpha2 * z + bias)` in a single call.
+    /// `z` must have the dimensions of `output`. Without a residual, pass a zero-filled tensor that can be reused from call to call:
+    /// giving `output` itself with `alpha2 = 0` would let its NaNs through.
+    /// cuDNN only supports Relu here, plus Identity with the ImplicitPrecompGemm algorithm.
+    pub fn bias_activation_forward(&self, cudnn: &mut Cudnn, alpha1: T::Scaling, input: &CuTensorDeref<T>, kernel: &CuFilterDeref<T>,
+                                   workspace: &mut CuVectorDeref<f32>, algo: CudnnConvolutionFwdAlgo, alpha2: T::Scaling, z: &CuTensorDeref<T>,
+                                   bias: &CuTensorDeref<T>, activation: &CuActivationDescriptor, output: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            let output_dims = output.descriptor.get_info()?.dimensions;
+            check_bias_dimensions("CuConvolutionDescriptor::bias_activation_forward",
+                                  &output_dims, &bias.descriptor.get_info()?.dimensions)?;
+            check_same_dims("CuConvolutionDescriptor::bias_activation_forward", "z", &z.descriptor.get_info()?.dimensions, &output_dims)?;
+        }
+        cudnn_convolution_bias_activation_forward(cudnn.handle,
+                                                  &alpha1 as *const T::Scaling as *const c_void,
+                                                  input.descriptor.data, input.data as *const c_void,
+                                                  kernel.descriptor.data, kernel.data as *const c_void,
+                                                  self.data, algo,
+                                                  workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                                  &alpha2 as *const T::Scaling as *const c_void,
+                                                  z.descriptor.data, z.data as *const c_void,
+                                                  bias.descriptor.data, bias.data as *const c_void,
+                                                  activation.data,
+                                                  output.descriptor.data, output.data as *mut c_void)
+    }
+
+    pub fn get_backward_data_workspace_size(&self, cudnn: &Cudnn, kernel_desc: &CuFilterDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                            input_desc: &CuTensorDescriptor<T>, algo: CudnnConvolutionBwdDataAlgo) -> Result<usize, CudnnError> {
+        let mut output = 0;
+        cudnn_get_convolution_backward_data_workspace_size(cudnn.handle, kernel_desc.data, output_desc.data,
+                                                           self.data, input_desc.data, algo, &mut output)?;
+        Ok(output)
+    }
+
+    /// Computes the gradient of the input of the convolution (`input_signal`) from the gradient of its output (`output_signal`).
+    pub fn backward_data(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling, output_signal: &CuTensorDeref<T>, kernel: &CuFilterDeref<T>,
+                         workspace: &mut CuVectorDeref<f32>, input_signal: &mut CuTensorDeref<T>, algo: CudnnConvolutionBwdDataAlgo) -> Result<(), CudnnError> {
+        cudnn_convolution_backward_data(cudnn.handle,
+                                        &alpha as *const T::Scaling as *const c_void,
+                                        kernel.descriptor.data, kernel.data as *const c_void,
+                                        output_signal.descriptor.data, output_signal.data as *const c_void,
+                                        self.data, algo,
+                                        workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                        &beta as *const T::Scaling as *const c_void,
+                                        input_signal.descriptor.data, input_signal.data as *mut c_void)
+    }
+
+    pub fn get_backward_filter_workspace_size(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                              kernel_desc: &CuFilterDescriptor<T>, algo: CudnnConvolutionBwdFilterAlgo) -> Result<usize, CudnnError> {
+        let mut output = 0;
+        cudnn_get_convolution_backward_filter_workspace_size(cudnn.handle, input_desc.data, output_desc.data,
+                                                             self.data, kernel_desc.data, algo, &mut output)?;
+        Ok(output)
+    }
+
+    /// Computes the gradient of the kernel of the convolution (`kernel_signal`) from its input and the gradient of its output.
+    /// kernel_signal = alpha * gradient + beta * kernel_signal, so beta = 1.0 sums the gradients of several batches.
+    pub fn backward_filter(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling, input: &CuTensorDeref<T>, output_signal: &CuTensorDeref<T>,
+                           workspace: &mut CuVectorDeref<f32>, kernel_signal: &mut CuFilterDeref<T>, algo: CudnnConvolutionBwdFilterAlgo) -> Result<(), CudnnError> {
+        cudnn_convolution_backward_filter(cudnn.handle,
+                                          &alpha as *const T::Scaling as *const c_void,
+                                          input.descriptor.data, input.data as *const c_void,
+                                          output_signal.descriptor.data, output_signal.data as *const c_void,
+                                          self.data, algo,
+                                          workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                          &beta as *const T::Scaling as *const c_void,
+                                          kernel_signal.descriptor.data, kernel_signal.data as *mut c_void)
+    }
+
+    /// Computes the gradient of the bias (1xCx1x1) added to the output of the convolution, by summing `output_signal` over N, H and W.
+    pub fn backward_bias(&self, cudnn: &mut Cudnn, alpha: T::Scaling, beta: T::Scaling, output_signal: &CuTensorDeref<T>,
+                         bias_signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_bias_dimensions("CuConvolutionDescriptor::backward_bias",
+                                  &output_signal.descriptor.get_info()?.dimensions, &bias_signal.descriptor.get_info()?.dimensions)?;
+        }
+        cudnn_convolution_backward_bias(cudnn.handle,
+                                        &alpha as *const T::Scaling as *const c_void,
+                                        output_signal.descriptor.data, output_signal.data as *const c_void,
+                                        &beta as *const T::Scaling as *const c_void,
+                                        bias_signal.descriptor.data, bias_signal.data as *mut c_void)
+    }
+
+    /// Forward algorithms ranked by the cuDNN heuristics, without running them.
+    pub fn get_forward_algorithms(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>,
+                                  output_desc: &CuTensorDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionFwdAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_forward_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionFwdAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_get_convolution_forward_algorithm_v7(cudnn.handle, input_desc.data, kernel_desc.data,
+                                                       self.data, output_desc.data, requested, returned, results)
+        })
+    }
+
+    /// Forward algorithms ranked by execution time, benchmarked on the given shapes.
+    pub fn find_forward_algorithms(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, kernel_desc: &CuFilterDescriptor<T>,
+                                   output_desc: &CuTensorDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionFwdAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_forward_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionFwdAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_find_convolution_forward_algorithm(cudnn.handle, input_desc.data, kernel_desc.data,
+                                                     self.data, output_desc.data, requested, returned, results)
+        })
+    }
+
+    /// Backward data algorithms ranked by the cuDNN heuristics, without running them.
+    pub fn get_backward_data_algorithms(&self, cudnn: &Cudnn, kernel_desc: &CuFilterDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                        input_desc: &CuTensorDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdDataAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_backward_data_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionBwdDataAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_get_convolution_backward_data_algorithm_v7(cudnn.handle, kernel_desc.data, output_desc.data,
+                                                             self.data, input_desc.data, requested, returned, results)
+        })
+    }
+
+    /// Backward data algorithms ranked by execution time, benchmarked on the given shapes.
+    pub fn find_backward_data_algorithms(&self, cudnn: &Cudnn, kernel_desc: &CuFilterDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                         input_desc: &CuTensorDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdDataAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_backward_data_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionBwdDataAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_find_convolution_backward_data_algorithm(cudnn.handle, kernel_desc.data, output_desc.data,
+                                                           self.data, input_desc.data, requested, returned, results)
+        })
+    }
+
+    /// Backward filter algorithms ranked by the cuDNN heuristics, without running them.
+    pub fn get_backward_filter_algorithms(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                          kernel_desc: &CuFilterDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdFilterAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_backward_filter_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionBwdFilterAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_get_convolution_backward_filter_algorithm_v7(cudnn.handle, input_desc.data, output_desc.data,
+                                                               self.data, kernel_desc.data, requested, returned, results)
+        })
     }
 
-    #[allow(unused_variables)]
-    pub fn backward_data(&self, cudnn: &mut Cudnn,
-                         alpha: f32, beta: f32, output: &CuTensorDeref<f32>,
-                         kernel_desc: &CuFilterDescriptor<f32>, kernel_data: &mut CuVectorDeref<f32>,
-                         workspace: &mut CuVectorDeref<f32>, input: &mut CuTensorDeref<f32>, algo: CudnnConvolutionBwdDataAlgo) {
-        unimplemented!()
+    /// Backward filter algorithms ranked by execution time, benchmarked on the given shapes.
+    pub fn find_backward_filter_algorithms(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, output_desc: &CuTensorDescriptor<T>,
+                                           kernel_desc: &CuFilterDescriptor<T>) -> Result<Vec<CuConvolutionAlgoPerf<CudnnConvolutionBwdFilterAlgo>>, CudnnError> {
+        let mut max_count = 0;
+        cudnn_get_convolution_backward_filter_algorithm_max_count(cudnn.handle, &mut max_count)?;
+        query_algorithms(CudnnConvolutionBwdFilterAlgo::Count, max_count, |requested, returned, results| {
+            cudnn_find_convolution_backward_filter_algorithm(cudnn.handle, input_desc.data, output_desc.data,
+                                                             self.data, kernel_desc.data, requested, returned, results)
+        })
     }
 
 }
 
-impl CuConvolutionDescriptor<f32> {
+#[cfg_attr(feature = "disable_checks", allow(dead_code))]
+fn check_bias_dimensions(function: &'static str, output_dims: &[i32], bias_dims: &[i32]) -> Result<(), CudnnError> {
+    let valid = output_dims.len() == bias_dims.len() && output_dims.len() >= 2
+        && bias_dims[1] == output_dims[1]
+        && bias_dims.iter().enumerate().all(|(i, &x)| i == 1 || x == 1);
+    if valid {
+        Ok(())
+    } else {
+        Err(CudnnError::bad_param(function, format!("bias dimensions {:?} don't match output dimensions {:?}, expected [1, {}, 1, ...]",
+                                                    bias_dims, output_dims, output_dims.get(1).cloned().unwrap_or(-1))))
+    }
+}
+
+impl<T: CudnnType> CuConvolutionDescriptor<T> {
 
-    pub fn new(paddings: &[i32], filters_stride: &[i32], dilatations: &[i32], mode: CudnnConvolutionMode/*, group_count: i32, math_type: CudnnMathType*/) -> CuConvolutionDescriptor<f32> {
-        let len = paddings.len();
-        assert_eq!(len, filters_stride.len());
-        assert_eq!(len, dilatations.len());
-        let mut data = ptr::null_mut();
-        cudnn_create_convolution_descriptor(&mut data);
-        cudnn_set_convolution_nd_descriptor(data, len as i32, paddings.as_ptr(),
-                                            filters_stride.as_ptr(), dilatations.as_ptr(), mode, CudnnDataType::Float);
-        //cudnn_set_convolution_group_count(data, group_count);
-        //cudnn_set_convolution_math_type(data, math_type);
-        CuConvolutionDescriptor { _phantom: PhantomData, data, array_len: len as i32 }
+    pub fn new(paddings: &[i32], filters_stride: &[i32], dilatations: &[i32], mode: CudnnConvolutionMode) -> Result<CuConvolutionDescriptor<T>, CudnnError> {
+        CuConvolutionDescriptorBuilder::new(paddings.len())
+            .paddings(paddings)
+            .filter_strides(filters_stride)
+            .dilatations(dilatations)
+            .mode(mode)
+            .build()
     }
 
-    pub fn new_2d(pad_h: i32, pad_w: i32, u: i32, v: i32, dilatation_h: i32, dilatation_w: i32, mode: CudnnConvolutionMode) -> CuConvolutionDescriptor<f32> {
-        let mut data = ptr::null_mut();
-        cudnn_create_convolution_descriptor(&mut data);
-        cudnn_set_convolution2d_descriptor(data, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, CudnnDataType::Float);
-        //cudnn_set_convolution_group_count(data, group_count); TODO
-        //cudnn_set_convolution_math_type(data, math_type);
-        CuConvolutionDescriptor { _phantom: PhantomData, data, array_len: 2 }
+    pub fn new_2d(pad_h: i32, pad_w: i32, u: i32, v: i32, dilatation_h: i32, dilatation_w: i32, mode: CudnnConvolutionMode) -> Result<CuConvolutionDescriptor<T>, CudnnError> {
+        let output = Self::create(2)?;
+        cudnn_set_convolution2d_descriptor(output.data, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, T::compute_type())?;
+        Ok(output)
     }
 
-    pub fn get_info(&self) -> CuConvolutionDescriptorInfo {
+    pub fn get_info(&self) -> Result<CuConvolutionDescriptorInfo, CudnnError> {
         let len = self.array_len as usize;
         let mut array_length = -1;
         let mut pads = vec![-1; len];
@@ -84,21 +250,108 @@ impl CuConvolutionDescriptor<f32> {
         let mut dilatations = vec![-1; len];
         let mut mode = CudnnConvolutionMode::Convolution;
         let mut data_type = CudnnDataType::Int8x4;
-        cudnn_get_convolution_nd_descriptor(self.data, self.array_len, &mut array_length, pads.as_mut_ptr(), filter_strides.as_mut_ptr(), dilatations.as_mut_ptr(), &mut mode, &mut data_type);
-        CuConvolutionDescriptorInfo {
+        let mut group_count = -1;
+        let mut math_type = CudnnMathType::Default;
+        cudnn_get_convolution_nd_descriptor(self.data, self.array_len, &mut array_length, pads.as_mut_ptr(), filter_strides.as_mut_ptr(), dilatations.as_mut_ptr(), &mut mode, &mut data_type)?;
+        cudnn_get_convolution_group_count(self.data, &mut group_count)?;
+        cudnn_get_convolution_math_type(self.data, &mut math_type)?;
+        Ok(CuConvolutionDescriptorInfo {
             array_length,
             pads,
             filter_strides,
             dilatations,
             mode,
             data_type,
+            group_count,
+            math_type,
+        })
+    }
+
+}
+
+
+/// Builds a `CuConvolutionDescriptor` with `nb_spatial_dims` spatial dimensions.
+/// Defaults to no padding, unit strides and dilatations, cross-correlation, a single group and default math.
+pub struct CuConvolutionDescriptorBuilder {
+    paddings: Vec<i32>,
+    filter_strides: Vec<i32>,
+    dilatations: Vec<i32>,
+    mode: CudnnConvolutionMode,
+    group_count: i32,
+    math_type: CudnnMathType,
+}
+
+impl CuConvolutionDescriptorBuilder {
+
+    pub fn new(nb_spatial_dims: usize) -> CuConvolutionDescriptorBuilder {
+        CuConvolutionDescriptorBuilder {
+            paddings: vec![0; nb_spatial_dims],
+            filter_strides: vec![1; nb_spatial_dims],
+            dilatations: vec![1; nb_spatial_dims],
+            mode: CudnnConvolutionMode::CrossCorrelation,
+            group_count: 1,
+            math_type: CudnnMathType::Default,
+        }
+    }
+
+    pub fn paddings(mut self, paddings: &[i32]) -> CuConvolutionDescriptorBuilder {
+        self.paddings = paddings.to_vec();
+        self
+    }
+
+    pub fn filter_strides(mut self, filter_strides: &[i32]) -> CuConvolutionDescriptorBuilder {
+        self.filter_strides = filter_strides.to_vec();
+        self
+    }
+
+    pub fn dilatations(mut self, dilatations: &[i32]) -> CuConvolutionDescriptorBuilder {
+        self.dilatations = dilatations.to_vec();
+        self
+    }
+
+    pub fn mode(mut self, mode: CudnnConvolutionMode) -> CuConvolutionDescriptorBuilder {
+        self.mode = mode;
+        self
+    }
+
+    /// Number of groups the input and output channels are split into.
+    /// Set it to the number of input channels for a depthwise convolution.
+    pub fn group_count(mut self, group_count: i32) -> CuConvolutionDescriptorBuilder {
+        self.group_count = group_count;
+        self
+    }
+
+    pub fn math_type(mut self, math_type: CudnnMathType) -> CuConvolutionDescriptorBuilder {
+        self.math_type = math_type;
+        self
+    }
+
+    /// Builds a descriptor computing in the compute type of `T`
+    pub fn build<T: CudnnType>(&self) -> Result<CuConvolutionDescriptor<T>, CudnnError> {
+        let len = self.paddings.len();
+        #[cfg(not(feature = "disable_checks"))] {
+            if len != self.filter_strides.len() {
+                return Err(CudnnError::bad_param("CuConvolutionDescriptorBuilder::build", "paddings.len() != filter_strides.len()"))
+            }
+            if len != self.dilatations.len() {
+                return Err(CudnnError::bad_param("CuConvolutionDescriptorBuilder::build", "paddings.len() != dilatations.len()"))
+            }
+            if self.group_count < 1 {
+                return Err(CudnnError::bad_param("CuConvolutionDescriptorBuilder::build", format!("group_count must be positive, got {}", self.group_count)))
+            }
         }
+        let output = CuConvolutionDescriptor::create(len as i32)?;
+        cudnn_set_convolution_nd_descriptor(output.data, len as i32, self.paddings.as_ptr(),
+                                            self.filter_strides.as_ptr(), self.dilatations.as_ptr(), self.mode, T::compute_type())?;
+        cudnn_set_convolution_group_count(output.data, self.group_count)?;
+        cudnn_set_convolution_math_type(output.data, self.math_type)?;
+        Ok(output)
     }
 
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct CuConvolutionDescriptorInfo {
     pub array_length: i32,
     pub pads: Vec<i32>,
@@ -106,6 +359,39 @@ pub struct CuConvolutionDescriptorInfo {
     pub dilatations: Vec<i32>,
     pub mode: CudnnConvolutionMode,
     pub data_type: CudnnDataType,
+    pub group_count: i32,
+    pub math_type: CudnnMathType,
+}
+
+impl CuConvolutionDescriptorInfo {
+
+    /// Dimensions of the output of a forward convolution, computed on the host.
+    /// Each spatial dimension is `1 + (input + 2 * pad - ((filter - 1) * dilatation + 1)) / stride`.
+    pub fn forward_output_dims(&self, input_dims: &[i32], kernel_dims: &[i32]) -> Result<Vec<i32>, CudnnError> {
+        let function = "CuConvolutionDescriptorInfo::forward_output_dims";
+        let nb_dims = self.array_length as usize + 2;
+        if input_dims.len() != nb_dims || kernel_dims.len() != nb_dims {
+            return Err(CudnnError::bad_param(function, format!("expected {} input and kernel dimensions, got {:?} and {:?}", nb_dims, input_dims, kernel_dims)))
+        }
+        if input_dims[1] != kernel_dims[1] * self.group_count {
+            return Err(CudnnError::bad_param(function, format!("input has {} channels but kernel expects {} in each of the {} groups",
+                                                               input_dims[1], kernel_dims[1], self.group_count)))
+        }
+        if kernel_dims[0] % self.group_count != 0 {
+            return Err(CudnnError::bad_param(function, format!("{} output channels can't be split into {} groups", kernel_dims[0], self.group_count)))
+        }
+        let mut output = vec![input_dims[0], kernel_dims[0]];
+        for i in 0..self.array_length as usize {
+            let extent = (kernel_dims[i+2] - 1) * self.dilatations[i] + 1;
+            let padded = input_dims[i+2] + 2 * self.pads[i];
+            if padded < extent {
+                return Err(CudnnError::bad_param(function, format!("kernel extent {} exceeds padded input size {} in spatial dimension {}", extent, padded, i)))
+            }
+            output.push(1 + (padded - extent) / self.filter_strides[i]);
+        }
+        Ok(output)
+    }
+
 }
 
 
@@ -115,6 +401,7 @@ pub struct CuConvolutionDescriptorInfo {
 mod tests {
 
     use super::*;
+    use test_utils::{host_data, assert_close};
 
     #[test]
     fn compare_2d_nd() {
@@ -123,42 +410,177 @@ mod tests {
             1, 1,
             1, 1,
             CudnnConvolutionMode::CrossCorrelation
-        );
-        let convolution_nd = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation);
-        assert!(convolution_2d.get_info().eq(&convolution_nd.get_info()))
+        ).unwrap();
+        let convolution_nd = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        assert!(convolution_2d.get_info().unwrap().eq(&convolution_nd.get_info().unwrap()))
+    }
+
+    #[test]
+    fn forward_output_dims() {
+        let info = CuConvolutionDescriptorInfo {
+            array_length: 2,
+            pads: vec![1, 0],
+            filter_strides: vec![2, 1],
+            dilatations: vec![1, 2],
+            mode: CudnnConvolutionMode::CrossCorrelation,
+            data_type: CudnnDataType::Float,
+            group_count: 1,
+            math_type: CudnnMathType::Default,
+        };
+        assert_eq!(info.forward_output_dims(&[2, 3, 7, 9], &[4, 3, 3, 3]).unwrap(), vec![2, 4, 4, 5]);
+        assert_eq!(info.forward_output_dims(&[2, 3, 1, 5], &[4, 3, 3, 3]).unwrap(), vec![2, 4, 1, 1]);
+        assert!(info.forward_output_dims(&[2, 3, 1, 4], &[4, 3, 3, 3]).is_err());
+        assert!(info.forward_output_dims(&[2, 2, 7, 9], &[4, 3, 3, 3]).is_err());
+        assert!(info.forward_output_dims(&[2, 3, 7, 9, 1], &[4, 3, 3, 3]).is_err());
+
+        let depthwise = CuConvolutionDescriptorInfo { group_count: 3, ..info.clone() };
+        assert_eq!(depthwise.forward_output_dims(&[2, 3, 7, 9], &[6, 1, 3, 3]).unwrap(), vec![2, 6, 4, 5]);
+        assert!(depthwise.forward_output_dims(&[2, 3, 7, 9], &[4, 1, 3, 3]).is_err());
+        assert!(depthwise.forward_output_dims(&[2, 3, 7, 9], &[6, 3, 3, 3]).is_err());
+    }
+
+    #[test]
+    fn builder() {
+        let convolution = CuConvolutionDescriptorBuilder::new(2)
+            .paddings(&[1, 2])
+            .filter_strides(&[2, 1])
+            .mode(CudnnConvolutionMode::Convolution)
+            .group_count(4)
+            .math_type(CudnnMathType::TensorOp)
+            .build::<f32>().unwrap();
+        let info = convolution.get_info().unwrap();
+        assert_eq!(info.pads, vec![1, 2]);
+        assert_eq!(info.filter_strides, vec![2, 1]);
+        assert_eq!(info.dilatations, vec![1, 1]);
+        assert_eq!(info.mode, CudnnConvolutionMode::Convolution);
+        assert_eq!(info.group_count, 4);
+        assert_eq!(info.math_type, CudnnMathType::TensorOp);
+
+        let default = CuConvolutionDescriptor::<f32>::new(&[0, 0], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        assert_eq!(CuConvolutionDescriptorBuilder::new(2).build::<f32>().unwrap().get_info().unwrap(), default.get_info().unwrap());
+        assert_eq!(default.get_info().unwrap().group_count, 1);
+
+        assert!(CuConvolutionDescriptorBuilder::new(2).dilatations(&[1]).build::<f32>().is_err());
+        assert!(CuConvolutionDescriptorBuilder::new(2).group_count(0).build::<f32>().is_err());
+    }
+
+    #[test]
+    fn compute_types() {
+        let convolution = CuConvolutionDescriptor::<f64>::new(&[0, 0], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        assert_eq!(convolution.get_info().unwrap().data_type, CudnnDataType::Double);
+        let convolution = CuConvolutionDescriptor::<Half>::new_2d(0, 0, 1, 1, 1, 1, CudnnConvolutionMode::CrossCorrelation).unwrap();
+        assert_eq!(convolution.get_info().unwrap().data_type, CudnnDataType::Float);
+        let convolution = CuConvolutionDescriptorBuilder::new(2).build::<i8>().unwrap();
+        assert_eq!(convolution.get_info().unwrap().data_type, CudnnDataType::Int32);
+    }
+
+    #[test]
+    fn depthwise_forward() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptorBuilder::new(2).paddings(&[1, 1]).group_count(4).build::<f32>().unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 4, 5, 6]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[8, 1, 3, 3]).unwrap();
+        let output_desc = convolution.get_forward_output_descriptor(&input_desc, &kernel_desc).unwrap();
+        assert_eq!(output_desc.get_info().unwrap().dimensions, vec![2, 8, 5, 6]);
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let kernel_host = host_data(kernel_desc.data_len(), 2);
+        let input_data = CuVector::<f32>::from_host_data(&input_host);
+        let kernel_data = CuVector::<f32>::from_host_data(&kernel_host);
+        let mut output_data = CuVector::<f32>::zero(output_desc.data_len());
+
+        let algo = CudnnConvolutionFwdAlgo::ImplicitGemm;
+        let workspace_size = convolution.get_forward_workspace_size(&cudnn, &input_desc, &kernel_desc, &output_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
+
+        convolution.forward(&mut cudnn, 1.0, 0.0,
+                            &input_desc.link(&input_data).unwrap(),
+                            &kernel_desc, &kernel_data,
+                            &mut workspace,
+                            &mut output_desc.link_mut(&mut output_data).unwrap(), algo).unwrap();
+
+        let mut expected = vec![0.0; output_desc.data_len()];
+        reference::convolution_forward(&convolution.get_info().unwrap(), 1.0,
+                                       &[2, 4, 5, 6], &input_host,
+                                       &[8, 1, 3, 3], &kernel_host,
+                                       0.0, &[2, 8, 5, 6], &mut expected);
+        assert_close("depthwise_forward", &output_data, &expected);
+    }
+
+    #[test]
+    fn forward_output_dims_match_cudnn() {
+        let configs: &[(&[i32], &[i32], &[i32], &[i32], &[i32])] = &[
+            (&[1, 1], &[1, 1], &[1, 1], &[1, 3, 4, 2], &[3, 3, 3, 3]),
+            (&[0, 2], &[2, 3], &[1, 2], &[2, 4, 11, 13], &[5, 4, 3, 2]),
+            (&[1, 1, 1], &[2, 2, 1], &[1, 1, 3], &[1, 2, 6, 7, 9], &[3, 2, 3, 3, 2]),
+        ];
+        for &(pads, strides, dilatations, input_dims, kernel_dims) in configs {
+            let convolution = CuConvolutionDescriptor::<f32>::new(pads, strides, dilatations, CudnnConvolutionMode::CrossCorrelation).unwrap();
+            let input_desc = CuTensorDescriptor::<f32>::fully_packed(input_dims).unwrap();
+            let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, kernel_dims).unwrap();
+            let expected = convolution.get_info().unwrap().forward_output_dims(input_dims, kernel_dims).unwrap();
+            assert_eq!(convolution.get_forward_output_dims(&input_desc, &kernel_desc).unwrap(), expected);
+            let output_desc = convolution.get_forward_output_descriptor(&input_desc, &kernel_desc).unwrap();
+            assert_eq!(output_desc.get_info().unwrap().dimensions, expected);
+        }
+    }
+
+    #[test]
+    fn algorithms() {
+        let cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+
+        let forward = convolution.get_forward_algorithms(&cudnn, &input_desc, &kernel_desc, &output_desc).unwrap();
+        assert!(!forward.is_empty());
+        let forward = convolution.find_forward_algorithms(&cudnn, &input_desc, &kernel_desc, &output_desc).unwrap();
+        assert!(!forward.is_empty());
+        assert!(forward.windows(2).all(|x| x[0].time <= x[1].time), "{:?}", forward);
+
+        assert!(!convolution.get_backward_data_algorithms(&cudnn, &kernel_desc, &output_desc, &input_desc).unwrap().is_empty());
+        assert!(!convolution.find_backward_data_algorithms(&cudnn, &kernel_desc, &output_desc, &input_desc).unwrap().is_empty());
+        assert!(!convolution.get_backward_filter_algorithms(&cudnn, &input_desc, &output_desc, &kernel_desc).unwrap().is_empty());
+        assert!(!convolution.find_backward_filter_algorithms(&cudnn, &input_desc, &output_desc, &kernel_desc).unwrap().is_empty());
     }
 
     #[test]
     fn convolution() {
-        let mut cudnn = Cudnn::new();
+        let mut cudnn = Cudnn::new().unwrap();
 
-        let convolution =  CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation);
-        let input_desc = CuTensorDescriptor::<f32>::new(&[1, 3, 4, 2], &[24, 1, 6, 3]);
-        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 3, 3, 3]);
+        let convolution =  CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::new(&[1, 3, 4, 2], &[24, 1, 6, 3]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 3, 3, 3]).unwrap();
 
         //println!("conv_desc = {:?}", convolution.get_info());
         //println!("input_desc = {:?}", input_desc.get_info());
         //println!("kernel_desc = {:?}", kernel_desc.get_info(4));
 
-        let mut input_data = CuVector::<f32>::zero(input_desc.data_len());
-        let mut output_data = CuVector::<f32>::zero(input_desc.data_len());
-        let kernel_data = CuVector::<f32>::zero(9);
+        let output_desc = convolution.get_forward_output_descriptor(&input_desc, &kernel_desc).unwrap();
+        assert_eq!(output_desc.get_info().unwrap().dimensions, vec![1, 3, 4, 2]);
+
+        let input_data = CuVector::<f32>::zero(input_desc.data_len());
+        let mut output_data = CuVector::<f32>::zero(output_desc.data_len());
+        let kernel_data = CuVector::<f32>::zero(kernel_desc.data_len());
         let algo = CudnnConvolutionFwdAlgo::Gemm;
 
         let workspace_size = convolution.get_forward_workspace_size(&cudnn, &input_desc,
                                                                    &kernel_desc,
-                                                                   &input_desc, algo);
-        let mut workspace = CuVector::<f32>::zero(workspace_size);
+                                                                   &output_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
 
-        convolution.forward(&mut cudnn, 1.0, 1.0, &mut input_desc.link_mut(&mut input_data), &kernel_desc, &kernel_data,
-                           &mut workspace, &mut input_desc.link_mut(&mut output_data), algo);
+        convolution.forward(&mut cudnn, 1.0, 1.0, &input_desc.link(&input_data).unwrap(), &kernel_desc, &kernel_data,
+                           &mut workspace, &mut output_desc.link_mut(&mut output_data).unwrap(), algo).unwrap();
 
     }
 
 
     #[test]
     fn convolution2d() {
-        let mut cudnn = Cudnn::new();
+        let mut cudnn = Cudnn::new().unwrap();
 
         let width = 2;
         let height = 4;
@@ -168,21 +590,21 @@ mod tests {
             1, 1,
             1, 1,
             CudnnConvolutionMode::CrossCorrelation
-        );
+        ).unwrap();
         let kernel_desc = CuFilterDescriptor::<f32>::new_4d(
             CudnnTensorFormat::Nchw,
             3, 3, 3, 3
-        );
+        ).unwrap();
         let input_desc = CuTensorDescriptor::<f32>::new_4d(
             CudnnTensorFormat::Nhwc,
             1, 3,
             height, width,
-        );
+        ).unwrap();
         let output_desc = CuTensorDescriptor::<f32>::new_4d(
             CudnnTensorFormat::Nhwc,
             1,3,
             height, width,
-        );
+        ).unwrap();
 
         //println!("conv_desc = {:?}", convolution.get_info());
         //println!("input_desc = {:?}", input_desc.get_info());
@@ -190,27 +612,268 @@ mod tests {
         //println!("kernel_desc = {:?}", kernel_desc.get_info(4));
 
 
-        let mut input_data = CuVector::<f32>::new(1.0, input_desc.data_len());
+        let input_data = CuVector::<f32>::new(1.0, input_desc.data_len());
         let mut output_data = CuVector::<f32>::zero(output_desc.data_len());
-        let kernel_data = CuVector::<f32>::new(2.0, 9);
+        let kernel_data = CuVector::<f32>::new(2.0, kernel_desc.data_len());
 
         let algo = CudnnConvolutionFwdAlgo::Gemm;
 
         let workspace_size = convolution.get_forward_workspace_size(&cudnn, &input_desc,
                                                                     &kernel_desc,
-                                                                    &output_desc, algo);
-        let mut workspace = CuVector::<f32>::zero(workspace_size);
+                                                                    &output_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
 
         convolution.forward(&mut cudnn,
                             1.0, 1.0,
-                            &mut input_desc.link_mut(&mut input_data),
+                            &input_desc.link(&input_data).unwrap(),
                             &kernel_desc, &kernel_data,
                             &mut workspace,
-                            &mut output_desc.link_mut(&mut output_data), algo);
+                            &mut output_desc.link_mut(&mut output_data).unwrap(), algo).unwrap();
 
         //println!("Input = {:?}", input_data);
         //println!("Output = {:?}", output_data);
 
     }
 
+    #[test]
+    fn backward_data() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+
+        let kernel_host = host_data(kernel_desc.data_len(), 1);
+        let output_signal_host = host_data(output_desc.data_len(), 2);
+        let input_signal_host = host_data(input_desc.data_len(), 3);
+
+        let kernel_data = CuVector::<f32>::from_host_data(&kernel_host);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_host);
+        let mut input_signal = CuVector::<f32>::from_host_data(&input_signal_host);
+
+        let algo = CudnnConvolutionBwdDataAlgo::Algo0;
+        let workspace_size = convolution.get_backward_data_workspace_size(&cudnn, &kernel_desc, &output_desc, &input_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
+
+        convolution.backward_data(&mut cudnn, 1.0, 0.5,
+                                  &output_desc.link(&output_signal).unwrap(),
+                                  &kernel_desc.link(&kernel_data).unwrap(),
+                                  &mut workspace,
+                                  &mut input_desc.link_mut(&mut input_signal).unwrap(), algo).unwrap();
+
+        let mut expected = input_signal_host.clone();
+        reference::convolution_backward_data(&convolution.get_info().unwrap(), 1.0,
+                                             &[3, 2, 3, 3], &kernel_host,
+                                             &[2, 3, 4, 5], &output_signal_host,
+                                             0.5, &[2, 2, 4, 5], &mut expected);
+        assert_close("backward_data", &input_signal, &expected);
+    }
+
+    /// cumath has no f64 vectors, so f64 data is stored as the bits of a f32 vector twice as long.
+    fn f64_data(values: &[f32]) -> CuVector<f32> {
+        let bits = values.iter().flat_map(|&x| {
+            let x = (x as f64).to_bits();
+            vec![f32::from_bits(x as u32), f32::from_bits((x >> 32) as u32)]
+        }).collect::<Vec<_>>();
+        CuVector::<f32>::from_host_data(&bits)
+    }
+
+    fn f64_to_host(data: &CuVector<f32>) -> Vec<f32> {
+        let mut bits = vec![0.0f32; data.len()];
+        data.clone_to_host(&mut bits);
+        bits.chunks(2).map(|x| f64::from_bits(x[0].to_bits() as u64 | (x[1].to_bits() as u64) << 32) as f32).collect()
+    }
+
+    #[test]
+    fn backward_f64() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f64>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f64>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f64>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = convolution.get_forward_output_descriptor(&input_desc, &kernel_desc).unwrap();
+        assert_eq!(output_desc.get_info().unwrap().data_type, CudnnDataType::Double);
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let kernel_host = host_data(kernel_desc.data_len(), 2);
+        let output_signal_host = host_data(output_desc.data_len(), 3);
+        let input_data = f64_data(&input_host);
+        let mut kernel_data = f64_data(&kernel_host);
+        let output_signal = f64_data(&output_signal_host);
+        let mut input_signal = f64_data(&input_host);
+
+        let data_algo = CudnnConvolutionBwdDataAlgo::Algo0;
+        let filter_algo = CudnnConvolutionBwdFilterAlgo::Algo0;
+        let workspace_size = convolution.get_backward_data_workspace_size(&cudnn, &kernel_desc, &output_desc, &input_desc, data_algo).unwrap()
+            .max(convolution.get_backward_filter_workspace_size(&cudnn, &input_desc, &output_desc, &kernel_desc, filter_algo).unwrap());
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
+
+        let output_signal = CuTensorDeref::new(&output_desc, output_signal.as_ptr() as *mut f64);
+        convolution.backward_data(&mut cudnn, 1.0, 0.5, &output_signal,
+                                  &CuFilterDeref { descriptor: &kernel_desc, data: kernel_data.as_ptr() as *mut f64 },
+                                  &mut workspace,
+                                  &mut CuTensorDeref::new(&input_desc, input_signal.as_mut_ptr() as *mut f64), data_algo).unwrap();
+        convolution.backward_filter(&mut cudnn, 2.0, 1.0,
+                                    &CuTensorDeref::new(&input_desc, input_data.as_ptr() as *mut f64), &output_signal,
+                                    &mut workspace,
+                                    &mut CuFilterDeref { descriptor: &kernel_desc, data: kernel_data.as_mut_ptr() as *mut f64 }, filter_algo).unwrap();
+
+        let info = convolution.get_info().unwrap();
+        let mut expected_input_signal = input_host.clone();
+        reference::convolution_backward_data(&info, 1.0, &[3, 2, 3, 3], &kernel_host, &[2, 3, 4, 5], &output_signal_host,
+                                             0.5, &[2, 2, 4, 5], &mut expected_input_signal);
+        let mut expected_kernel = kernel_host.clone();
+        reference::convolution_backward_filter(&info, 2.0, &[2, 2, 4, 5], &input_host, &[2, 3, 4, 5], &output_signal_host,
+                                               1.0, &[3, 2, 3, 3], &mut expected_kernel);
+        for (name, output, expected) in vec![("input_signal", f64_to_host(&input_signal), expected_input_signal),
+                                             ("kernel", f64_to_host(&kernel_data), expected_kernel)] {
+            for i in 0..expected.len() {
+                assert!((output[i] - expected[i]).abs() < 1e-4 * expected[i].abs().max(1.0), "{} : {:?} != {:?}", name, output, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn backward_filter_accumulates() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[0, 1], &[1, 2], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 2, 3]).unwrap();
+
+        let input_host = [host_data(input_desc.data_len(), 1), host_data(input_desc.data_len(), 4)];
+        let output_signal_host = [host_data(output_desc.data_len(), 2), host_data(output_desc.data_len(), 5)];
+
+        let algo = CudnnConvolutionBwdFilterAlgo::Algo0;
+        let workspace_size = convolution.get_backward_filter_workspace_size(&cudnn, &input_desc, &output_desc, &kernel_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
+        let mut kernel_signal = CuVector::<f32>::zero(kernel_desc.data_len());
+        let mut expected = vec![0.0; kernel_desc.data_len()];
+
+        // Sum the gradients of two micro-batches
+        for i in 0..2 {
+            let input = CuVector::<f32>::from_host_data(&input_host[i]);
+            let output_signal = CuVector::<f32>::from_host_data(&output_signal_host[i]);
+            convolution.backward_filter(&mut cudnn, 1.0, 1.0,
+                                        &input_desc.link(&input).unwrap(),
+                                        &output_desc.link(&output_signal).unwrap(),
+                                        &mut workspace,
+                                        &mut kernel_desc.link_mut(&mut kernel_signal).unwrap(), algo).unwrap();
+            reference::convolution_backward_filter(&convolution.get_info().unwrap(), 1.0,
+                                                   &[2, 2, 4, 5], &input_host[i],
+                                                   &[2, 3, 2, 3], &output_signal_host[i],
+                                                   1.0, &[3, 2, 3, 3], &mut expected);
+        }
+        assert_close("backward_filter", &kernel_signal, &expected);
+    }
+
+    #[test]
+    fn bias_dimensions() {
+        assert!(check_bias_dimensions("", &[2, 3, 4, 5], &[1, 3, 1, 1]).is_ok());
+        assert!(check_bias_dimensions("", &[2, 3, 4, 5, 6], &[1, 3, 1, 1, 1]).is_ok());
+        assert!(check_bias_dimensions("", &[2, 3, 4, 5], &[1, 4, 1, 1]).is_err());
+        assert!(check_bias_dimensions("", &[2, 3, 4, 5], &[1, 3, 4, 1]).is_err());
+        assert!(check_bias_dimensions("", &[2, 3, 4, 5], &[1, 3, 1]).is_err());
+    }
+
+    #[test]
+    fn backward_bias() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+        let bias_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 1, 1]).unwrap();
+        let wrong_bias_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 1, 1]).unwrap();
+
+        let output_signal_host = host_data(output_desc.data_len(), 2);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_host);
+        let mut bias_signal = CuVector::<f32>::zero(bias_desc.data_len());
+        let mut wrong_bias_signal = CuVector::<f32>::zero(wrong_bias_desc.data_len());
+
+        convolution.backward_bias(&mut cudnn, 1.0, 0.0,
+                                  &output_desc.link(&output_signal).unwrap(),
+                                  &mut bias_desc.link_mut(&mut bias_signal).unwrap()).unwrap();
+        let mut expected = vec![0.0; 3];
+        reference::convolution_backward_bias(1.0, &[2, 3, 4, 5], &output_signal_host, 0.0, &mut expected);
+        assert_close("backward_bias", &bias_signal, &expected);
+
+        let error = convolution.backward_bias(&mut cudnn, 1.0, 0.0,
+                                              &output_desc.link(&output_signal).unwrap(),
+                                              &mut wrong_bias_desc.link_mut(&mut wrong_bias_signal).unwrap()).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+    #[test]
+    fn bias_activation_forward() {
+        let mut cudnn = Cudnn::new().unwrap();
+
+        let convolution = CuConvolutionDescriptor::<f32>::new(&[1, 1], &[1, 1], &[1, 1], CudnnConvolutionMode::CrossCorrelation).unwrap();
+        let activation = CuActivationDescriptor::relu().unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 4, 5]).unwrap();
+        let kernel_desc = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[3, 2, 3, 3]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+        let bias_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 1, 1]).unwrap();
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let kernel_host = host_data(kernel_desc.data_len(), 2);
+        let z_host = host_data(output_desc.data_len(), 3);
+        let bias_host = host_data(bias_desc.data_len(), 4);
+        let input_data = CuVector::<f32>::from_host_data(&input_host);
+        let kernel_data = CuVector::<f32>::from_host_data(&kernel_host);
+        let z_data = CuVector::<f32>::from_host_data(&z_host);
+        let zero_data = CuVector::<f32>::zero(output_desc.data_len());
+        let bias_data = CuVector::<f32>::from_host_data(&bias_host);
+        let mut output_data = CuVector::<f32>::new(::std::f32::NAN, output_desc.data_len());
+
+        let algo = CudnnConvolutionFwdAlgo::ImplicitPrecompGemm;
+        let workspace_size = convolution.get_forward_workspace_size(&cudnn, &input_desc, &kernel_desc, &output_desc, algo).unwrap();
+        let mut workspace = CuVector::<f32>::zero((workspace_size + size_of::<f32>() - 1) / size_of::<f32>());
+
+        let mut convolved = vec![0.0; output_desc.data_len()];
+        reference::convolution_forward(&convolution.get_info().unwrap(), 1.5,
+                                       &[2, 2, 4, 5], &input_host,
+                                       &[3, 2, 3, 3], &kernel_host,
+                                       0.0, &[2, 3, 4, 5], &mut convolved);
+        let pre_activation = |z: Option<&[f32]>| {
+            convolved.iter().enumerate().map(|(i, x)| {
+                x + z.map_or(0.0, |z| 0.5 * z[i]) + bias_host[i / 20 % 3]
+            }).collect::<Vec<_>>()
+        };
+
+        // With a zero z, the previous content of the output must be ignored, even NaNs
+        convolution.bias_activation_forward(&mut cudnn, 1.5,
+                                            &input_desc.link(&input_data).unwrap(),
+                                            &kernel_desc.link(&kernel_data).unwrap(),
+                                            &mut workspace, algo, 0.5, &output_desc.link(&zero_data).unwrap(),
+                                            &bias_desc.link(&bias_data).unwrap(),
+                                            &activation,
+                                            &mut output_desc.link_mut(&mut output_data).unwrap()).unwrap();
+        let mut expected = vec![0.0; output_desc.data_len()];
+        reference::activation_forward(CudnnActivationMode::Relu, 0.0, 1.0, &pre_activation(None), 0.0, &mut expected);
+        assert_close("bias_activation_forward", &output_data, &expected);
+
+        convolution.bias_activation_forward(&mut cudnn, 1.5,
+                                            &input_desc.link(&input_data).unwrap(),
+                                            &kernel_desc.link(&kernel_data).unwrap(),
+                                            &mut workspace, algo, 0.5, &output_desc.link(&z_data).unwrap(),
+                                            &bias_desc.link(&bias_data).unwrap(),
+                                            &activation,
+                                            &mut output_desc.link_mut(&mut output_data).unwrap()).unwrap();
+        reference::activation_forward(CudnnActivationMode::Relu, 0.0, 1.0, &pre_activation(Some(&z_host)), 0.0, &mut expected);
+        assert_close("bias_activation_forward_z", &output_data, &expected);
+
+        let wrong_z_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 4, 5]).unwrap();
+        let wrong_z_data = CuVector::<f32>::zero(wrong_z_desc.data_len());
+        let error = convolution.bias_activation_forward(&mut cudnn, 1.5,
+                                                        &input_desc.link(&input_data).unwrap(),
+                                                        &kernel_desc.link(&kernel_data).unwrap(),
+                                                        &mut workspace, algo, 0.5, &wrong_z_desc.link(&wrong_z_data).unwrap(),
+                                                        &bias_desc.link(&bias_data).unwrap(),
+                                                        &activation,
+                                                        &mut output_desc.link_mut(&mut output_data).unwrap()).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
 }
\ No newline at end of file
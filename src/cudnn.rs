@@ -9,16 +9,16 @@ pub struct Cudnn {
 
 impl Drop for Cudnn {
     fn drop(&mut self) {
-        cudnn_destroy(self.handle)
+        let _ = cudnn_destroy(self.handle);
     }
 }
 
 impl Cudnn {
 
-    pub fn new() -> Cudnn {
+    pub fn new() -> Result<Cudnn, CudnnError> {
         let mut data = ptr::null_mut();
-        cudnn_create(&mut data);
-        Cudnn { handle: data }
+        cudnn_create(&mut data)?;
+        Ok(Cudnn { handle: data })
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,147 @@
+use super::ffi::CudnnDataType;
+
+
+
+/// Rust element types which cuDNN tensors, filters and descriptors can be made of.
+pub trait CudnnType: Copy + 'static {
+    /// Type of the alpha/beta scaling factors of the operations on this type.
+    /// cuDNN expects doubles for double tensors, and floats for every other type.
+    /// This is also the type of the batch normalization parameters of tensors of this type.
+    type Scaling: CudnnType + From<f32>;
+
+    /// Data type of the tensors and filters made of this type
+    fn data_type() -> CudnnDataType;
+
+    /// Data type the computations on this type are carried in, as given to the convolution and RNN descriptors
+    fn compute_type() -> CudnnDataType;
+}
+
+impl CudnnType for f32 {
+    type Scaling = f32;
+    fn data_type() -> CudnnDataType { CudnnDataType::Float }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Float }
+}
+
+impl CudnnType for f64 {
+    type Scaling = f64;
+    fn data_type() -> CudnnDataType { CudnnDataType::Double }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Double }
+}
+
+impl CudnnType for Half {
+    type Scaling = f32;
+    fn data_type() -> CudnnDataType { CudnnDataType::Half }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Float }
+}
+
+impl CudnnType for i8 {
+    type Scaling = f32;
+    fn data_type() -> CudnnDataType { CudnnDataType::Int8 }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Int32 }
+}
+
+impl CudnnType for u8 {
+    type Scaling = f32;
+    fn data_type() -> CudnnDataType { CudnnDataType::Uint8 }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Int32 }
+}
+
+impl CudnnType for i32 {
+    type Scaling = f32;
+    fn data_type() -> CudnnDataType { CudnnDataType::Int32 }
+    fn compute_type() -> CudnnDataType { CudnnDataType::Int32 }
+}
+
+
+/// IEEE 754 half precision float, stored as its bits.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy, Default)]
+pub struct Half(pub u16);
+
+impl Half {
+
+    /// Nearest half to `value`, ties to even. Values too large for a half become infinities.
+    pub fn from_f32(value: f32) -> Half {
+        let bits = value.to_bits();
+        let sign = ((bits >> 16) & 0x8000) as u16;
+        let exponent = ((bits >> 23) & 0xff) as i32;
+        let mantissa = bits & 0x7f_ffff;
+        if exponent == 0xff {
+            return Half(sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 })
+        }
+        let exponent = exponent - 127 + 15;
+        if exponent >= 0x1f {
+            Half(sign | 0x7c00)
+        } else if exponent > 0 {
+            // A carry out of the mantissa correctly bumps the exponent, up to infinity
+            Half(sign | round_shift(((exponent as u32) << 23) | mantissa, 13) as u16)
+        } else if exponent >= -10 {
+            Half(sign | round_shift(mantissa | 0x80_0000, (14 - exponent) as u32) as u16)
+        } else {
+            Half(sign)
+        }
+    }
+
+    pub fn to_f32(self) -> f32 {
+        let sign = ((self.0 as u32) & 0x8000) << 16;
+        let exponent = ((self.0 >> 10) & 0x1f) as u32;
+        let mantissa = (self.0 & 0x3ff) as u32;
+        match exponent {
+            0 => {
+                let value = mantissa as f32 * (1.0 / (1 << 24) as f32);
+                if sign == 0 { value } else { -value }
+            },
+            0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+            _ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (mantissa << 13)),
+        }
+    }
+
+}
+
+fn round_shift(value: u32, shift: u32) -> u32 {
+    let truncated = value >> shift;
+    let remainder = value & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if remainder > half || (remainder == half && truncated & 1 == 1) { truncated + 1 } else { truncated }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn data_types() {
+        assert_eq!(f32::data_type(), CudnnDataType::Float);
+        assert_eq!(f64::data_type(), CudnnDataType::Double);
+        assert_eq!(Half::data_type(), CudnnDataType::Half);
+        assert_eq!(Half::compute_type(), CudnnDataType::Float);
+        assert_eq!(i8::data_type(), CudnnDataType::Int8);
+        assert_eq!(u8::compute_type(), CudnnDataType::Int32);
+    }
+
+    #[test]
+    fn half_conversions() {
+        for &(value, bits) in &[(0.0, 0x0000), (-0.0, 0x8000), (1.0, 0x3c00), (-2.0, 0xc000), (0.5, 0x3800),
+                                (65504.0, 0x7bff), (1.0 / (1 << 24) as f32, 0x0001), (1.0 / (1 << 14) as f32, 0x0400),
+                                (::std::f32::INFINITY, 0x7c00), (::std::f32::NEG_INFINITY, 0xfc00)] {
+            assert_eq!(Half::from_f32(value), Half(bits), "from_f32({})", value);
+            assert_eq!(Half(bits).to_f32(), value, "to_f32({:#x})", bits);
+        }
+        assert!(Half::from_f32(::std::f32::NAN).to_f32().is_nan());
+    }
+
+    #[test]
+    fn half_rounding() {
+        // Ties go to the even mantissa
+        assert_eq!(Half::from_f32(1.0 + 1.0 / 2048.0), Half(0x3c00));
+        assert_eq!(Half::from_f32(1.0 + 3.0 / 2048.0), Half(0x3c02));
+        assert_eq!(Half::from_f32(1.0 + 1.5 / 2048.0), Half(0x3c01));
+        assert_eq!(Half::from_f32(65520.0), Half(0x7c00));
+        assert_eq!(Half::from_f32(1e6), Half(0x7c00));
+        assert_eq!(Half::from_f32(1e-9), Half(0x0000));
+        assert_eq!(Half::from_f32(3.0 / (1 << 25) as f32), Half(0x0002));
+    }
+
+}
@@ -1,49 +1,207 @@
 
 use super::ffi::*;
-use super::Cudnn;
+use super::{Cudnn, CudnnError, CuTensorDescriptor, CuTensorDeref};
 use std::ptr;
+use std::mem::size_of;
 use std::marker::PhantomData;
 use std::os::raw::c_void;
-use cumath::CuDataType;
 use cumath::CuVector;
+use cudnn_type::CudnnType;
+#[cfg_attr(feature = "disable_checks", allow(unused_imports))]
+use tensor::check_same_dims;
 
-pub struct CuDropoutDescriptor<T: CuDataType> {
+pub struct CuDropoutDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
-    _states: CuVector<T>,
+    states: CuVector<f32>,
+    states_size: usize,
+    dropout: f32,
+    seed: u64,
     pub(crate) data: *mut _DropoutDescriptorStruct,
 }
 
-impl<T: CuDataType> Drop for CuDropoutDescriptor<T> {
+impl<T: CudnnType> Drop for CuDropoutDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_dropout_descriptor(self.data)
+        let _ = cudnn_destroy_dropout_descriptor(self.data);
     }
 }
 
-impl CuDropoutDescriptor<f32> {
+impl<T: CudnnType> CuDropoutDescriptor<T> {
 
-    pub fn new(cudnn: &Cudnn, dropout: f32, seed: u64) -> CuDropoutDescriptor<f32> {
-        let mut states = CuVector::<f32>::zero(Self::get_states_size(cudnn));
+    pub fn new(cudnn: &Cudnn, dropout: f32, seed: u64) -> Result<CuDropoutDescriptor<T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_dropout("CuDropoutDescriptor::new", dropout)?;
+        }
+        let states_size = Self::get_states_size(cudnn)?;
+        let states = CuVector::<f32>::zero((states_size + size_of::<f32>() - 1) / size_of::<f32>());
         let mut data = ptr::null_mut();
-        cudnn_create_dropout_descriptor(&mut data);
-        cudnn_set_dropout_descriptor(data, cudnn.handle, dropout, states.as_mut_ptr() as *mut c_void,
-                                     states.len(), seed);
-
-        CuDropoutDescriptor {
+        cudnn_create_dropout_descriptor(&mut data)?;
+        let mut output = CuDropoutDescriptor {
             _phantom: PhantomData,
-            _states: states,
+            states,
+            states_size,
+            dropout,
+            seed,
             data
+        };
+        cudnn_set_dropout_descriptor(output.data, cudnn.handle, dropout, output.states.as_mut_ptr() as *mut c_void,
+                                     output.states_size, seed)?;
+        Ok(output)
+    }
+
+    pub fn get_info(&self) -> CuDropoutDescriptorInfo {
+        CuDropoutDescriptorInfo { dropout: self.dropout, seed: self.seed }
+    }
+
+    /// Copies the current state of the random generator to the host, as the `get_states_size` bytes cuDNN uses
+    pub fn save_states(&self) -> Vec<u8> {
+        let mut states = vec![0.0f32; self.states.len()];
+        self.states.clone_to_host(&mut states);
+        let mut bytes = states.iter().flat_map(|x| x.to_bits().to_ne_bytes().to_vec()).collect::<Vec<_>>();
+        bytes.truncate(self.states_size);
+        bytes
+    }
+
+    /// Puts back the random generator in a state returned by `save_states`, so that the following
+    /// forward passes draw the same masks as they did after the save.
+    pub fn restore_states(&mut self, cudnn: &Cudnn, states: &[u8]) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if states.len() != self.states_size {
+                return Err(CudnnError::bad_param("CuDropoutDescriptor::restore_states",
+                                                 format!("expected {} bytes of states, got {}", self.states_size, states.len())))
+            }
         }
+        let words = states.chunks(size_of::<f32>()).map(|x| {
+            let mut word = [0; 4];
+            word[..x.len()].copy_from_slice(x);
+            f32::from_bits(u32::from_ne_bytes(word))
+        }).collect::<Vec<_>>();
+        self.states.clone_from_host(&words);
+        cudnn_restore_dropout_descriptor(self.data, cudnn.handle, self.dropout, self.states.as_mut_ptr() as *mut c_void,
+                                         self.states_size, self.seed)
+    }
 
+    /// Reinitializes the random generator from `seed`. This is as slow as creating a new descriptor.
+    pub fn reseed(&mut self, cudnn: &Cudnn, seed: u64) -> Result<(), CudnnError> {
+        cudnn_set_dropout_descriptor(self.data, cudnn.handle, self.dropout, self.states.as_mut_ptr() as *mut c_void,
+                                     self.states_size, seed)?;
+        self.seed = seed;
+        Ok(())
     }
 
-    pub fn get_states_size(cudnn: &Cudnn) -> usize {
+    /// Changes the dropout probability, keeping the current state of the random generator
+    pub fn set_dropout(&mut self, cudnn: &Cudnn, dropout: f32) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_dropout("CuDropoutDescriptor::set_dropout", dropout)?;
+        }
+        cudnn_restore_dropout_descriptor(self.data, cudnn.handle, dropout, self.states.as_mut_ptr() as *mut c_void,
+                                         self.states_size, self.seed)?;
+        self.dropout = dropout;
+        Ok(())
+    }
+
+    /// Size in bytes of the random generator states
+    pub fn get_states_size(cudnn: &Cudnn) -> Result<usize, CudnnError> {
         let mut states_size = 0;
-        cudnn_dropout_get_states_size(cudnn.handle, &mut states_size);
-        states_size
+        cudnn_dropout_get_states_size(cudnn.handle, &mut states_size)?;
+        Ok(states_size)
+    }
+
+    /// Size in bytes of the reserve space needed to drop out a tensor described by `desc`
+    pub fn get_reserve_space_size(desc: &CuTensorDescriptor<T>) -> Result<usize, CudnnError> {
+        let mut size = 0;
+        cudnn_dropout_get_reserve_space_size(desc.data, &mut size)?;
+        Ok(size)
+    }
+
+    /// Randomly zeroes elements of `input` into `output`, scaling the kept ones by 1/(1-dropout).
+    /// The mask is saved in `reserve`, to give back to `backward`. The reserve is only reallocated when
+    /// the size of the input changes, so the same one can be reused from a call to the next.
+    pub fn forward(&mut self, cudnn: &mut Cudnn, input: &CuTensorDeref<T>, output: &mut CuTensorDeref<T>,
+                   reserve: &mut CuDropoutReserve) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_same_dims("CuDropoutDescriptor::forward", "output",
+                            &output.descriptor.get_info()?.dimensions, &input.descriptor.get_info()?.dimensions)?;
+        }
+        let size = Self::get_reserve_space_size(input.descriptor())?;
+        if size != reserve.size {
+            *reserve = CuDropoutReserve::with_size(size);
+        }
+        cudnn_dropout_forward(cudnn.handle, self.data,
+                              input.descriptor.data, input.data as *const c_void,
+                              output.descriptor.data, output.data as *mut c_void,
+                              reserve.data.as_mut_ptr() as *mut c_void, reserve.size)
+    }
+
+    /// Back-propagates `output_signal` through the mask saved in `reserve` by the matching `forward` call
+    pub fn backward(&self, cudnn: &mut Cudnn, reserve: &CuDropoutReserve,
+                    output_signal: &CuTensorDeref<T>, input_signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_same_dims("CuDropoutDescriptor::backward", "input_signal",
+                            &input_signal.descriptor.get_info()?.dimensions, &output_signal.descriptor.get_info()?.dimensions)?;
+            let size = Self::get_reserve_space_size(output_signal.descriptor())?;
+            if size != reserve.size {
+                return Err(CudnnError::bad_param("CuDropoutDescriptor::backward",
+                                                 format!("reserve of {} bytes doesn't match output_signal, which needs {}", reserve.size, size)))
+            }
+        }
+        cudnn_dropout_backward(cudnn.handle, self.data,
+                               output_signal.descriptor.data, output_signal.data as *const c_void,
+                               input_signal.descriptor.data, input_signal.data as *mut c_void,
+                               reserve.data.as_ptr() as *mut c_void, reserve.size)
+    }
+
+}
+
+
+#[cfg(not(feature = "disable_checks"))]
+fn check_dropout(function: &'static str, dropout: f32) -> Result<(), CudnnError> {
+    if !(dropout >= 0.0 && dropout < 1.0) {
+        return Err(CudnnError::bad_param(function, format!("dropout probability must be in [0, 1), got {}", dropout)))
+    }
+    Ok(())
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CuDropoutDescriptorInfo {
+    pub dropout: f32,
+    pub seed: u64,
+}
+
+
+/// Mask saved by a dropout forward pass
+pub struct CuDropoutReserve {
+    data: CuVector<f32>,
+    size: usize,
+}
+
+impl CuDropoutReserve {
+
+    /// An empty reserve, allocated by the first `forward` it's given to
+    pub fn new() -> CuDropoutReserve {
+        CuDropoutReserve::with_size(0)
+    }
+
+    fn with_size(size: usize) -> CuDropoutReserve {
+        CuDropoutReserve {
+            data: CuVector::<f32>::zero((size + size_of::<f32>() - 1) / size_of::<f32>()),
+            size,
+        }
+    }
+
+    /// Size in bytes of the reserve
+    pub fn size(&self) -> usize {
+        self.size
     }
 
 }
 
+impl Default for CuDropoutReserve {
+    fn default() -> CuDropoutReserve {
+        CuDropoutReserve::new()
+    }
+}
+
 
 
 #[cfg(test)]
@@ -51,11 +209,153 @@ mod tests {
 
     use super::*;
 
+    use cumath::CuVector;
+    use test_utils::{host_data, assert_close};
+
     #[test]
     fn test() {
-        let cudnn = Cudnn::new();
-        let _dropout = CuDropoutDescriptor::new(&cudnn, 0.5, 545016);
-        let _states_size = CuDropoutDescriptor::get_states_size(&cudnn);
+        let cudnn = Cudnn::new().unwrap();
+        let _dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+        let _states_size = CuDropoutDescriptor::<f32>::get_states_size(&cudnn).unwrap();
+    }
+
+    #[test]
+    fn forward_backward() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let mut dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+        let desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 8, 8]).unwrap();
+
+        let input = CuVector::<f32>::new(1.0, desc.data_len());
+        let output_signal = CuVector::<f32>::new(3.0, desc.data_len());
+        let mut output = CuVector::<f32>::zero(desc.data_len());
+        let mut input_signal = CuVector::<f32>::zero(desc.data_len());
+
+        let mut reserve = CuDropoutReserve::new();
+        dropout.forward(&mut cudnn, &desc.link(&input).unwrap(), &mut desc.link_mut(&mut output).unwrap(), &mut reserve).unwrap();
+        assert_eq!(reserve.size(), CuDropoutDescriptor::<f32>::get_reserve_space_size(&desc).unwrap());
+        let mut output_data = vec![0.0; desc.data_len()];
+        output.clone_to_host(&mut output_data);
+        assert!(output_data.iter().all(|x| *x == 0.0 || *x == 2.0));
+        assert!(output_data.iter().any(|x| *x == 0.0));
+        assert!(output_data.iter().any(|x| *x == 2.0));
+
+        dropout.backward(&mut cudnn, &reserve, &desc.link(&output_signal).unwrap(), &mut desc.link_mut(&mut input_signal).unwrap()).unwrap();
+        assert_close("dropout backward", &input_signal, &output_data.iter().map(|x| 3.0 * x).collect::<Vec<_>>());
+
+        #[cfg(not(feature = "disable_checks"))] {
+            let other_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 8, 8]).unwrap();
+            let other_signal = CuVector::<f32>::new(3.0, other_desc.data_len());
+            let mut other = CuVector::<f32>::zero(other_desc.data_len());
+            assert!(dropout.backward(&mut cudnn, &reserve, &other_desc.link(&other_signal).unwrap(),
+                                     &mut other_desc.link_mut(&mut other).unwrap()).is_err());
+            assert!(dropout.backward(&mut cudnn, &reserve, &desc.link(&output_signal).unwrap(),
+                                     &mut other_desc.link_mut(&mut other).unwrap()).is_err());
+            assert!(dropout.forward(&mut cudnn, &desc.link(&input).unwrap(),
+                                    &mut other_desc.link_mut(&mut other).unwrap(), &mut reserve).is_err());
+        }
+    }
+
+    fn mask(cudnn: &mut Cudnn, dropout: &mut CuDropoutDescriptor<f32>) -> Vec<f32> {
+        let desc = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 8, 8]).unwrap();
+        let input = CuVector::<f32>::new(1.0, desc.data_len());
+        let mut output = CuVector::<f32>::zero(desc.data_len());
+        dropout.forward(cudnn, &desc.link(&input).unwrap(), &mut desc.link_mut(&mut output).unwrap(), &mut CuDropoutReserve::new()).unwrap();
+        let mut output_data = vec![0.0; desc.data_len()];
+        output.clone_to_host(&mut output_data);
+        output_data
+    }
+
+    #[test]
+    fn states_size() {
+        let cudnn = Cudnn::new().unwrap();
+        let dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+        let states_size = CuDropoutDescriptor::<f32>::get_states_size(&cudnn).unwrap();
+        assert_eq!(dropout.states_size, states_size);
+        assert_eq!(dropout.states.len(), (states_size + 3) / 4);
+        assert_eq!(dropout.get_info(), CuDropoutDescriptorInfo { dropout: 0.5, seed: 545016 });
+    }
+
+    #[test]
+    fn restore_states() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let mut dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+        mask(&mut cudnn, &mut dropout);
+
+        let states = dropout.save_states();
+        assert_eq!(states.len(), CuDropoutDescriptor::<f32>::get_states_size(&cudnn).unwrap());
+        let first = mask(&mut cudnn, &mut dropout);
+        let second = mask(&mut cudnn, &mut dropout);
+        assert_ne!(first, second);
+
+        dropout.restore_states(&cudnn, &states).unwrap();
+        assert_eq!(mask(&mut cudnn, &mut dropout), first);
+        assert_eq!(mask(&mut cudnn, &mut dropout), second);
+
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(dropout.restore_states(&cudnn, &states[1..]).is_err());
+            let mut longer = states.clone();
+            longer.push(0);
+            assert!(dropout.restore_states(&cudnn, &longer).is_err());
+        }
+    }
+
+    #[test]
+    fn reseed() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let mut dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+        let first = mask(&mut cudnn, &mut dropout);
+
+        dropout.reseed(&cudnn, 12).unwrap();
+        assert_eq!(dropout.get_info().seed, 12);
+        let reseeded = mask(&mut cudnn, &mut dropout);
+        let mut fresh = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 12).unwrap();
+        assert_eq!(reseeded, mask(&mut cudnn, &mut fresh));
+
+        dropout.reseed(&cudnn, 545016).unwrap();
+        assert_eq!(mask(&mut cudnn, &mut dropout), first);
+    }
+
+    #[test]
+    fn set_dropout() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let mut dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.5, 545016).unwrap();
+
+        dropout.set_dropout(&cudnn, 0.0).unwrap();
+        assert_eq!(dropout.get_info().dropout, 0.0);
+        assert!(mask(&mut cudnn, &mut dropout).iter().all(|x| *x == 1.0));
+
+        dropout.set_dropout(&cudnn, 0.75).unwrap();
+        assert!(mask(&mut cudnn, &mut dropout).iter().all(|x| *x == 0.0 || *x == 4.0));
+
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(dropout.set_dropout(&cudnn, 1.0).is_err());
+            assert!(CuDropoutDescriptor::<f32>::new(&cudnn, -0.1, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn null_dropout() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let mut dropout = CuDropoutDescriptor::<f32>::new(&cudnn, 0.0, 545016).unwrap();
+        let desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 5, 5]).unwrap();
+        let input_data = host_data(desc.data_len(), 1);
+
+        let input = CuVector::<f32>::from_host_data(&input_data);
+        let mut output = CuVector::<f32>::zero(desc.data_len());
+        let mut reserve = CuDropoutReserve::new();
+        dropout.forward(&mut cudnn, &desc.link(&input).unwrap(), &mut desc.link_mut(&mut output).unwrap(), &mut reserve).unwrap();
+        assert_close("null dropout", &output, &input_data);
+        let size = reserve.size();
+
+        // A reserve is reused while the input keeps the same size, and reallocated otherwise
+        let reserve_ptr = reserve.data.as_ptr();
+        dropout.forward(&mut cudnn, &desc.link(&input).unwrap(), &mut desc.link_mut(&mut output).unwrap(), &mut reserve).unwrap();
+        assert_eq!(reserve.data.as_ptr(), reserve_ptr);
+        let small_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 5, 5]).unwrap();
+        let small_input = CuVector::<f32>::zero(small_desc.data_len());
+        let mut small_output = CuVector::<f32>::zero(small_desc.data_len());
+        dropout.forward(&mut cudnn, &small_desc.link(&small_input).unwrap(), &mut small_desc.link_mut(&mut small_output).unwrap(), &mut reserve).unwrap();
+        assert!(reserve.size() < size);
     }
 
 }
\ No newline at end of file
@@ -1,8 +1,9 @@
 
 use std::os::raw::c_void;
-use super::{CudnnStatus, CudnnActivationMode, CudnnNanPropagation};
+use super::{CudnnStatus, CudnnError, CudnnActivationMode, CudnnNanPropagation};
 use super::cudnn::_CudnnStruct;
 use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
 
 
 
@@ -11,53 +12,55 @@ pub enum _ActivationDescriptorStruct {}
 
 
 
-#[allow(non_snake_case)]
-extern {
-
-    fn cudnnCreateActivationDescriptor(activationDesc: *mut*mut _ActivationDescriptorStruct) -> CudnnStatus;
-
-    fn cudnnDestroyActivationDescriptor(activationDesc: *mut _ActivationDescriptorStruct) -> CudnnStatus;
-
-    fn cudnnSetActivationDescriptor(
-        activationDesc: *mut _ActivationDescriptorStruct,
-        mode: CudnnActivationMode,
-        reluNanOpt: CudnnNanPropagation,
-        coef: f64
-    ) -> CudnnStatus;
-
-    fn cudnnGetActivationDescriptor(
-        activationDesc: *const _ActivationDescriptorStruct,
-        mode: *mut CudnnActivationMode,
-        reluNanOpt: *mut CudnnNanPropagation,
-        coef: *mut f64
-    ) -> CudnnStatus;
-
-    fn cudnnActivationForward(
-        handle: *const _CudnnStruct,
-        activationDesc: *const _ActivationDescriptorStruct,
-        alpha: *const c_void,
-        xDesc: *const _TensorDescriptorStruct,
-        x: *const c_void,
-        beta: *const c_void,
-        yDesc: *const _TensorDescriptorStruct,
-        y: *mut c_void
-    ) -> CudnnStatus;
-
-    fn cudnnActivationBackward(
-        handle: *const _CudnnStruct,
-        activationDesc: *const _ActivationDescriptorStruct,
-        alpha: *const c_void,
-        yDesc: *const _TensorDescriptorStruct,
-        y: *const c_void,
-        dyDesc: *const _TensorDescriptorStruct,
-        dy: *const c_void,
-        xDesc: *const _TensorDescriptorStruct,
-        x: *const c_void,
-        beta: *const c_void,
-        dxDesc: *const _TensorDescriptorStruct,
-        dx: *mut c_void,
-    ) -> CudnnStatus;
+cudnn_api! {
+    /// Activation descriptors, forward and backward activations
+    pub trait ActivationApi {
+
+        fn cudnnCreateActivationDescriptor(activationDesc: *mut*mut _ActivationDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyActivationDescriptor(activationDesc: *mut _ActivationDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetActivationDescriptor(
+            activationDesc: *mut _ActivationDescriptorStruct,
+            mode: CudnnActivationMode,
+            reluNanOpt: CudnnNanPropagation,
+            coef: f64
+        ) -> CudnnStatus;
+
+        fn cudnnGetActivationDescriptor(
+            activationDesc: *const _ActivationDescriptorStruct,
+            mode: *mut CudnnActivationMode,
+            reluNanOpt: *mut CudnnNanPropagation,
+            coef: *mut f64
+        ) -> CudnnStatus;
+
+        fn cudnnActivationForward(
+            handle: *const _CudnnStruct,
+            activationDesc: *const _ActivationDescriptorStruct,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void
+        ) -> CudnnStatus;
+
+        fn cudnnActivationBackward(
+            handle: *const _CudnnStruct,
+            activationDesc: *const _ActivationDescriptorStruct,
+            alpha: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            dxDesc: *const _TensorDescriptorStruct,
+            dx: *mut c_void,
+        ) -> CudnnStatus;
 
+    }
 }
 
 
@@ -65,62 +68,68 @@ extern {
 
 
 #[inline]
-pub fn cudnn_create_activation_descriptor(activation_desc: *mut*mut _ActivationDescriptorStruct) {
+pub fn cudnn_create_activation_descriptor(activation_desc: *mut*mut _ActivationDescriptorStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreateActivationDescriptor(activation_desc) }.assert_success();
+        unsafe { Backend::cudnnCreateActivationDescriptor(activation_desc) }.into_result("cudnnCreateActivationDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreateActivationDescriptor(activation_desc) };
+        unsafe { Backend::cudnnCreateActivationDescriptor(activation_desc) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy_activation_descriptor(activation_desc: *mut _ActivationDescriptorStruct) {
+pub fn cudnn_destroy_activation_descriptor(activation_desc: *mut _ActivationDescriptorStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroyActivationDescriptor(activation_desc) }.assert_success();
+        unsafe { Backend::cudnnDestroyActivationDescriptor(activation_desc) }.into_result("cudnnDestroyActivationDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroyActivationDescriptor(activation_desc) };
+        unsafe { Backend::cudnnDestroyActivationDescriptor(activation_desc) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_activation_descriptor(activation_desc: *mut _ActivationDescriptorStruct, mode: CudnnActivationMode, relu_nan_opt: CudnnNanPropagation, coef: f64) {
+pub fn cudnn_set_activation_descriptor(activation_desc: *mut _ActivationDescriptorStruct, mode: CudnnActivationMode, relu_nan_opt: CudnnNanPropagation, coef: f64) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) }.assert_success();
+        unsafe { Backend::cudnnSetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) }.into_result("cudnnSetActivationDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) };
+        unsafe { Backend::cudnnSetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_activation_descriptor(activation_desc: *const _ActivationDescriptorStruct, mode: *mut CudnnActivationMode, relu_nan_opt: *mut CudnnNanPropagation, coef: *mut f64) {
+pub fn cudnn_get_activation_descriptor(activation_desc: *const _ActivationDescriptorStruct, mode: *mut CudnnActivationMode, relu_nan_opt: *mut CudnnNanPropagation, coef: *mut f64) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) }.assert_success();
+        unsafe { Backend::cudnnGetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) }.into_result("cudnnGetActivationDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) };
+        unsafe { Backend::cudnnGetActivationDescriptor(activation_desc, mode, relu_nan_opt, coef) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_activation_forward(handle: *const _CudnnStruct, activation_desc: *const _ActivationDescriptorStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) {
+pub fn cudnn_activation_forward(handle: *const _CudnnStruct, activation_desc: *const _ActivationDescriptorStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnActivationForward(handle, activation_desc, alpha, x_desc, x, beta, y_desc, y) }.assert_success();
+        unsafe { Backend::cudnnActivationForward(handle, activation_desc, alpha, x_desc, x, beta, y_desc, y) }.into_result("cudnnActivationForward")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnActivationForward(handle, activation_desc, alpha, x_desc, x, beta, y_desc, y) };
+        unsafe { Backend::cudnnActivationForward(handle, activation_desc, alpha, x_desc, x, beta, y_desc, y) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_activation_backward(handle: *const _CudnnStruct, activation_desc: *const _ActivationDescriptorStruct, alpha: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void) {
+pub fn cudnn_activation_backward(handle: *const _CudnnStruct, activation_desc: *const _ActivationDescriptorStruct, alpha: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnActivationBackward(handle, activation_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) }.assert_success();
+        unsafe { Backend::cudnnActivationBackward(handle, activation_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) }.into_result("cudnnActivationBackward")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnActivationBackward(handle, activation_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) };
+        unsafe { Backend::cudnnActivationBackward(handle, activation_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) };
+        Ok(())
     }
 }
 
@@ -0,0 +1,63 @@
+
+//! The implementation of the cuDNN API the safe wrappers call into.
+//!
+//! Every module of `ffi` declares its part of the API as a trait through `cudnn_api!`, with the same
+//! functions and signatures as the C library. `CudnnFfi` implements them by calling libcudnn, and is
+//! the `Backend` by default. With the `cpu_backend` feature, libcudnn isn't linked at all and
+//! `Backend` is `CudnnCpu`, a host implementation built on the `reference` module.
+
+use super::*;
+
+
+
+/// Declares a part of the cuDNN API: the trait listing its functions, and, unless the `cpu_backend`
+/// feature is enabled, the extern declarations of libcudnn and their implementation for `CudnnFfi`.
+macro_rules! cudnn_api {
+    ($(#[$attr:meta])* pub trait $api:ident {
+        $(fn $name:ident($($arg:ident: $arg_type:ty),* $(,)*) -> CudnnStatus;)*
+    }) => {
+        $(#[$attr])*
+        #[allow(non_snake_case)]
+        pub trait $api {
+            $(unsafe fn $name($($arg: $arg_type),*) -> CudnnStatus;)*
+        }
+
+        #[cfg(not(feature = "cpu_backend"))]
+        #[allow(non_snake_case)]
+        extern {
+            $(fn $name($($arg: $arg_type),*) -> CudnnStatus;)*
+        }
+
+        #[cfg(not(feature = "cpu_backend"))]
+        #[allow(non_snake_case)]
+        impl $api for ::ffi::backend::CudnnFfi {
+            $(unsafe fn $name($($arg: $arg_type),*) -> CudnnStatus { $name($($arg),*) })*
+        }
+    };
+}
+
+
+
+/// The whole cuDNN API used by the crate.
+pub trait CudnnBackend: HandleApi + TensorApi + FilterApi + ActivationApi + ConvolutionApi + PoolingApi + SoftmaxApi
+                        + BatchNormalizationApi + OpTensorApi + ReduceTensorApi + DropoutApi + RNNApi {}
+
+impl<B> CudnnBackend for B
+    where B: HandleApi + TensorApi + FilterApi + ActivationApi + ConvolutionApi + PoolingApi + SoftmaxApi
+             + BatchNormalizationApi + OpTensorApi + ReduceTensorApi + DropoutApi + RNNApi {}
+
+
+/// libcudnn, through its C API.
+pub struct CudnnFfi;
+
+#[cfg(not(feature = "cpu_backend"))]
+pub type Backend = CudnnFfi;
+
+#[cfg(feature = "cpu_backend")]
+pub type Backend = super::cpu::CudnnCpu;
+
+/// Fails to compile if `Backend` misses a part of the API.
+fn _assert_backend() {
+    fn is_backend<B: CudnnBackend>() {}
+    is_backend::<Backend>()
+}
@@ -0,0 +1,133 @@
+
+use std::os::raw::c_void;
+use super::{CudnnStatus, CudnnError, CudnnBatchNormMode};
+use super::cudnn::_CudnnStruct;
+use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
+
+
+
+
+cudnn_api! {
+    /// Batch normalization passes
+    pub trait BatchNormalizationApi {
+
+        fn cudnnDeriveBNTensorDescriptor(
+            derivedBnDesc: *mut _TensorDescriptorStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            mode: CudnnBatchNormMode,
+        ) -> CudnnStatus;
+
+        fn cudnnBatchNormalizationForwardTraining(
+            handle: *mut _CudnnStruct,
+            mode: CudnnBatchNormMode,
+            alpha: *const c_void,
+            beta: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+            bnScaleBiasMeanVarDesc: *const _TensorDescriptorStruct,
+            bnScale: *const c_void,
+            bnBias: *const c_void,
+            exponentialAverageFactor: f64,
+            resultRunningMean: *mut c_void,
+            resultRunningVariance: *mut c_void,
+            epsilon: f64,
+            resultSaveMean: *mut c_void,
+            resultSaveInvVariance: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnBatchNormalizationForwardInference(
+            handle: *mut _CudnnStruct,
+            mode: CudnnBatchNormMode,
+            alpha: *const c_void,
+            beta: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+            bnScaleBiasMeanVarDesc: *const _TensorDescriptorStruct,
+            bnScale: *const c_void,
+            bnBias: *const c_void,
+            estimatedMean: *const c_void,
+            estimatedVariance: *const c_void,
+            epsilon: f64,
+        ) -> CudnnStatus;
+
+        fn cudnnBatchNormalizationBackward(
+            handle: *mut _CudnnStruct,
+            mode: CudnnBatchNormMode,
+            alphaDataDiff: *const c_void,
+            betaDataDiff: *const c_void,
+            alphaParamDiff: *const c_void,
+            betaParamDiff: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            dxDesc: *const _TensorDescriptorStruct,
+            dx: *mut c_void,
+            dBnScaleBiasDesc: *const _TensorDescriptorStruct,
+            bnScale: *const c_void,
+            dBnScaleResult: *mut c_void,
+            dBnBiasResult: *mut c_void,
+            epsilon: f64,
+            savedMean: *const c_void,
+            savedInvVariance: *const c_void,
+        ) -> CudnnStatus;
+
+    }
+}
+
+
+
+
+
+#[inline]
+pub fn cudnn_derive_bn_tensor_descriptor(derived_bn_desc: *mut _TensorDescriptorStruct, x_desc: *const _TensorDescriptorStruct, mode: CudnnBatchNormMode) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDeriveBNTensorDescriptor(derived_bn_desc, x_desc, mode) }.into_result("cudnnDeriveBNTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDeriveBNTensorDescriptor(derived_bn_desc, x_desc, mode) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_batch_normalization_forward_training(handle: *mut _CudnnStruct, mode: CudnnBatchNormMode, alpha: *const c_void, beta: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void, bn_scale_bias_mean_var_desc: *const _TensorDescriptorStruct, bn_scale: *const c_void, bn_bias: *const c_void, exponential_average_factor: f64, result_running_mean: *mut c_void, result_running_variance: *mut c_void, epsilon: f64, result_save_mean: *mut c_void, result_save_inv_variance: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnBatchNormalizationForwardTraining(handle, mode, alpha, beta, x_desc, x, y_desc, y, bn_scale_bias_mean_var_desc, bn_scale, bn_bias, exponential_average_factor, result_running_mean, result_running_variance, epsilon, result_save_mean, result_save_inv_variance) }.into_result("cudnnBatchNormalizationForwardTraining")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnBatchNormalizationForwardTraining(handle, mode, alpha, beta, x_desc, x, y_desc, y, bn_scale_bias_mean_var_desc, bn_scale, bn_bias, exponential_average_factor, result_running_mean, result_running_variance, epsilon, result_save_mean, result_save_inv_variance) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_batch_normalization_forward_inference(handle: *mut _CudnnStruct, mode: CudnnBatchNormMode, alpha: *const c_void, beta: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void, bn_scale_bias_mean_var_desc: *const _TensorDescriptorStruct, bn_scale: *const c_void, bn_bias: *const c_void, estimated_mean: *const c_void, estimated_variance: *const c_void, epsilon: f64) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnBatchNormalizationForwardInference(handle, mode, alpha, beta, x_desc, x, y_desc, y, bn_scale_bias_mean_var_desc, bn_scale, bn_bias, estimated_mean, estimated_variance, epsilon) }.into_result("cudnnBatchNormalizationForwardInference")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnBatchNormalizationForwardInference(handle, mode, alpha, beta, x_desc, x, y_desc, y, bn_scale_bias_mean_var_desc, bn_scale, bn_bias, estimated_mean, estimated_variance, epsilon) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_batch_normalization_backward(handle: *mut _CudnnStruct, mode: CudnnBatchNormMode, alpha_data_diff: *const c_void, beta_data_diff: *const c_void, alpha_param_diff: *const c_void, beta_param_diff: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void, d_bn_scale_bias_desc: *const _TensorDescriptorStruct, bn_scale: *const c_void, d_bn_scale_result: *mut c_void, d_bn_bias_result: *mut c_void, epsilon: f64, saved_mean: *const c_void, saved_inv_variance: *const c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnBatchNormalizationBackward(handle, mode, alpha_data_diff, beta_data_diff, alpha_param_diff, beta_param_diff, x_desc, x, dy_desc, dy, dx_desc, dx, d_bn_scale_bias_desc, bn_scale, d_bn_scale_result, d_bn_bias_result, epsilon, saved_mean, saved_inv_variance) }.into_result("cudnnBatchNormalizationBackward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnBatchNormalizationBackward(handle, mode, alpha_data_diff, beta_data_diff, alpha_param_diff, beta_param_diff, x_desc, x, dy_desc, dy, dx_desc, dx, d_bn_scale_bias_desc, bn_scale, d_bn_scale_result, d_bn_bias_result, epsilon, saved_mean, saved_inv_variance) };
+        Ok(())
+    }
+}
+
+
+
+
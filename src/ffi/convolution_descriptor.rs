@@ -1,188 +1,607 @@
 
 use std::os::raw::c_void;
-use super::{CudnnStatus, CudnnDataType, CudnnConvolutionFwdAlgo, CudnnConvolutionMode, CudnnMathType};
+use super::{CudnnStatus, CudnnError, CudnnDataType, CudnnConvolutionFwdAlgo, CudnnConvolutionBwdDataAlgo, CudnnConvolutionBwdFilterAlgo, CudnnConvolutionMode, CudnnMathType, CudnnDeterminism};
 use super::cudnn::_CudnnStruct;
 use super::filter_descriptor::_FilterDescriptorStruct;
 use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::activation_descriptor::_ActivationDescriptorStruct;
+use super::backend::Backend;
 
 
 
 pub enum _ConvolutionDescriptorStruct {}
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct _ConvolutionAlgoPerfStruct<A: Copy> {
+    pub algo: A,
+    pub status: CudnnStatus,
+    pub time: f32,
+    pub memory: usize,
+    pub determinism: CudnnDeterminism,
+    pub math_type: CudnnMathType,
+    reserved: [i32; 3],
+}
+impl<A: Copy> _ConvolutionAlgoPerfStruct<A> {
+    pub fn new(algo: A) -> _ConvolutionAlgoPerfStruct<A> {
+        _ConvolutionAlgoPerfStruct {
+            algo,
+            status: CudnnStatus::NotInitialized,
+            time: -1.0,
+            memory: 0,
+            determinism: CudnnDeterminism::NonDeterministic,
+            math_type: CudnnMathType::Default,
+            reserved: [0; 3],
+        }
+    }
+}
 
 
 
-#[allow(non_snake_case)]
-extern {
 
-    fn cudnnCreateConvolutionDescriptor(convDesc: *mut*mut _ConvolutionDescriptorStruct) -> CudnnStatus;
+cudnn_api! {
+    /// Convolution descriptors, algorithm queries and convolution passes
+    pub trait ConvolutionApi {
+
+        fn cudnnCreateConvolutionDescriptor(convDesc: *mut*mut _ConvolutionDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyConvolutionDescriptor(convDesc: *mut _ConvolutionDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetConvolutionNdDescriptor(
+            convDesc: *mut _ConvolutionDescriptorStruct,
+            arrayLength: i32,
+            padA: *const i32,
+            filterStrideA: *const i32,
+            dilatationA: *const i32,
+            mode: CudnnConvolutionMode,
+            dataType: CudnnDataType,
+        ) -> CudnnStatus;
+
+        fn cudnnSetConvolution2dDescriptor(
+            convDesc: *mut _ConvolutionDescriptorStruct,
+            pad_h: i32,
+            pad_w: i32,
+            u: i32,
+            v: i32,
+            dilatation_h: i32,
+            dilatation_w: i32,
+            mode: CudnnConvolutionMode,
+            computeType: CudnnDataType,
+        ) -> CudnnStatus;
+
+        fn cudnnSetConvolutionGroupCount(
+            convDesc: *mut _ConvolutionDescriptorStruct,
+            groupCount: i32,
+        ) -> CudnnStatus;
+
+        fn cudnnSetConvolutionMathType(
+            convDesc: *mut _ConvolutionDescriptorStruct,
+            math_type: CudnnMathType,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionNdDescriptor(
+            convDesc: *const _ConvolutionDescriptorStruct,
+            arrayLengthRequested: i32,
+            arrayLength: *mut i32,
+            padA: *mut i32,
+            filterStrideA: *mut i32,
+            dilatationA: *mut i32,
+            mode: *mut CudnnConvolutionMode,
+            dataType: *mut CudnnDataType,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionForwardWorkspaceSize(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            algo: CudnnConvolutionFwdAlgo,
+            sizeInBytes: *mut usize
+        ) -> CudnnStatus;
+
+        fn cudnnConvolutionForward(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            wDesc: *const _FilterDescriptorStruct,
+            w: *const c_void,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            algo: CudnnConvolutionFwdAlgo,
+            workspace: *mut c_void,
+            workspaceSizeInBytes: usize,
+            beta: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardDataWorkspaceSize(
+            handle: *mut _CudnnStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dxDesc: *const _TensorDescriptorStruct,
+            algo: CudnnConvolutionBwdDataAlgo,
+            sizeInBytes: *mut usize
+        ) -> CudnnStatus;
+
+        fn cudnnConvolutionBackwardData(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            wDesc: *const _FilterDescriptorStruct,
+            w: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            algo: CudnnConvolutionBwdDataAlgo,
+            workspace: *mut c_void,
+            workspaceSizeInBytes: usize,
+            beta: *const c_void,
+            dxDesc: *const _TensorDescriptorStruct,
+            dx: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardFilterWorkspaceSize(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dwDesc: *const _FilterDescriptorStruct,
+            algo: CudnnConvolutionBwdFilterAlgo,
+            sizeInBytes: *mut usize,
+        ) -> CudnnStatus;
+
+        fn cudnnConvolutionBackwardFilter(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            algo: CudnnConvolutionBwdFilterAlgo,
+            workspace: *mut c_void,
+            workspaceSizeInBytes: usize,
+            beta: *const c_void,
+            dwDesc: *const _FilterDescriptorStruct,
+            dw: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnConvolutionBackwardBias(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            beta: *const c_void,
+            dbDesc: *const _TensorDescriptorStruct,
+            db: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionForwardAlgorithmMaxCount(
+            handle: *mut _CudnnStruct,
+            count: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionForwardAlgorithm_v7(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnFindConvolutionForwardAlgorithm(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardDataAlgorithmMaxCount(
+            handle: *mut _CudnnStruct,
+            count: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardDataAlgorithm_v7(
+            handle: *mut _CudnnStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dxDesc: *const _TensorDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnFindConvolutionBackwardDataAlgorithm(
+            handle: *mut _CudnnStruct,
+            wDesc: *const _FilterDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dxDesc: *const _TensorDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardFilterAlgorithmMaxCount(
+            handle: *mut _CudnnStruct,
+            count: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionBackwardFilterAlgorithm_v7(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dwDesc: *const _FilterDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnFindConvolutionBackwardFilterAlgorithm(
+            handle: *mut _CudnnStruct,
+            xDesc: *const _TensorDescriptorStruct,
+            dyDesc: *const _TensorDescriptorStruct,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            dwDesc: *const _FilterDescriptorStruct,
+            requestedAlgoCount: i32,
+            returnedAlgoCount: *mut i32,
+            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionNdForwardOutputDim(
+            convDesc: *const _ConvolutionDescriptorStruct,
+            inputTensorDesc: *const _TensorDescriptorStruct,
+            filterDesc: *const _FilterDescriptorStruct,
+            nbDims: i32,
+            tensorOutputDimA: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionGroupCount(
+            convDesc: *const _ConvolutionDescriptorStruct,
+            groupCount: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetConvolutionMathType(
+            convDesc: *const _ConvolutionDescriptorStruct,
+            mathType: *mut CudnnMathType,
+        ) -> CudnnStatus;
+
+        fn cudnnConvolutionBiasActivationForward(
+            handle: *mut _CudnnStruct,
+            alpha1: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            wDesc: *const _FilterDescriptorStruct,
+            w: *const c_void,
+            convDesc: *const _ConvolutionDescriptorStruct,
+            algo: CudnnConvolutionFwdAlgo,
+            workSpace: *mut c_void,
+            workSpaceSizeInBytes: usize,
+            alpha2: *const c_void,
+            zDesc: *const _TensorDescriptorStruct,
+            z: *const c_void,
+            biasDesc: *const _TensorDescriptorStruct,
+            bias: *const c_void,
+            activationDesc: *const _ActivationDescriptorStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+        ) -> CudnnStatus;
 
-    fn cudnnDestroyConvolutionDescriptor(convDesc: *mut _ConvolutionDescriptorStruct) -> CudnnStatus;
+    }
+}
 
-    fn cudnnSetConvolutionNdDescriptor(
-        convDesc: *mut _ConvolutionDescriptorStruct,
-        arrayLength: i32,
-        padA: *const i32,
-        filterStrideA: *const i32,
-        dilatationA: *const i32,
-        mode: CudnnConvolutionMode,
-        dataType: CudnnDataType,
-    ) -> CudnnStatus;
 
-    fn cudnnSetConvolution2dDescriptor(
-        convDesc: *mut _ConvolutionDescriptorStruct,
-        pad_h: i32,
-        pad_w: i32,
-        u: i32,
-        v: i32,
-        dilatation_h: i32,
-        dilatation_w: i32,
-        mode: CudnnConvolutionMode,
-        computeType: CudnnDataType,
-    ) -> CudnnStatus;
 
-    fn cudnnSetConvolutionGroupCount(
-        convDesc: *mut _ConvolutionDescriptorStruct,
-        groupCount: i32,
-    ) -> CudnnStatus;
 
-    fn cudnnSetConvolutionMathType(
-        convDesc: *mut _ConvolutionDescriptorStruct,
-        math_type: CudnnMathType,
-    ) -> CudnnStatus;
 
-    fn cudnnGetConvolutionNdDescriptor(
-        convDesc: *const _ConvolutionDescriptorStruct,
-        arrayLengthRequested: i32,
-        arrayLength: *mut i32,
-        padA: *mut i32,
-        filterStrideA: *mut i32,
-        dilatationA: *mut i32,
-        mode: *mut CudnnConvolutionMode,
-        dataType: *mut CudnnDataType,
-    ) -> CudnnStatus;
+#[inline]
+pub fn cudnn_create_convolution_descriptor(conv_desc: *mut*mut _ConvolutionDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnCreateConvolutionDescriptor(conv_desc) }.into_result("cudnnCreateConvolutionDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnCreateConvolutionDescriptor(conv_desc) };
+        Ok(())
+    }
+}
 
-    fn cudnnGetConvolutionForwardWorkspaceSize(
-        handle: *mut _CudnnStruct,
-        xDesc: *const _TensorDescriptorStruct,
-        wDesc: *const _FilterDescriptorStruct,
-        convDesc: *const _ConvolutionDescriptorStruct,
-        yDesc: *const _TensorDescriptorStruct,
-        algo: CudnnConvolutionFwdAlgo,
-        sizeInBytes: *mut usize
-    ) -> CudnnStatus;
+#[inline]
+pub fn cudnn_destroy_convolution_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDestroyConvolutionDescriptor(conv_desc) }.into_result("cudnnDestroyConvolutionDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDestroyConvolutionDescriptor(conv_desc) };
+        Ok(())
+    }
+}
 
-    fn cudnnConvolutionForward(
-        handle: *mut _CudnnStruct,
-        alpha: *const c_void,
-        xDesc: *const _TensorDescriptorStruct,
-        x: *const c_void,
-        wDesc: *const _FilterDescriptorStruct,
-        w: *const c_void,
-        convDesc: *const _ConvolutionDescriptorStruct,
-        algo: CudnnConvolutionFwdAlgo,
-        workspace: *mut c_void,
-        workspaceSizeInBytes: usize,
-        beta: *const c_void,
-        yDesc: *const _TensorDescriptorStruct,
-        y: *mut c_void,
-    ) -> CudnnStatus;
+#[inline]
+pub fn cudnn_set_convolution_nd_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct, array_length: i32, pad_a: *const i32, filter_stride_a: *const i32, dilatation_a: *const i32, mode: CudnnConvolutionMode, data_type: CudnnDataType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetConvolutionNdDescriptor(conv_desc, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) }.into_result("cudnnSetConvolutionNdDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetConvolutionNdDescriptor(conv_desc, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_set_convolution2d_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct, pad_h: i32, pad_w: i32, u: i32, v: i32, dilatation_h: i32, dilatation_w: i32, mode: CudnnConvolutionMode, compute_type: CudnnDataType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetConvolution2dDescriptor(conv_desc, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, compute_type) }.into_result("cudnnSetConvolution2dDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetConvolution2dDescriptor(conv_desc, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, compute_type) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_set_convolution_group_count(conv_desc: *mut _ConvolutionDescriptorStruct, group_count: i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetConvolutionGroupCount(conv_desc, group_count) }.into_result("cudnnSetConvolutionGroupCount")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetConvolutionGroupCount(conv_desc, group_count) };
+        Ok(())
+    }
 }
 
+#[inline]
+pub fn cudnn_set_convolution_math_type(conv_desc: *mut _ConvolutionDescriptorStruct, math_type: CudnnMathType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetConvolutionMathType(conv_desc, math_type) }.into_result("cudnnSetConvolutionMathType")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetConvolutionMathType(conv_desc, math_type) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_get_convolution_nd_descriptor(conv_desc: *const _ConvolutionDescriptorStruct, array_length_requested: i32, array_length: *mut i32, pad_a: *mut i32, filter_stride_a: *mut i32, dilatation_a: *mut i32, mode: *mut CudnnConvolutionMode, data_type: *mut CudnnDataType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionNdDescriptor(conv_desc, array_length_requested, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) }.into_result("cudnnGetConvolutionNdDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionNdDescriptor(conv_desc, array_length_requested, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_get_convolution_forward_workspace_size(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, w_desc: *const _FilterDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, y_desc: *const _TensorDescriptorStruct, algo: CudnnConvolutionFwdAlgo, size_in_bytes: *mut usize) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionForwardWorkspaceSize(handle, x_desc, w_desc, conv_desc, y_desc, algo, size_in_bytes) }.into_result("cudnnGetConvolutionForwardWorkspaceSize")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionForwardWorkspaceSize(handle, x_desc, w_desc, conv_desc, y_desc, algo, size_in_bytes) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_convolution_forward(handle: *mut _CudnnStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, w_desc: *const _FilterDescriptorStruct, w: *const c_void, conv_desc: *const _ConvolutionDescriptorStruct, algo: CudnnConvolutionFwdAlgo, workspace: *mut c_void, workspace_size_in_bytes: usize, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnConvolutionForward(handle, alpha, x_desc, x, w_desc, w, conv_desc, algo, workspace, workspace_size_in_bytes, beta, y_desc, y) }.into_result("cudnnConvolutionForward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnConvolutionForward(handle, alpha, x_desc, x, w_desc, w, conv_desc, algo, workspace, workspace_size_in_bytes, beta, y_desc, y) };
+        Ok(())
+    }
+}
 
 #[inline]
-pub fn cudnn_create_convolution_descriptor(conv_desc: *mut*mut _ConvolutionDescriptorStruct) {
+pub fn cudnn_get_convolution_backward_data_workspace_size(handle: *mut _CudnnStruct, w_desc: *const _FilterDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dx_desc: *const _TensorDescriptorStruct, algo: CudnnConvolutionBwdDataAlgo, size_in_bytes: *mut usize) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreateConvolutionDescriptor(conv_desc) }.assert_success();
+        unsafe { Backend::cudnnGetConvolutionBackwardDataWorkspaceSize(handle, w_desc, dy_desc, conv_desc, dx_desc, algo, size_in_bytes) }.into_result("cudnnGetConvolutionBackwardDataWorkspaceSize")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreateConvolutionDescriptor(conv_desc) };
+        unsafe { Backend::cudnnGetConvolutionBackwardDataWorkspaceSize(handle, w_desc, dy_desc, conv_desc, dx_desc, algo, size_in_bytes) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy_convolution_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct) {
+pub fn cudnn_convolution_backward_data(handle: *mut _CudnnStruct, alpha: *const c_void, w_desc: *const _FilterDescriptorStruct, w: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, conv_desc: *const _ConvolutionDescriptorStruct, algo: CudnnConvolutionBwdDataAlgo, workspace: *mut c_void, workspace_size_in_bytes: usize, beta: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroyConvolutionDescriptor(conv_desc) }.assert_success();
+        unsafe { Backend::cudnnConvolutionBackwardData(handle, alpha, w_desc, w, dy_desc, dy, conv_desc, algo, workspace, workspace_size_in_bytes, beta, dx_desc, dx) }.into_result("cudnnConvolutionBackwardData")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroyConvolutionDescriptor(conv_desc) };
+        unsafe { Backend::cudnnConvolutionBackwardData(handle, alpha, w_desc, w, dy_desc, dy, conv_desc, algo, workspace, workspace_size_in_bytes, beta, dx_desc, dx) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_convolution_nd_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct, array_length: i32, pad_a: *const i32, filter_stride_a: *const i32, dilatation_a: *const i32, mode: CudnnConvolutionMode, data_type: CudnnDataType) {
+pub fn cudnn_get_convolution_backward_filter_workspace_size(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dw_desc: *const _FilterDescriptorStruct, algo: CudnnConvolutionBwdFilterAlgo, size_in_bytes: *mut usize) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetConvolutionNdDescriptor(conv_desc, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) }.assert_success();
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterWorkspaceSize(handle, x_desc, dy_desc, conv_desc, dw_desc, algo, size_in_bytes) }.into_result("cudnnGetConvolutionBackwardFilterWorkspaceSize")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetConvolutionNdDescriptor(conv_desc, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) };
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterWorkspaceSize(handle, x_desc, dy_desc, conv_desc, dw_desc, algo, size_in_bytes) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_convolution2d_descriptor(conv_desc: *mut _ConvolutionDescriptorStruct, pad_h: i32, pad_w: i32, u: i32, v: i32, dilatation_h: i32, dilatation_w: i32, mode: CudnnConvolutionMode, compute_type: CudnnDataType) {
+pub fn cudnn_convolution_backward_filter(handle: *mut _CudnnStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, conv_desc: *const _ConvolutionDescriptorStruct, algo: CudnnConvolutionBwdFilterAlgo, workspace: *mut c_void, workspace_size_in_bytes: usize, beta: *const c_void, dw_desc: *const _FilterDescriptorStruct, dw: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetConvolution2dDescriptor(conv_desc, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, compute_type) }.assert_success();
+        unsafe { Backend::cudnnConvolutionBackwardFilter(handle, alpha, x_desc, x, dy_desc, dy, conv_desc, algo, workspace, workspace_size_in_bytes, beta, dw_desc, dw) }.into_result("cudnnConvolutionBackwardFilter")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetConvolution2dDescriptor(conv_desc, pad_h, pad_w, u, v, dilatation_h, dilatation_w, mode, compute_type) };
+        unsafe { Backend::cudnnConvolutionBackwardFilter(handle, alpha, x_desc, x, dy_desc, dy, conv_desc, algo, workspace, workspace_size_in_bytes, beta, dw_desc, dw) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_convolution_group_count(conv_desc: *mut _ConvolutionDescriptorStruct, group_count: i32) {
+pub fn cudnn_convolution_backward_bias(handle: *mut _CudnnStruct, alpha: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, beta: *const c_void, db_desc: *const _TensorDescriptorStruct, db: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetConvolutionGroupCount(conv_desc, group_count) }.assert_success();
+        unsafe { Backend::cudnnConvolutionBackwardBias(handle, alpha, dy_desc, dy, beta, db_desc, db) }.into_result("cudnnConvolutionBackwardBias")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetConvolutionGroupCount(conv_desc, group_count) };
+        unsafe { Backend::cudnnConvolutionBackwardBias(handle, alpha, dy_desc, dy, beta, db_desc, db) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_convolution_math_type(conv_desc: *mut _ConvolutionDescriptorStruct, math_type: CudnnMathType) {
+pub fn cudnn_get_convolution_forward_algorithm_max_count(handle: *mut _CudnnStruct, count: *mut i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetConvolutionMathType(conv_desc, math_type) }.assert_success();
+        unsafe { Backend::cudnnGetConvolutionForwardAlgorithmMaxCount(handle, count) }.into_result("cudnnGetConvolutionForwardAlgorithmMaxCount")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetConvolutionMathType(conv_desc, math_type) };
+        unsafe { Backend::cudnnGetConvolutionForwardAlgorithmMaxCount(handle, count) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_convolution_nd_descriptor(conv_desc: *const _ConvolutionDescriptorStruct, array_length_requested: i32, array_length: *mut i32, pad_a: *mut i32, filter_stride_a: *mut i32, dilatation_a: *mut i32, mode: *mut CudnnConvolutionMode, data_type: *mut CudnnDataType) {
+pub fn cudnn_get_convolution_forward_algorithm_v7(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, w_desc: *const _FilterDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, y_desc: *const _TensorDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetConvolutionNdDescriptor(conv_desc, array_length_requested, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) }.assert_success();
+        unsafe { Backend::cudnnGetConvolutionForwardAlgorithm_v7(handle, x_desc, w_desc, conv_desc, y_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnGetConvolutionForwardAlgorithm_v7")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetConvolutionNdDescriptor(conv_desc, array_length_requested, array_length, pad_a, filter_stride_a, dilatation_a, mode, data_type) };
+        unsafe { Backend::cudnnGetConvolutionForwardAlgorithm_v7(handle, x_desc, w_desc, conv_desc, y_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_convolution_forward_workspace_size(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, w_desc: *const _FilterDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, y_desc: *const _TensorDescriptorStruct, algo: CudnnConvolutionFwdAlgo, size_in_bytes: *mut usize) {
+pub fn cudnn_find_convolution_forward_algorithm(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, w_desc: *const _FilterDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, y_desc: *const _TensorDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetConvolutionForwardWorkspaceSize(handle, x_desc, w_desc, conv_desc, y_desc, algo, size_in_bytes) }.assert_success();
+        unsafe { Backend::cudnnFindConvolutionForwardAlgorithm(handle, x_desc, w_desc, conv_desc, y_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnFindConvolutionForwardAlgorithm")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetConvolutionForwardWorkspaceSize(handle, x_desc, w_desc, conv_desc, y_desc, algo, size_in_bytes) };
+        unsafe { Backend::cudnnFindConvolutionForwardAlgorithm(handle, x_desc, w_desc, conv_desc, y_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_convolution_forward(handle: *mut _CudnnStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, w_desc: *const _FilterDescriptorStruct, w: *const c_void, conv_desc: *const _ConvolutionDescriptorStruct, algo: CudnnConvolutionFwdAlgo, workspace: *mut c_void, workspace_size_in_bytes: usize, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) {
+pub fn cudnn_get_convolution_backward_data_algorithm_max_count(handle: *mut _CudnnStruct, count: *mut i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnConvolutionForward(handle, alpha, x_desc, x, w_desc, w, conv_desc, algo, workspace, workspace_size_in_bytes, beta, y_desc, y) }.assert_success();
+        unsafe { Backend::cudnnGetConvolutionBackwardDataAlgorithmMaxCount(handle, count) }.into_result("cudnnGetConvolutionBackwardDataAlgorithmMaxCount")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnConvolutionForward(handle, alpha, x_desc, x, w_desc, w, conv_desc, algo, workspace, workspace_size_in_bytes, beta, y_desc, y) };
+        unsafe { Backend::cudnnGetConvolutionBackwardDataAlgorithmMaxCount(handle, count) };
+        Ok(())
     }
 }
 
+#[inline]
+pub fn cudnn_get_convolution_backward_data_algorithm_v7(handle: *mut _CudnnStruct, w_desc: *const _FilterDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dx_desc: *const _TensorDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionBackwardDataAlgorithm_v7(handle, w_desc, dy_desc, conv_desc, dx_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnGetConvolutionBackwardDataAlgorithm_v7")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionBackwardDataAlgorithm_v7(handle, w_desc, dy_desc, conv_desc, dx_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_find_convolution_backward_data_algorithm(handle: *mut _CudnnStruct, w_desc: *const _FilterDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dx_desc: *const _TensorDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnFindConvolutionBackwardDataAlgorithm(handle, w_desc, dy_desc, conv_desc, dx_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnFindConvolutionBackwardDataAlgorithm")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnFindConvolutionBackwardDataAlgorithm(handle, w_desc, dy_desc, conv_desc, dx_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_convolution_backward_filter_algorithm_max_count(handle: *mut _CudnnStruct, count: *mut i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterAlgorithmMaxCount(handle, count) }.into_result("cudnnGetConvolutionBackwardFilterAlgorithmMaxCount")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterAlgorithmMaxCount(handle, count) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_convolution_backward_filter_algorithm_v7(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dw_desc: *const _FilterDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterAlgorithm_v7(handle, x_desc, dy_desc, conv_desc, dw_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnGetConvolutionBackwardFilterAlgorithm_v7")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionBackwardFilterAlgorithm_v7(handle, x_desc, dy_desc, conv_desc, dw_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_find_convolution_backward_filter_algorithm(handle: *mut _CudnnStruct, x_desc: *const _TensorDescriptorStruct, dy_desc: *const _TensorDescriptorStruct, conv_desc: *const _ConvolutionDescriptorStruct, dw_desc: *const _FilterDescriptorStruct, requested_algo_count: i32, returned_algo_count: *mut i32, perf_results: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnFindConvolutionBackwardFilterAlgorithm(handle, x_desc, dy_desc, conv_desc, dw_desc, requested_algo_count, returned_algo_count, perf_results) }.into_result("cudnnFindConvolutionBackwardFilterAlgorithm")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnFindConvolutionBackwardFilterAlgorithm(handle, x_desc, dy_desc, conv_desc, dw_desc, requested_algo_count, returned_algo_count, perf_results) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_convolution_nd_forward_output_dim(conv_desc: *const _ConvolutionDescriptorStruct, input_tensor_desc: *const _TensorDescriptorStruct, filter_desc: *const _FilterDescriptorStruct, nb_dims: i32, tensor_output_dim_a: *mut i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionNdForwardOutputDim(conv_desc, input_tensor_desc, filter_desc, nb_dims, tensor_output_dim_a) }.into_result("cudnnGetConvolutionNdForwardOutputDim")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionNdForwardOutputDim(conv_desc, input_tensor_desc, filter_desc, nb_dims, tensor_output_dim_a) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_convolution_group_count(conv_desc: *const _ConvolutionDescriptorStruct, group_count: *mut i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionGroupCount(conv_desc, group_count) }.into_result("cudnnGetConvolutionGroupCount")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionGroupCount(conv_desc, group_count) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_convolution_math_type(conv_desc: *const _ConvolutionDescriptorStruct, math_type: *mut CudnnMathType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetConvolutionMathType(conv_desc, math_type) }.into_result("cudnnGetConvolutionMathType")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetConvolutionMathType(conv_desc, math_type) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_convolution_bias_activation_forward(handle: *mut _CudnnStruct, alpha1: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, w_desc: *const _FilterDescriptorStruct, w: *const c_void, conv_desc: *const _ConvolutionDescriptorStruct, algo: CudnnConvolutionFwdAlgo, work_space: *mut c_void, work_space_size_in_bytes: usize, alpha2: *const c_void, z_desc: *const _TensorDescriptorStruct, z: *const c_void, bias_desc: *const _TensorDescriptorStruct, bias: *const c_void, activation_desc: *const _ActivationDescriptorStruct, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnConvolutionBiasActivationForward(handle, alpha1, x_desc, x, w_desc, w, conv_desc, algo, work_space, work_space_size_in_bytes, alpha2, z_desc, z, bias_desc, bias, activation_desc, y_desc, y) }.into_result("cudnnConvolutionBiasActivationForward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnConvolutionBiasActivationForward(handle, alpha1, x_desc, x, w_desc, w, conv_desc, algo, work_space, work_space_size_in_bytes, alpha2, z_desc, z, bias_desc, bias, activation_desc, y_desc, y) };
+        Ok(())
+    }
+}
+
+
 
 
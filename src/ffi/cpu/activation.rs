@@ -0,0 +1,85 @@
+use super::*;
+use reference;
+
+
+
+impl Opaque for _ActivationDescriptorStruct {
+    type Data = Activation;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Activation {
+    pub mode: CudnnActivationMode,
+    pub nan_opt: CudnnNanPropagation,
+    pub coef: f64,
+}
+
+impl Default for Activation {
+    fn default() -> Activation {
+        Activation { mode: CudnnActivationMode::Relu, nan_opt: CudnnNanPropagation::NotPropagate, coef: 0.0 }
+    }
+}
+
+
+impl ActivationApi for CudnnCpu {
+
+    unsafe fn cudnnCreateActivationDescriptor(activationDesc: *mut*mut _ActivationDescriptorStruct) -> CudnnStatus {
+        create(activationDesc)
+    }
+
+    unsafe fn cudnnDestroyActivationDescriptor(activationDesc: *mut _ActivationDescriptorStruct) -> CudnnStatus {
+        destroy(activationDesc)
+    }
+
+    unsafe fn cudnnSetActivationDescriptor(activationDesc: *mut _ActivationDescriptorStruct, mode: CudnnActivationMode,
+                                           reluNanOpt: CudnnNanPropagation, coef: f64) -> CudnnStatus {
+        run(|| {
+            *data_mut(activationDesc)? = Activation { mode, nan_opt: reluNanOpt, coef };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetActivationDescriptor(activationDesc: *const _ActivationDescriptorStruct, mode: *mut CudnnActivationMode,
+                                           reluNanOpt: *mut CudnnNanPropagation, coef: *mut f64) -> CudnnStatus {
+        run(|| {
+            let activation = data(activationDesc)?;
+            set(mode, activation.mode);
+            set(reluNanOpt, activation.nan_opt);
+            set(coef, activation.coef);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnActivationForward(_handle: *const _CudnnStruct, activationDesc: *const _ActivationDescriptorStruct,
+                                     alpha: *const c_void, xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                     beta: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let activation = data(activationDesc)?;
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let x = x_desc.read(x)?;
+            let mut output = y_desc.read(y)?;
+            reference::activation_forward(activation.mode, activation.coef, alpha, &x, beta, &mut output);
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnActivationBackward(_handle: *const _CudnnStruct, activationDesc: *const _ActivationDescriptorStruct,
+                                      alpha: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *const c_void,
+                                      dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                      xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                      beta: *const c_void, dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let activation = data(activationDesc)?;
+            let (y_desc, dy_desc, x_desc, dx_desc) = (data(yDesc)?, data(dyDesc)?, data(xDesc)?, data(dxDesc)?);
+            check(y_desc.dims == dy_desc.dims && y_desc.dims == x_desc.dims && y_desc.dims == dx_desc.dims)?;
+            let (alpha, beta) = (dx_desc.scaling(alpha)?, dx_desc.scaling(beta)?);
+            let (y, dy, x) = (y_desc.read(y)?, dy_desc.read(dy)?, x_desc.read(x)?);
+            let mut output = dx_desc.read(dx)?;
+            reference::activation_backward(activation.mode, activation.coef, alpha, &y, &dy, &x, beta, &mut output);
+            dx_desc.write(dx, &output)
+        })
+    }
+
+}
@@ -0,0 +1,147 @@
+use super::*;
+use reference;
+
+
+
+/// Packed descriptor of the parameters of a batch normalization of `x`
+fn parameters(x: &Tensor, mode: CudnnBatchNormMode) -> Result<Tensor, CudnnStatus> {
+    check(x.dims.len() >= 3)?;
+    let dims = x.dims.iter().enumerate().map(|(i, &d)| match (i, mode) {
+        (0, _) => 1,
+        (1, _) | (_, CudnnBatchNormMode::PerActivation) => d,
+        _ => 1,
+    }).collect::<Vec<_>>();
+    let data_type = match x.data_type {
+        CudnnDataType::Half => CudnnDataType::Float,
+        data_type => data_type,
+    };
+    Ok(Tensor { data_type, strides: format_strides(CudnnTensorFormat::Nchw, &dims), dims })
+}
+
+unsafe fn check_parameters(x: &Tensor, mode: CudnnBatchNormMode, desc: *const _TensorDescriptorStruct) -> Result<&Tensor, CudnnStatus> {
+    let desc = data(desc)?;
+    check(desc.dims == parameters(x, mode)?.dims)?;
+    Ok(desc)
+}
+
+/// Parameters at `ptr`, or `None` when it's null
+unsafe fn read_optional(desc: &Tensor, ptr: *const c_void) -> Result<Option<Vec<f32>>, CudnnStatus> {
+    if ptr.is_null() { Ok(None) } else { desc.read(ptr).map(Some) }
+}
+
+unsafe fn write_optional(desc: &Tensor, ptr: *mut c_void, values: &[f32]) -> Result<(), CudnnStatus> {
+    if ptr.is_null() { Ok(()) } else { desc.write(ptr, values) }
+}
+
+
+impl BatchNormalizationApi for CudnnCpu {
+
+    unsafe fn cudnnDeriveBNTensorDescriptor(derivedBnDesc: *mut _TensorDescriptorStruct, xDesc: *const _TensorDescriptorStruct,
+                                            mode: CudnnBatchNormMode) -> CudnnStatus {
+        run(|| {
+            *data_mut(derivedBnDesc)? = parameters(data(xDesc)?, mode)?;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnBatchNormalizationForwardTraining(_handle: *mut _CudnnStruct, mode: CudnnBatchNormMode,
+                                                     alpha: *const c_void, beta: *const c_void,
+                                                     xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                                     yDesc: *const _TensorDescriptorStruct, y: *mut c_void,
+                                                     bnScaleBiasMeanVarDesc: *const _TensorDescriptorStruct,
+                                                     bnScale: *const c_void, bnBias: *const c_void,
+                                                     exponentialAverageFactor: f64,
+                                                     resultRunningMean: *mut c_void, resultRunningVariance: *mut c_void,
+                                                     epsilon: f64,
+                                                     resultSaveMean: *mut c_void, resultSaveInvVariance: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims)?;
+            let params_desc = check_parameters(x_desc, mode, bnScaleBiasMeanVarDesc)?;
+            let nb_params = params_desc.len();
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let (x, scale, bias) = (x_desc.read(x)?, params_desc.read(bnScale)?, params_desc.read(bnBias)?);
+            let mut running_mean = read_optional(params_desc, resultRunningMean)?.unwrap_or(vec![0.0; nb_params]);
+            let mut running_variance = read_optional(params_desc, resultRunningVariance)?.unwrap_or(vec![0.0; nb_params]);
+            let mut save_mean = vec![0.0; nb_params];
+            let mut save_inv_variance = vec![0.0; nb_params];
+            let mut output = y_desc.read(y)?;
+            reference::batch_normalization_forward_training(mode, alpha, beta, &x_desc.dims, &x, &mut output, &scale, &bias,
+                                                            exponentialAverageFactor, &mut running_mean, &mut running_variance,
+                                                            epsilon, &mut save_mean, &mut save_inv_variance);
+            write_optional(params_desc, resultRunningMean, &running_mean)?;
+            write_optional(params_desc, resultRunningVariance, &running_variance)?;
+            write_optional(params_desc, resultSaveMean, &save_mean)?;
+            write_optional(params_desc, resultSaveInvVariance, &save_inv_variance)?;
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnBatchNormalizationForwardInference(_handle: *mut _CudnnStruct, mode: CudnnBatchNormMode,
+                                                      alpha: *const c_void, beta: *const c_void,
+                                                      xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                                      yDesc: *const _TensorDescriptorStruct, y: *mut c_void,
+                                                      bnScaleBiasMeanVarDesc: *const _TensorDescriptorStruct,
+                                                      bnScale: *const c_void, bnBias: *const c_void,
+                                                      estimatedMean: *const c_void, estimatedVariance: *const c_void,
+                                                      epsilon: f64) -> CudnnStatus {
+        run(|| {
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims)?;
+            let params_desc = check_parameters(x_desc, mode, bnScaleBiasMeanVarDesc)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let (x, scale, bias) = (x_desc.read(x)?, params_desc.read(bnScale)?, params_desc.read(bnBias)?);
+            let (mean, variance) = (params_desc.read(estimatedMean)?, params_desc.read(estimatedVariance)?);
+            let mut output = y_desc.read(y)?;
+            reference::batch_normalization_forward_inference(mode, alpha, beta, &x_desc.dims, &x, &mut output,
+                                                             &scale, &bias, &mean, &variance, epsilon);
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnBatchNormalizationBackward(_handle: *mut _CudnnStruct, mode: CudnnBatchNormMode,
+                                              alphaDataDiff: *const c_void, betaDataDiff: *const c_void,
+                                              alphaParamDiff: *const c_void, betaParamDiff: *const c_void,
+                                              xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                              dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                              dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void,
+                                              dBnScaleBiasDesc: *const _TensorDescriptorStruct,
+                                              bnScale: *const c_void, dBnScaleResult: *mut c_void, dBnBiasResult: *mut c_void,
+                                              epsilon: f64,
+                                              savedMean: *const c_void, savedInvVariance: *const c_void) -> CudnnStatus {
+        run(|| {
+            let (x_desc, dy_desc, dx_desc) = (data(xDesc)?, data(dyDesc)?, data(dxDesc)?);
+            check(x_desc.dims == dy_desc.dims && x_desc.dims == dx_desc.dims)?;
+            let params_desc = check_parameters(x_desc, mode, dBnScaleBiasDesc)?;
+            let nb_params = params_desc.len();
+            let (alpha_data, beta_data) = (dx_desc.scaling(alphaDataDiff)?, dx_desc.scaling(betaDataDiff)?);
+            let (alpha_param, beta_param) = (params_desc.scaling(alphaParamDiff)?, params_desc.scaling(betaParamDiff)?);
+            let (x, dy, scale) = (x_desc.read(x)?, dy_desc.read(dy)?, params_desc.read(bnScale)?);
+            let (saved_mean, saved_inv_variance) = match (savedMean.is_null(), savedInvVariance.is_null()) {
+                (false, false) => (params_desc.read(savedMean)?, params_desc.read(savedInvVariance)?),
+                (true, true) => {
+                    // Recomputes the statistics of the batch, as a forward pass would have saved them
+                    let mut mean = vec![0.0; nb_params];
+                    let mut inv_variance = vec![0.0; nb_params];
+                    reference::batch_normalization_forward_training(mode, 1.0, 0.0, &x_desc.dims, &x, &mut vec![0.0; x.len()],
+                                                                    &scale, &vec![0.0; nb_params], 0.0,
+                                                                    &mut vec![0.0; nb_params], &mut vec![0.0; nb_params],
+                                                                    epsilon, &mut mean, &mut inv_variance);
+                    (mean, inv_variance)
+                },
+                _ => return Err(CudnnStatus::BadParam),
+            };
+            let mut output = dx_desc.read(dx)?;
+            let mut scale_diff = params_desc.read(dBnScaleResult)?;
+            let mut bias_diff = params_desc.read(dBnBiasResult)?;
+            reference::batch_normalization_backward(mode, alpha_data, beta_data, alpha_param, beta_param,
+                                                    &x_desc.dims, &x, &dy, &mut output,
+                                                    &scale, &mut scale_diff, &mut bias_diff,
+                                                    &saved_mean, &saved_inv_variance);
+            params_desc.write(dBnScaleResult, &scale_diff)?;
+            params_desc.write(dBnBiasResult, &bias_diff)?;
+            dx_desc.write(dx, &output)
+        })
+    }
+
+}
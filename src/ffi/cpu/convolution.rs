@@ -0,0 +1,380 @@
+use super::*;
+use reference;
+use CuConvolutionDescriptorInfo;
+
+
+
+impl Opaque for _ConvolutionDescriptorStruct {
+    type Data = Convolution;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Convolution {
+    pub info: CuConvolutionDescriptorInfo,
+}
+
+impl Default for Convolution {
+    fn default() -> Convolution {
+        Convolution {
+            info: CuConvolutionDescriptorInfo {
+                array_length: 0,
+                pads: Vec::new(),
+                filter_strides: Vec::new(),
+                dilatations: Vec::new(),
+                mode: CudnnConvolutionMode::CrossCorrelation,
+                data_type: CudnnDataType::Float,
+                group_count: 1,
+                math_type: CudnnMathType::Default,
+            }
+        }
+    }
+}
+
+impl Convolution {
+
+    fn set(&mut self, pads: &[i32], filter_strides: &[i32], dilatations: &[i32],
+           mode: CudnnConvolutionMode, data_type: CudnnDataType) -> Result<(), CudnnStatus> {
+        check(pads.iter().all(|&x| x >= 0))?;
+        check(filter_strides.iter().chain(dilatations.iter()).all(|&x| x > 0))?;
+        self.info.array_length = pads.len() as i32;
+        self.info.pads = pads.to_vec();
+        self.info.filter_strides = filter_strides.to_vec();
+        self.info.dilatations = dilatations.to_vec();
+        self.info.mode = mode;
+        self.info.data_type = data_type;
+        Ok(())
+    }
+
+    fn output_dims(&self, x_dims: &[i32], w_dims: &[i32]) -> Result<Vec<i32>, CudnnStatus> {
+        self.info.forward_output_dims(x_dims, w_dims).map_err(|_| CudnnStatus::BadParam)
+    }
+
+    /// `alpha * convolution(x) + beta * y`, checking that `y` has the dimensions of the output
+    unsafe fn forward(&self, alpha: f32, x_desc: &Tensor, x: *const c_void, w_desc: &Filter, w: *const c_void,
+                      beta: f32, y_desc: &Tensor, y: *const c_void) -> Result<Vec<f32>, CudnnStatus> {
+        check(self.output_dims(&x_desc.dims, &w_desc.dims)? == y_desc.dims)?;
+        let (x, w) = (x_desc.read(x)?, w_desc.read(w)?);
+        let mut output = if beta == 0.0 { vec![0.0; y_desc.len()] } else { y_desc.read(y)? };
+        reference::convolution_forward(&self.info, alpha, &x_desc.dims, &x, &w_desc.dims, &w, beta, &y_desc.dims, &mut output);
+        Ok(output)
+    }
+
+}
+
+
+const FWD_ALGOS: [CudnnConvolutionFwdAlgo; 8] = [
+    CudnnConvolutionFwdAlgo::ImplicitGemm, CudnnConvolutionFwdAlgo::ImplicitPrecompGemm, CudnnConvolutionFwdAlgo::Gemm,
+    CudnnConvolutionFwdAlgo::Direct, CudnnConvolutionFwdAlgo::Fft, CudnnConvolutionFwdAlgo::FftTiling,
+    CudnnConvolutionFwdAlgo::Winograd, CudnnConvolutionFwdAlgo::WinogradNonfused,
+];
+
+const BWD_DATA_ALGOS: [CudnnConvolutionBwdDataAlgo; 6] = [
+    CudnnConvolutionBwdDataAlgo::Algo0, CudnnConvolutionBwdDataAlgo::Algo1, CudnnConvolutionBwdDataAlgo::Fft,
+    CudnnConvolutionBwdDataAlgo::FftTiling, CudnnConvolutionBwdDataAlgo::Winograd, CudnnConvolutionBwdDataAlgo::WinogradNonfused,
+];
+
+const BWD_FILTER_ALGOS: [CudnnConvolutionBwdFilterAlgo; 7] = [
+    CudnnConvolutionBwdFilterAlgo::Algo0, CudnnConvolutionBwdFilterAlgo::Algo1, CudnnConvolutionBwdFilterAlgo::Fft,
+    CudnnConvolutionBwdFilterAlgo::Algo3, CudnnConvolutionBwdFilterAlgo::Winograd, CudnnConvolutionBwdFilterAlgo::WinogradNonfused,
+    CudnnConvolutionBwdFilterAlgo::FftTiling,
+];
+
+/// Reports every algorithm as a deterministic success, needing no workspace, in enum order.
+unsafe fn algorithms<A: Copy>(algos: &[A], convDesc: *const _ConvolutionDescriptorStruct,
+                              requestedAlgoCount: i32, returnedAlgoCount: *mut i32,
+                              perfResults: *mut _ConvolutionAlgoPerfStruct<A>) -> CudnnStatus {
+    run(|| {
+        let convolution = data(convDesc)?;
+        check(requestedAlgoCount > 0 && !returnedAlgoCount.is_null() && !perfResults.is_null())?;
+        let count = algos.len().min(requestedAlgoCount as usize);
+        for (i, &algo) in algos[..count].iter().enumerate() {
+            let mut perf = _ConvolutionAlgoPerfStruct::new(algo);
+            perf.status = CudnnStatus::Success;
+            perf.time = 0.0;
+            perf.determinism = CudnnDeterminism::Deterministic;
+            perf.math_type = convolution.info.math_type;
+            *perfResults.add(i) = perf;
+        }
+        *returnedAlgoCount = count as i32;
+        Ok(())
+    })
+}
+
+unsafe fn no_workspace(sizeInBytes: *mut usize) -> CudnnStatus {
+    run(|| {
+        check(!sizeInBytes.is_null())?;
+        *sizeInBytes = 0;
+        Ok(())
+    })
+}
+
+
+impl ConvolutionApi for CudnnCpu {
+
+    unsafe fn cudnnCreateConvolutionDescriptor(convDesc: *mut*mut _ConvolutionDescriptorStruct) -> CudnnStatus {
+        create(convDesc)
+    }
+
+    unsafe fn cudnnDestroyConvolutionDescriptor(convDesc: *mut _ConvolutionDescriptorStruct) -> CudnnStatus {
+        destroy(convDesc)
+    }
+
+    unsafe fn cudnnSetConvolutionNdDescriptor(convDesc: *mut _ConvolutionDescriptorStruct, arrayLength: i32,
+                                              padA: *const i32, filterStrideA: *const i32, dilatationA: *const i32,
+                                              mode: CudnnConvolutionMode, dataType: CudnnDataType) -> CudnnStatus {
+        run(|| {
+            check(arrayLength > 0 && arrayLength <= 6)?;
+            data_mut(convDesc)?.set(array(padA, arrayLength)?, array(filterStrideA, arrayLength)?, array(dilatationA, arrayLength)?,
+                                    mode, dataType)
+        })
+    }
+
+    unsafe fn cudnnSetConvolution2dDescriptor(convDesc: *mut _ConvolutionDescriptorStruct, pad_h: i32, pad_w: i32,
+                                              u: i32, v: i32, dilatation_h: i32, dilatation_w: i32,
+                                              mode: CudnnConvolutionMode, computeType: CudnnDataType) -> CudnnStatus {
+        run(|| {
+            data_mut(convDesc)?.set(&[pad_h, pad_w], &[u, v], &[dilatation_h, dilatation_w], mode, computeType)
+        })
+    }
+
+    unsafe fn cudnnSetConvolutionGroupCount(convDesc: *mut _ConvolutionDescriptorStruct, groupCount: i32) -> CudnnStatus {
+        run(|| {
+            check(groupCount > 0)?;
+            data_mut(convDesc)?.info.group_count = groupCount;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnSetConvolutionMathType(convDesc: *mut _ConvolutionDescriptorStruct, math_type: CudnnMathType) -> CudnnStatus {
+        run(|| {
+            data_mut(convDesc)?.info.math_type = math_type;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionNdDescriptor(convDesc: *const _ConvolutionDescriptorStruct, arrayLengthRequested: i32,
+                                              arrayLength: *mut i32, padA: *mut i32, filterStrideA: *mut i32, dilatationA: *mut i32,
+                                              mode: *mut CudnnConvolutionMode, dataType: *mut CudnnDataType) -> CudnnStatus {
+        run(|| {
+            let info = &data(convDesc)?.info;
+            set(arrayLength, info.array_length);
+            set_array(padA, arrayLengthRequested, &info.pads);
+            set_array(filterStrideA, arrayLengthRequested, &info.filter_strides);
+            set_array(dilatationA, arrayLengthRequested, &info.dilatations);
+            set(mode, info.mode);
+            set(dataType, info.data_type);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionForwardWorkspaceSize(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                      _wDesc: *const _FilterDescriptorStruct, _convDesc: *const _ConvolutionDescriptorStruct,
+                                                      _yDesc: *const _TensorDescriptorStruct, _algo: CudnnConvolutionFwdAlgo,
+                                                      sizeInBytes: *mut usize) -> CudnnStatus {
+        no_workspace(sizeInBytes)
+    }
+
+    unsafe fn cudnnConvolutionForward(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                                      xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                      wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                      convDesc: *const _ConvolutionDescriptorStruct, _algo: CudnnConvolutionFwdAlgo,
+                                      _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                      beta: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let y_desc = data(yDesc)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let output = data(convDesc)?.forward(alpha, data(xDesc)?, x, data(wDesc)?, w, beta, y_desc, y)?;
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardDataWorkspaceSize(_handle: *mut _CudnnStruct, _wDesc: *const _FilterDescriptorStruct,
+                                                           _dyDesc: *const _TensorDescriptorStruct, _convDesc: *const _ConvolutionDescriptorStruct,
+                                                           _dxDesc: *const _TensorDescriptorStruct, _algo: CudnnConvolutionBwdDataAlgo,
+                                                           sizeInBytes: *mut usize) -> CudnnStatus {
+        no_workspace(sizeInBytes)
+    }
+
+    unsafe fn cudnnConvolutionBackwardData(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                                           wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                           dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                           convDesc: *const _ConvolutionDescriptorStruct, _algo: CudnnConvolutionBwdDataAlgo,
+                                           _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                           beta: *const c_void, dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let convolution = data(convDesc)?;
+            let (w_desc, dy_desc, dx_desc) = (data(wDesc)?, data(dyDesc)?, data(dxDesc)?);
+            check(convolution.output_dims(&dx_desc.dims, &w_desc.dims)? == dy_desc.dims)?;
+            let (alpha, beta) = (dx_desc.scaling(alpha)?, dx_desc.scaling(beta)?);
+            let (w, dy) = (w_desc.read(w)?, dy_desc.read(dy)?);
+            let mut output = dx_desc.read(dx)?;
+            reference::convolution_backward_data(&convolution.info, alpha, &w_desc.dims, &w, &dy_desc.dims, &dy,
+                                                 beta, &dx_desc.dims, &mut output);
+            dx_desc.write(dx, &output)
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardFilterWorkspaceSize(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                             _dyDesc: *const _TensorDescriptorStruct, _convDesc: *const _ConvolutionDescriptorStruct,
+                                                             _dwDesc: *const _FilterDescriptorStruct, _algo: CudnnConvolutionBwdFilterAlgo,
+                                                             sizeInBytes: *mut usize) -> CudnnStatus {
+        no_workspace(sizeInBytes)
+    }
+
+    unsafe fn cudnnConvolutionBackwardFilter(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                                             xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                             dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                             convDesc: *const _ConvolutionDescriptorStruct, _algo: CudnnConvolutionBwdFilterAlgo,
+                                             _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                             beta: *const c_void, dwDesc: *const _FilterDescriptorStruct, dw: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let convolution = data(convDesc)?;
+            let (x_desc, dy_desc, dw_desc) = (data(xDesc)?, data(dyDesc)?, data(dwDesc)?);
+            check(convolution.output_dims(&x_desc.dims, &dw_desc.dims)? == dy_desc.dims)?;
+            let (alpha, beta) = (scaling(dw_desc.data_type, alpha)?, scaling(dw_desc.data_type, beta)?);
+            let (x, dy) = (x_desc.read(x)?, dy_desc.read(dy)?);
+            let mut output = dw_desc.read(dw)?;
+            reference::convolution_backward_filter(&convolution.info, alpha, &x_desc.dims, &x, &dy_desc.dims, &dy,
+                                                   beta, &dw_desc.dims, &mut output);
+            dw_desc.write(dw, &output)
+        })
+    }
+
+    unsafe fn cudnnConvolutionBackwardBias(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                                           dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                           beta: *const c_void, dbDesc: *const _TensorDescriptorStruct, db: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (dy_desc, db_desc) = (data(dyDesc)?, data(dbDesc)?);
+            check(dy_desc.dims.len() >= 2 && db_desc.dims.len() == dy_desc.dims.len())?;
+            check(db_desc.dims.iter().enumerate().all(|(i, &d)| d == if i == 1 { dy_desc.dims[1] } else { 1 }))?;
+            let (alpha, beta) = (db_desc.scaling(alpha)?, db_desc.scaling(beta)?);
+            let dy = dy_desc.read(dy)?;
+            let mut output = db_desc.read(db)?;
+            reference::convolution_backward_bias(alpha, &dy_desc.dims, &dy, beta, &mut output);
+            db_desc.write(db, &output)
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionForwardAlgorithmMaxCount(_handle: *mut _CudnnStruct, count: *mut i32) -> CudnnStatus {
+        run(|| {
+            check(!count.is_null())?;
+            *count = FWD_ALGOS.len() as i32;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionForwardAlgorithm_v7(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                     _wDesc: *const _FilterDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                     _yDesc: *const _TensorDescriptorStruct, requestedAlgoCount: i32,
+                                                     returnedAlgoCount: *mut i32,
+                                                     perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>) -> CudnnStatus {
+        algorithms(&FWD_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnFindConvolutionForwardAlgorithm(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                   _wDesc: *const _FilterDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                   _yDesc: *const _TensorDescriptorStruct, requestedAlgoCount: i32,
+                                                   returnedAlgoCount: *mut i32,
+                                                   perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionFwdAlgo>) -> CudnnStatus {
+        algorithms(&FWD_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardDataAlgorithmMaxCount(_handle: *mut _CudnnStruct, count: *mut i32) -> CudnnStatus {
+        run(|| {
+            check(!count.is_null())?;
+            *count = BWD_DATA_ALGOS.len() as i32;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardDataAlgorithm_v7(_handle: *mut _CudnnStruct, _wDesc: *const _FilterDescriptorStruct,
+                                                          _dyDesc: *const _TensorDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                          _dxDesc: *const _TensorDescriptorStruct, requestedAlgoCount: i32,
+                                                          returnedAlgoCount: *mut i32,
+                                                          perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>) -> CudnnStatus {
+        algorithms(&BWD_DATA_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnFindConvolutionBackwardDataAlgorithm(_handle: *mut _CudnnStruct, _wDesc: *const _FilterDescriptorStruct,
+                                                        _dyDesc: *const _TensorDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                        _dxDesc: *const _TensorDescriptorStruct, requestedAlgoCount: i32,
+                                                        returnedAlgoCount: *mut i32,
+                                                        perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdDataAlgo>) -> CudnnStatus {
+        algorithms(&BWD_DATA_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardFilterAlgorithmMaxCount(_handle: *mut _CudnnStruct, count: *mut i32) -> CudnnStatus {
+        run(|| {
+            check(!count.is_null())?;
+            *count = BWD_FILTER_ALGOS.len() as i32;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionBackwardFilterAlgorithm_v7(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                            _dyDesc: *const _TensorDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                            _dwDesc: *const _FilterDescriptorStruct, requestedAlgoCount: i32,
+                                                            returnedAlgoCount: *mut i32,
+                                                            perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>) -> CudnnStatus {
+        algorithms(&BWD_FILTER_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnFindConvolutionBackwardFilterAlgorithm(_handle: *mut _CudnnStruct, _xDesc: *const _TensorDescriptorStruct,
+                                                          _dyDesc: *const _TensorDescriptorStruct, convDesc: *const _ConvolutionDescriptorStruct,
+                                                          _dwDesc: *const _FilterDescriptorStruct, requestedAlgoCount: i32,
+                                                          returnedAlgoCount: *mut i32,
+                                                          perfResults: *mut _ConvolutionAlgoPerfStruct<CudnnConvolutionBwdFilterAlgo>) -> CudnnStatus {
+        algorithms(&BWD_FILTER_ALGOS, convDesc, requestedAlgoCount, returnedAlgoCount, perfResults)
+    }
+
+    unsafe fn cudnnGetConvolutionNdForwardOutputDim(convDesc: *const _ConvolutionDescriptorStruct, inputTensorDesc: *const _TensorDescriptorStruct,
+                                                    filterDesc: *const _FilterDescriptorStruct, nbDims: i32, tensorOutputDimA: *mut i32) -> CudnnStatus {
+        run(|| {
+            let output = data(convDesc)?.output_dims(&data(inputTensorDesc)?.dims, &data(filterDesc)?.dims)?;
+            check(nbDims == output.len() as i32 && !tensorOutputDimA.is_null())?;
+            set_array(tensorOutputDimA, nbDims, &output);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionGroupCount(convDesc: *const _ConvolutionDescriptorStruct, groupCount: *mut i32) -> CudnnStatus {
+        run(|| {
+            set(groupCount, data(convDesc)?.info.group_count);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetConvolutionMathType(convDesc: *const _ConvolutionDescriptorStruct, mathType: *mut CudnnMathType) -> CudnnStatus {
+        run(|| {
+            set(mathType, data(convDesc)?.info.math_type);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnConvolutionBiasActivationForward(_handle: *mut _CudnnStruct, alpha1: *const c_void,
+                                                    xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                                    wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                                    convDesc: *const _ConvolutionDescriptorStruct, _algo: CudnnConvolutionFwdAlgo,
+                                                    _workSpace: *mut c_void, _workSpaceSizeInBytes: usize,
+                                                    alpha2: *const c_void, zDesc: *const _TensorDescriptorStruct, z: *const c_void,
+                                                    biasDesc: *const _TensorDescriptorStruct, bias: *const c_void,
+                                                    activationDesc: *const _ActivationDescriptorStruct,
+                                                    yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (z_desc, bias_desc, y_desc) = (data(zDesc)?, data(biasDesc)?, data(yDesc)?);
+            let activation = data(activationDesc)?;
+            check(z_desc.dims == y_desc.dims)?;
+            let (alpha1, alpha2) = (y_desc.scaling(alpha1)?, y_desc.scaling(alpha2)?);
+            let mut output = data(convDesc)?.forward(alpha1, data(xDesc)?, x, data(wDesc)?, w, 0.0, y_desc, y)?;
+            let bias = tensor::broadcast(&bias_desc.read(bias)?, &bias_desc.dims, &y_desc.dims)?;
+            let z = z_desc.read(z)?;
+            for i in 0..output.len() {
+                output[i] += alpha2 * z[i] + bias[i];
+            }
+            let mut activated = vec![0.0; output.len()];
+            reference::activation_forward(activation.mode, activation.coef, 1.0, &output, 0.0, &mut activated);
+            y_desc.write(y, &activated)
+        })
+    }
+
+}
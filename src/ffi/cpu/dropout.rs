@@ -0,0 +1,145 @@
+use super::*;
+
+
+
+impl Opaque for _DropoutDescriptorStruct {
+    type Data = Dropout;
+}
+
+/// The random generator is a splitmix64, whose 64 bits state lives in the `states` buffer given by the user.
+pub(super) struct Dropout {
+    pub dropout: f32,
+    pub states: *mut c_void,
+    pub seed: u64,
+}
+
+impl Default for Dropout {
+    fn default() -> Dropout {
+        Dropout { dropout: 0.0, states: ptr::null_mut(), seed: 0 }
+    }
+}
+
+const STATES_SIZE: usize = 8;
+
+impl Dropout {
+
+    fn set(&mut self, dropout: f32, states: *mut c_void, states_size: usize, seed: u64) -> Result<(), CudnnStatus> {
+        check(dropout >= 0.0 && dropout < 1.0)?;
+        check(states.is_null() || states_size >= STATES_SIZE)?;
+        *self = Dropout { dropout, states, seed };
+        Ok(())
+    }
+
+    /// Draws the masks of `len` elements, `true` meaning that the element is kept.
+    unsafe fn masks(&self, len: usize) -> Result<Vec<bool>, CudnnStatus> {
+        check(!self.states.is_null())?;
+        let mut state = ptr::read_unaligned(self.states as *const u64);
+        let masks = (0..len).map(|_| {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            (z >> 40) as f32 / (1u64 << 24) as f32 >= self.dropout
+        }).collect();
+        ptr::write_unaligned(self.states as *mut u64, state);
+        Ok(masks)
+    }
+
+    /// Applies `masks` to `values`, scaling the kept ones by 1/(1-dropout)
+    fn apply(&self, masks: &[bool], values: &[f32]) -> Vec<f32> {
+        let scale = 1.0 / (1.0 - self.dropout);
+        masks.iter().zip(values.iter()).map(|(&keep, &x)| if keep { x * scale } else { 0.0 }).collect()
+    }
+
+}
+
+
+impl DropoutApi for CudnnCpu {
+
+    unsafe fn cudnnCreateDropoutDescriptor(dropoutDesc: *mut*mut _DropoutDescriptorStruct) -> CudnnStatus {
+        create(dropoutDesc)
+    }
+
+    unsafe fn cudnnDestroyDropoutDescriptor(dropoutDesc: *mut _DropoutDescriptorStruct) -> CudnnStatus {
+        destroy(dropoutDesc)
+    }
+
+    unsafe fn cudnnSetDropoutDescriptor(dropoutDesc: *mut _DropoutDescriptorStruct, _handle: *mut _CudnnStruct,
+                                        dropout: f32, states: *mut c_void, stateSizeInBytes: usize, seed: u64) -> CudnnStatus {
+        run(|| {
+            data_mut(dropoutDesc)?.set(dropout, states, stateSizeInBytes, seed)?;
+            if !states.is_null() {
+                ptr::write_unaligned(states as *mut u64, seed);
+            }
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetDropoutDescriptor(dropoutDesc: *mut _DropoutDescriptorStruct, _handle: *mut _CudnnStruct,
+                                        dropout: *mut f32, states: *mut*mut c_void, seed: *mut u64) -> CudnnStatus {
+        run(|| {
+            let desc = data(dropoutDesc)?;
+            set(dropout, desc.dropout);
+            set(states, desc.states);
+            set(seed, desc.seed);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnDropoutGetReserveSpaceSize(xDesc: *const _TensorDescriptorStruct, sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            let x_desc = data(xDesc)?;
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = x_desc.len();
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnDropoutGetStatesSize(_handle: *mut _CudnnStruct, sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = STATES_SIZE;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnDropoutForward(_handle: *mut _CudnnStruct, dropoutDesc: *const _DropoutDescriptorStruct,
+                                  xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                  yDesc: *const _TensorDescriptorStruct, y: *mut c_void,
+                                  reserveSpace: *mut c_void, reserveSpaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let dropout = data(dropoutDesc)?;
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims)?;
+            check(!reserveSpace.is_null() && reserveSpaceSizeInBytes >= x_desc.len())?;
+            let x = x_desc.read(x)?;
+            let masks = dropout.masks(x.len())?;
+            for (i, &keep) in masks.iter().enumerate() {
+                *(reserveSpace as *mut u8).add(i) = keep as u8;
+            }
+            y_desc.write(y, &dropout.apply(&masks, &x))
+        })
+    }
+
+    unsafe fn cudnnDropoutBackward(_handle: *mut _CudnnStruct, dropoutDesc: *const _DropoutDescriptorStruct,
+                                   dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                   dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void,
+                                   reserveSpace: *mut c_void, reserveSpaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let dropout = data(dropoutDesc)?;
+            let (dy_desc, dx_desc) = (data(dyDesc)?, data(dxDesc)?);
+            check(dy_desc.dims == dx_desc.dims)?;
+            check(!reserveSpace.is_null() && reserveSpaceSizeInBytes >= dy_desc.len())?;
+            let dy = dy_desc.read(dy)?;
+            let masks = (0..dy.len()).map(|i| *(reserveSpace as *const u8).add(i) != 0).collect::<Vec<_>>();
+            dx_desc.write(dx, &dropout.apply(&masks, &dy))
+        })
+    }
+
+    unsafe fn cudnnRestoreDropoutDescriptor(dropoutDesc: *mut _DropoutDescriptorStruct, _handle: *mut _CudnnStruct,
+                                            dropout: f32, states: *mut c_void, stateSizeInBytes: usize, seed: u64) -> CudnnStatus {
+        run(|| data_mut(dropoutDesc)?.set(dropout, states, stateSizeInBytes, seed))
+    }
+
+}
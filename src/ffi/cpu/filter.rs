@@ -0,0 +1,90 @@
+use super::*;
+
+
+
+impl Opaque for _FilterDescriptorStruct {
+    type Data = Filter;
+}
+
+/// A filter is a tensor of dimensions [K, C, spatial...] which is packed in its format.
+#[derive(Clone, Debug)]
+pub(super) struct Filter {
+    pub data_type: CudnnDataType,
+    pub format: CudnnTensorFormat,
+    pub dims: Vec<i32>,
+}
+
+impl Default for Filter {
+    fn default() -> Filter {
+        Filter { data_type: CudnnDataType::Float, format: CudnnTensorFormat::Nchw, dims: Vec::new() }
+    }
+}
+
+impl Filter {
+
+    pub fn new(data_type: CudnnDataType, format: CudnnTensorFormat, dims: &[i32]) -> Result<Filter, CudnnStatus> {
+        check(!dims.is_empty() && dims.len() <= 8 && dims.iter().all(|&x| x > 0))?;
+        Ok(Filter { data_type, format, dims: dims.to_vec() })
+    }
+
+    pub fn len(&self) -> usize {
+        self.dims.iter().product::<i32>() as usize
+    }
+
+    pub fn tensor(&self) -> Tensor {
+        Tensor { data_type: self.data_type, dims: self.dims.clone(), strides: format_strides(self.format, &self.dims) }
+    }
+
+    /// KCRS packed copy of the filter at `data`
+    pub unsafe fn read(&self, data: *const c_void) -> Result<Vec<f32>, CudnnStatus> {
+        self.tensor().read(data)
+    }
+
+    /// Stores the KCRS packed `values` into the filter at `data`
+    pub unsafe fn write(&self, data: *mut c_void, values: &[f32]) -> Result<(), CudnnStatus> {
+        self.tensor().write(data, values)
+    }
+
+}
+
+
+impl FilterApi for CudnnCpu {
+
+    unsafe fn cudnnCreateFilterDescriptor(filterDesc: *mut*mut _FilterDescriptorStruct) -> CudnnStatus {
+        create(filterDesc)
+    }
+
+    unsafe fn cudnnDestroyFilterDescriptor(filterDesc: *mut _FilterDescriptorStruct) -> CudnnStatus {
+        destroy(filterDesc)
+    }
+
+    unsafe fn cudnnSetFilterNdDescriptor(filterDesc: *mut _FilterDescriptorStruct, dataType: CudnnDataType, format: CudnnTensorFormat,
+                                         nbDims: i32, filterDimA: *const i32) -> CudnnStatus {
+        run(|| {
+            *data_mut(filterDesc)? = Filter::new(dataType, format, array(filterDimA, nbDims)?)?;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnSetFilter4dDescriptor(filterDesc: *mut _FilterDescriptorStruct, dataType: CudnnDataType, format: CudnnTensorFormat,
+                                         k: i32, c: i32, h: i32, w: i32) -> CudnnStatus {
+        run(|| {
+            *data_mut(filterDesc)? = Filter::new(dataType, format, &[k, c, h, w])?;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetFilterNdDescriptor(filterDesc: *const _FilterDescriptorStruct, nbDimsRequested: i32,
+                                         dataType: *mut CudnnDataType, format: *mut CudnnTensorFormat,
+                                         nbDims: *mut i32, filterDimA: *mut i32) -> CudnnStatus {
+        run(|| {
+            let filter = data(filterDesc)?;
+            set(dataType, filter.data_type);
+            set(format, filter.format);
+            set(nbDims, filter.dims.len() as i32);
+            set_array(filterDimA, nbDimsRequested, &filter.dims);
+            Ok(())
+        })
+    }
+
+}
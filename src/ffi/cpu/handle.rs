@@ -0,0 +1,19 @@
+use super::*;
+
+
+
+impl Opaque for _CudnnStruct {
+    type Data = ();
+}
+
+impl HandleApi for CudnnCpu {
+
+    unsafe fn cudnnCreate(handle: *mut *mut _CudnnStruct) -> CudnnStatus {
+        create(handle)
+    }
+
+    unsafe fn cudnnDestroy(handle: *mut _CudnnStruct) -> CudnnStatus {
+        destroy(handle)
+    }
+
+}
@@ -0,0 +1,158 @@
+
+//! Host implementation of the cuDNN API, used as `Backend` with the `cpu_backend` feature.
+//!
+//! Descriptors are boxed Rust structs behind the opaque pointers of the C API. Data pointers are
+//! read and written directly, so every buffer given to the library must be accessible from the host.
+//! Values are converted to f32 and computed with the `reference` functions, whatever their data type.
+//! Workspaces are never needed, and every algorithm runs the same code.
+
+#![allow(non_snake_case)]
+
+use std::ptr;
+use std::os::raw::c_void;
+use super::*;
+use Half;
+
+mod handle;
+mod tensor;
+mod filter;
+mod activation;
+mod convolution;
+mod pooling;
+mod softmax;
+mod batch_normalization;
+mod op_tensor;
+mod reduce;
+mod dropout;
+mod rnn;
+
+use self::tensor::Tensor;
+use self::filter::Filter;
+
+
+
+/// cuDNN, computed on the host.
+pub struct CudnnCpu;
+
+
+/// Rust struct behind an opaque descriptor pointer.
+trait Opaque {
+    type Data: Default;
+}
+
+/// Boxes a default descriptor into `*output`.
+unsafe fn create<R: Opaque>(output: *mut *mut R) -> CudnnStatus {
+    if output.is_null() { return CudnnStatus::BadParam }
+    *output = Box::into_raw(Box::new(R::Data::default())) as *mut R;
+    CudnnStatus::Success
+}
+
+unsafe fn destroy<R: Opaque>(desc: *mut R) -> CudnnStatus {
+    if !desc.is_null() {
+        drop(Box::from_raw(desc as *mut R::Data));
+    }
+    CudnnStatus::Success
+}
+
+unsafe fn data<'a, R: Opaque>(desc: *const R) -> Result<&'a R::Data, CudnnStatus> {
+    (desc as *const R::Data).as_ref().ok_or(CudnnStatus::BadParam)
+}
+
+unsafe fn data_mut<'a, R: Opaque>(desc: *mut R) -> Result<&'a mut R::Data, CudnnStatus> {
+    (desc as *mut R::Data).as_mut().ok_or(CudnnStatus::BadParam)
+}
+
+/// Runs the body of an API function.
+fn run<F: FnOnce() -> Result<(), CudnnStatus>>(f: F) -> CudnnStatus {
+    match f() {
+        Ok(()) => CudnnStatus::Success,
+        Err(status) => status,
+    }
+}
+
+fn check(condition: bool) -> Result<(), CudnnStatus> {
+    if condition { Ok(()) } else { Err(CudnnStatus::BadParam) }
+}
+
+/// Writes `value` to an output pointer, which may be null when the caller isn't interested.
+unsafe fn set<T>(output: *mut T, value: T) {
+    if !output.is_null() {
+        *output = value;
+    }
+}
+
+/// Copies `values` into the `requested` first elements of an output array.
+unsafe fn set_array(output: *mut i32, requested: i32, values: &[i32]) {
+    if output.is_null() { return }
+    for (i, &x) in values.iter().take(requested.max(0) as usize).enumerate() {
+        *output.offset(i as isize) = x;
+    }
+}
+
+unsafe fn array<'a>(input: *const i32, len: i32) -> Result<&'a [i32], CudnnStatus> {
+    check(!input.is_null() && len >= 0)?;
+    Ok(::std::slice::from_raw_parts(input, len as usize))
+}
+
+
+/// Size in bytes of an element of `data_type`
+fn element_size(data_type: CudnnDataType) -> Result<usize, CudnnStatus> {
+    match data_type {
+        CudnnDataType::Float | CudnnDataType::Int32 => Ok(4),
+        CudnnDataType::Double => Ok(8),
+        CudnnDataType::Half => Ok(2),
+        CudnnDataType::Int8 | CudnnDataType::Uint8 => Ok(1),
+        CudnnDataType::Int8x4 | CudnnDataType::Uint8x4 => Err(CudnnStatus::NotSupported),
+    }
+}
+
+/// Element `offset` of a buffer of `data_type`, as a f32.
+unsafe fn load(data_type: CudnnDataType, data: *const c_void, offset: usize) -> f32 {
+    match data_type {
+        CudnnDataType::Float => ptr::read_unaligned((data as *const f32).add(offset)),
+        CudnnDataType::Double => ptr::read_unaligned((data as *const f64).add(offset)) as f32,
+        CudnnDataType::Half => Half(ptr::read_unaligned((data as *const u16).add(offset))).to_f32(),
+        CudnnDataType::Int8 => *(data as *const i8).add(offset) as f32,
+        CudnnDataType::Uint8 => *(data as *const u8).add(offset) as f32,
+        CudnnDataType::Int32 => ptr::read_unaligned((data as *const i32).add(offset)) as f32,
+        CudnnDataType::Int8x4 | CudnnDataType::Uint8x4 => unreachable!(),
+    }
+}
+
+/// Stores `value` as element `offset` of a buffer of `data_type`, rounding and saturating for integers.
+unsafe fn store(data_type: CudnnDataType, data: *mut c_void, offset: usize, value: f32) {
+    match data_type {
+        CudnnDataType::Float => ptr::write_unaligned((data as *mut f32).add(offset), value),
+        CudnnDataType::Double => ptr::write_unaligned((data as *mut f64).add(offset), value as f64),
+        CudnnDataType::Half => ptr::write_unaligned((data as *mut u16).add(offset), Half::from_f32(value).0),
+        CudnnDataType::Int8 => *(data as *mut i8).add(offset) = value.round().max(-128.0).min(127.0) as i8,
+        CudnnDataType::Uint8 => *(data as *mut u8).add(offset) = value.round().max(0.0).min(255.0) as u8,
+        CudnnDataType::Int32 => ptr::write_unaligned((data as *mut i32).add(offset), value.round().max(-2147483648.0).min(2147483520.0) as i32),
+        CudnnDataType::Int8x4 | CudnnDataType::Uint8x4 => unreachable!(),
+    }
+}
+
+/// Host scaling factor of an operation on `data_type`: a double for Double tensors, a float otherwise.
+unsafe fn scaling(data_type: CudnnDataType, value: *const c_void) -> Result<f32, CudnnStatus> {
+    check(!value.is_null())?;
+    Ok(match data_type {
+        CudnnDataType::Double => *(value as *const f64) as f32,
+        _ => *(value as *const f32),
+    })
+}
+
+/// Strides of a packed tensor of dimensions `dims` in `format`, the channels being dimension 1.
+fn format_strides(format: CudnnTensorFormat, dims: &[i32]) -> Vec<i32> {
+    let mut order = (0..dims.len()).collect::<Vec<_>>();
+    if format == CudnnTensorFormat::Nhwc && dims.len() > 2 {
+        let channels = order.remove(1);
+        order.push(channels);
+    }
+    let mut strides = vec![0; dims.len()];
+    let mut stride = 1;
+    for &i in order.iter().rev() {
+        strides[i] = stride;
+        stride *= dims[i];
+    }
+    strides
+}
@@ -0,0 +1,73 @@
+use super::*;
+use reference;
+
+
+
+impl Opaque for _OpTensorDescriptorStruct {
+    type Data = OpTensor;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct OpTensor {
+    pub op: CudnnOpTensorOp,
+    pub comp_type: CudnnDataType,
+    pub nan_opt: CudnnNanPropagation,
+}
+
+impl Default for OpTensor {
+    fn default() -> OpTensor {
+        OpTensor { op: CudnnOpTensorOp::Add, comp_type: CudnnDataType::Float, nan_opt: CudnnNanPropagation::NotPropagate }
+    }
+}
+
+
+impl OpTensorApi for CudnnCpu {
+
+    unsafe fn cudnnCreateOpTensorDescriptor(opTensorDesc: *mut*mut _OpTensorDescriptorStruct) -> CudnnStatus {
+        create(opTensorDesc)
+    }
+
+    unsafe fn cudnnDestroyOpTensorDescriptor(opTensorDesc: *mut _OpTensorDescriptorStruct) -> CudnnStatus {
+        destroy(opTensorDesc)
+    }
+
+    unsafe fn cudnnSetOpTensorDescriptor(opTensorDesc: *mut _OpTensorDescriptorStruct, opTensorOp: CudnnOpTensorOp,
+                                         opTensorCompType: CudnnDataType, opTensorNanOpt: CudnnNanPropagation) -> CudnnStatus {
+        run(|| {
+            *data_mut(opTensorDesc)? = OpTensor { op: opTensorOp, comp_type: opTensorCompType, nan_opt: opTensorNanOpt };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetOpTensorDescriptor(opTensorDesc: *const _OpTensorDescriptorStruct, opTensorOp: *mut CudnnOpTensorOp,
+                                         opTensorCompType: *mut CudnnDataType, opTensorNanOpt: *mut CudnnNanPropagation) -> CudnnStatus {
+        run(|| {
+            let op_tensor = data(opTensorDesc)?;
+            set(opTensorOp, op_tensor.op);
+            set(opTensorCompType, op_tensor.comp_type);
+            set(opTensorNanOpt, op_tensor.nan_opt);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnOpTensor(_handle: *mut _CudnnStruct, opTensorDesc: *const _OpTensorDescriptorStruct,
+                            alpha1: *const c_void, aDesc: *const _TensorDescriptorStruct, A: *const c_void,
+                            alpha2: *const c_void, bDesc: *const _TensorDescriptorStruct, B: *const c_void,
+                            beta: *const c_void, cDesc: *const _TensorDescriptorStruct, C: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let op_tensor = data(opTensorDesc)?;
+            let (a_desc, b_desc, c_desc) = (data(aDesc)?, data(bDesc)?, data(cDesc)?);
+            check(a_desc.dims == c_desc.dims && b_desc.dims.len() == c_desc.dims.len())?;
+            check(b_desc.dims.iter().zip(c_desc.dims.iter()).all(|(&b, &c)| b == c || b == 1))?;
+            let comp_type = op_tensor.comp_type;
+            let (alpha1, alpha2, beta) = (scaling(comp_type, alpha1)?, scaling(comp_type, alpha2)?, scaling(comp_type, beta)?);
+            let a = a_desc.read(A)?;
+            // B isn't read by the unary operations, and may be null
+            let b = if B.is_null() { vec![0.0; b_desc.len()] } else { b_desc.read(B)? };
+            let mut output = c_desc.read(C)?;
+            reference::op_tensor(op_tensor.op, alpha1, &c_desc.dims, &a, alpha2, &b_desc.dims, &b, beta, &mut output);
+            c_desc.write(C, &output)
+        })
+    }
+
+}
@@ -0,0 +1,138 @@
+use super::*;
+use reference;
+use CuPoolingDescriptorInfo;
+
+
+
+impl Opaque for _PoolingDescriptorStruct {
+    type Data = Pooling;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Pooling {
+    pub info: CuPoolingDescriptorInfo,
+}
+
+impl Default for Pooling {
+    fn default() -> Pooling {
+        Pooling {
+            info: CuPoolingDescriptorInfo {
+                mode: CudnnPoolingMode::Max,
+                maxpooling_nan_opt: CudnnNanPropagation::NotPropagate,
+                nb_dims: 0,
+                window_dims: Vec::new(),
+                paddings: Vec::new(),
+                strides: Vec::new(),
+            }
+        }
+    }
+}
+
+impl Pooling {
+
+    fn set(&mut self, mode: CudnnPoolingMode, nan_opt: CudnnNanPropagation,
+           window_dims: &[i32], paddings: &[i32], strides: &[i32]) -> Result<(), CudnnStatus> {
+        check(window_dims.iter().chain(strides.iter()).all(|&x| x > 0) && paddings.iter().all(|&x| x >= 0))?;
+        self.info = CuPoolingDescriptorInfo {
+            mode,
+            maxpooling_nan_opt: nan_opt,
+            nb_dims: window_dims.len() as i32,
+            window_dims: window_dims.to_vec(),
+            paddings: paddings.to_vec(),
+            strides: strides.to_vec(),
+        };
+        Ok(())
+    }
+
+    fn output_dims(&self, x_dims: &[i32]) -> Result<Vec<i32>, CudnnStatus> {
+        self.info.forward_output_dims(x_dims).map_err(|_| CudnnStatus::BadParam)
+    }
+
+}
+
+
+impl PoolingApi for CudnnCpu {
+
+    unsafe fn cudnnCreatePoolingDescriptor(poolingDesc: *mut*mut _PoolingDescriptorStruct) -> CudnnStatus {
+        create(poolingDesc)
+    }
+
+    unsafe fn cudnnDestroyPoolingDescriptor(poolingDesc: *mut _PoolingDescriptorStruct) -> CudnnStatus {
+        destroy(poolingDesc)
+    }
+
+    unsafe fn cudnnSetPooling2dDescriptor(poolingDesc: *mut _PoolingDescriptorStruct, mode: CudnnPoolingMode, maxpoolingNanOpt: CudnnNanPropagation,
+                                          windowHeight: i32, windowWidth: i32, verticalPadding: i32, horizontalPadding: i32,
+                                          verticalStride: i32, horizontalStride: i32) -> CudnnStatus {
+        run(|| {
+            data_mut(poolingDesc)?.set(mode, maxpoolingNanOpt, &[windowHeight, windowWidth],
+                                       &[verticalPadding, horizontalPadding], &[verticalStride, horizontalStride])
+        })
+    }
+
+    unsafe fn cudnnSetPoolingNdDescriptor(poolingDesc: *mut _PoolingDescriptorStruct, mode: CudnnPoolingMode, maxpoolingNanOpt: CudnnNanPropagation,
+                                          nbDims: i32, windowDimA: *const i32, paddingA: *const i32, strideA: *const i32) -> CudnnStatus {
+        run(|| {
+            check(nbDims > 0 && nbDims <= 6)?;
+            data_mut(poolingDesc)?.set(mode, maxpoolingNanOpt, array(windowDimA, nbDims)?, array(paddingA, nbDims)?, array(strideA, nbDims)?)
+        })
+    }
+
+    unsafe fn cudnnGetPoolingNdDescriptor(poolingDesc: *const _PoolingDescriptorStruct, nbDimsRequested: i32,
+                                          mode: *mut CudnnPoolingMode, maxpoolingNanOpt: *mut CudnnNanPropagation, nbDims: *mut i32,
+                                          windowDimA: *mut i32, paddingA: *mut i32, strideA: *mut i32) -> CudnnStatus {
+        run(|| {
+            let info = &data(poolingDesc)?.info;
+            set(mode, info.mode);
+            set(maxpoolingNanOpt, info.maxpooling_nan_opt);
+            set(nbDims, info.nb_dims);
+            set_array(windowDimA, nbDimsRequested, &info.window_dims);
+            set_array(paddingA, nbDimsRequested, &info.paddings);
+            set_array(strideA, nbDimsRequested, &info.strides);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetPoolingNdForwardOutputDim(poolingDesc: *const _PoolingDescriptorStruct, inputTensorDesc: *const _TensorDescriptorStruct,
+                                                nbDims: i32, outputTensorDimA: *mut i32) -> CudnnStatus {
+        run(|| {
+            let output = data(poolingDesc)?.output_dims(&data(inputTensorDesc)?.dims)?;
+            check(nbDims == output.len() as i32 && !outputTensorDimA.is_null())?;
+            set_array(outputTensorDimA, nbDims, &output);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnPoolingForward(_handle: *mut _CudnnStruct, poolingDesc: *const _PoolingDescriptorStruct, alpha: *const c_void,
+                                  xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                  beta: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let pooling = data(poolingDesc)?;
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(pooling.output_dims(&x_desc.dims)? == y_desc.dims)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let x = x_desc.read(x)?;
+            let mut output = y_desc.read(y)?;
+            reference::pooling_forward(&pooling.info, alpha, &x_desc.dims, &x, beta, &y_desc.dims, &mut output);
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnPoolingBackward(_handle: *mut _CudnnStruct, poolingDesc: *const _PoolingDescriptorStruct, alpha: *const c_void,
+                                   yDesc: *const _TensorDescriptorStruct, _y: *const c_void,
+                                   dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                   xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                   beta: *const c_void, dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let pooling = data(poolingDesc)?;
+            let (y_desc, dy_desc, x_desc, dx_desc) = (data(yDesc)?, data(dyDesc)?, data(xDesc)?, data(dxDesc)?);
+            check(pooling.output_dims(&x_desc.dims)? == dy_desc.dims && y_desc.dims == dy_desc.dims && x_desc.dims == dx_desc.dims)?;
+            let (alpha, beta) = (dx_desc.scaling(alpha)?, dx_desc.scaling(beta)?);
+            let (dy, x) = (dy_desc.read(dy)?, x_desc.read(x)?);
+            let mut output = dx_desc.read(dx)?;
+            reference::pooling_backward(&pooling.info, alpha, &dy_desc.dims, &dy, &x_desc.dims, &x, beta, &mut output);
+            dx_desc.write(dx, &output)
+        })
+    }
+
+}
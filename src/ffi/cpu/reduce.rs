@@ -0,0 +1,140 @@
+use super::*;
+use reference;
+
+
+
+impl Opaque for _ReduceTensorDescriptorStruct {
+    type Data = ReduceTensor;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct ReduceTensor {
+    pub op: CudnnReduceTensorOp,
+    pub comp_type: CudnnDataType,
+    pub nan_opt: CudnnNanPropagation,
+    pub indices: CudnnReduceTensorIndices,
+    pub indices_type: CudnnIndicesType,
+}
+
+impl Default for ReduceTensor {
+    fn default() -> ReduceTensor {
+        ReduceTensor {
+            op: CudnnReduceTensorOp::Add,
+            comp_type: CudnnDataType::Float,
+            nan_opt: CudnnNanPropagation::NotPropagate,
+            indices: CudnnReduceTensorIndices::NoIndices,
+            indices_type: CudnnIndicesType::Indices32bit,
+        }
+    }
+}
+
+impl ReduceTensor {
+
+    /// Whether the reduction outputs indices
+    fn with_indices(&self) -> bool {
+        self.indices == CudnnReduceTensorIndices::FlattenedIndices
+    }
+
+}
+
+/// Checks that `c` is `a` reduced along some of its dimensions
+fn check_reduction(a: &Tensor, c: &Tensor) -> Result<(), CudnnStatus> {
+    check(a.dims.len() == c.dims.len() && a.dims.iter().zip(c.dims.iter()).all(|(&a, &c)| a == c || c == 1))
+}
+
+
+impl ReduceTensorApi for CudnnCpu {
+
+    unsafe fn cudnnCreateReduceTensorDescriptor(reduceTensorDesc: *mut*mut _ReduceTensorDescriptorStruct) -> CudnnStatus {
+        create(reduceTensorDesc)
+    }
+
+    unsafe fn cudnnDestroyReduceTensorDescriptor(reduceTensorDesc: *mut _ReduceTensorDescriptorStruct) -> CudnnStatus {
+        destroy(reduceTensorDesc)
+    }
+
+    unsafe fn cudnnSetReduceTensorDescriptor(reduceTensorDesc: *mut _ReduceTensorDescriptorStruct, reduceTensorOp: CudnnReduceTensorOp,
+                                             reduceTensorCompType: CudnnDataType, reduceTensorNanOpt: CudnnNanPropagation,
+                                             reduceTensorIndices: CudnnReduceTensorIndices, reduceTensorIndicesType: CudnnIndicesType) -> CudnnStatus {
+        run(|| {
+            *data_mut(reduceTensorDesc)? = ReduceTensor {
+                op: reduceTensorOp,
+                comp_type: reduceTensorCompType,
+                nan_opt: reduceTensorNanOpt,
+                indices: reduceTensorIndices,
+                indices_type: reduceTensorIndicesType,
+            };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetReduceTensorDescriptor(reduceTensorDesc: *const _ReduceTensorDescriptorStruct, reduceTensorOp: &mut CudnnReduceTensorOp,
+                                             reduceTensorCompType: &mut CudnnDataType, reduceTensorNanOpt: &mut CudnnNanPropagation,
+                                             reduceTensorIndices: &mut CudnnReduceTensorIndices, reduceTensorIndicesType: &mut CudnnIndicesType) -> CudnnStatus {
+        run(|| {
+            let reduce = data(reduceTensorDesc)?;
+            *reduceTensorOp = reduce.op;
+            *reduceTensorCompType = reduce.comp_type;
+            *reduceTensorNanOpt = reduce.nan_opt;
+            *reduceTensorIndices = reduce.indices;
+            *reduceTensorIndicesType = reduce.indices_type;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetReductionIndicesSize(_handle: *mut _CudnnStruct, reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+                                           aDesc: *const _TensorDescriptorStruct, cDesc: *const _TensorDescriptorStruct,
+                                           sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            let reduce = data(reduceTensorDesc)?;
+            let (a_desc, c_desc) = (data(aDesc)?, data(cDesc)?);
+            check_reduction(a_desc, c_desc)?;
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = if reduce.with_indices() { c_desc.len() * 4 } else { 0 };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetReductionWorkspaceSize(_handle: *mut _CudnnStruct, _reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+                                             aDesc: *const _TensorDescriptorStruct, cDesc: *const _TensorDescriptorStruct,
+                                             sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            check_reduction(data(aDesc)?, data(cDesc)?)?;
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnReduceTensor(_handle: *mut _CudnnStruct, reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+                                indices: *mut c_void, indicesSizeInBytes: usize,
+                                _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                alpha: *const c_void, aDesc: *const _TensorDescriptorStruct, A: *const c_void,
+                                beta: *const c_void, cDesc: *const _TensorDescriptorStruct, C: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let reduce = data(reduceTensorDesc)?;
+            let (a_desc, c_desc) = (data(aDesc)?, data(cDesc)?);
+            check_reduction(a_desc, c_desc)?;
+            let with_indices = reduce.with_indices();
+            if with_indices {
+                match reduce.op {
+                    CudnnReduceTensorOp::Min | CudnnReduceTensorOp::Max | CudnnReduceTensorOp::Amax => (),
+                    _ => return Err(CudnnStatus::NotSupported),
+                }
+                if reduce.indices_type != CudnnIndicesType::Indices32bit { return Err(CudnnStatus::NotSupported) }
+                check(!indices.is_null() && indicesSizeInBytes >= c_desc.len() * 4)?;
+            }
+            let (alpha, beta) = (scaling(reduce.comp_type, alpha)?, scaling(reduce.comp_type, beta)?);
+            let a = a_desc.read(A)?;
+            let mut output = c_desc.read(C)?;
+            let mut selected = vec![0; output.len()];
+            reference::reduce_tensor(reduce.op, alpha, &a_desc.dims, &a, beta, &c_desc.dims, &mut output,
+                                     if with_indices { Some(&mut selected) } else { None });
+            if with_indices {
+                ::std::slice::from_raw_parts_mut(indices as *mut i32, selected.len()).copy_from_slice(&selected);
+            }
+            c_desc.write(C, &output)
+        })
+    }
+
+}
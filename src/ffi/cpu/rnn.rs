@@ -0,0 +1,745 @@
+use super::*;
+
+
+
+impl Opaque for _RNNDescriptorStruct {
+    type Data = Rnn;
+}
+
+/// The weights of each pseudo layer are packed as the matrices of its linear layers, in order, followed by
+/// their biases. Matrices are [hidden, input] for the first half of the linear layers and [hidden, hidden]
+/// for the second half, which applies to the recurrent hidden state.
+///
+/// Between layers, dropout is only applied by `cudnnRNNForwardTraining`, which doesn't support it here.
+pub(super) struct Rnn {
+    pub hidden_size: usize,
+    pub nb_layers: usize,
+    pub dropout: *const _DropoutDescriptorStruct,
+    pub input_mode: CudnnRNNInputMode,
+    pub direction: CudnnDirectionMode,
+    pub mode: CudnnRNNMode,
+    pub algo: CudnnRNNAlgo,
+    pub data_type: CudnnDataType,
+}
+
+impl Default for Rnn {
+    fn default() -> Rnn {
+        Rnn {
+            hidden_size: 0,
+            nb_layers: 0,
+            dropout: ptr::null(),
+            input_mode: CudnnRNNInputMode::LinearInput,
+            direction: CudnnDirectionMode::Unidirectional,
+            mode: CudnnRNNMode::Lstm,
+            algo: CudnnRNNAlgo::Standard,
+            data_type: CudnnDataType::Float,
+        }
+    }
+}
+
+
+/// Steps of a sequence, stored one after the other, the batch size of each step being at most the one of the previous step.
+struct Sequence<'a> {
+    steps: Vec<&'a Tensor>,
+    width: usize,
+}
+
+impl<'a> Sequence<'a> {
+
+    unsafe fn new(len: i32, descs: *const *const _TensorDescriptorStruct) -> Result<Sequence<'a>, CudnnStatus> {
+        check(len > 0 && !descs.is_null())?;
+        let steps = (0..len as usize).map(|t| data(*descs.add(t))).collect::<Result<Vec<_>, _>>()?;
+        check(steps.iter().all(|x| x.dims.len() >= 2))?;
+        let width = steps[0].dims[1];
+        check(steps.iter().all(|x| x.dims[1] == width))?;
+        check(steps.windows(2).all(|x| x[1].dims[0] <= x[0].dims[0]))?;
+        Ok(Sequence { steps, width: width as usize })
+    }
+
+    fn shape(&self) -> Shape {
+        let batches = self.steps.iter().map(|x| x.dims[0] as usize).collect::<Vec<_>>();
+        let starts = batches.iter().scan(0, |start, &batch| { *start += batch; Some(*start - batch) }).collect();
+        let rows = batches.iter().sum();
+        Shape { batches, starts, rows }
+    }
+
+    /// Address of step `t` in the sequence at `data`
+    fn step(&self, data: *const c_void, t: usize) -> Result<*const c_void, CudnnStatus> {
+        let size = element_size(self.steps[t].data_type)?;
+        Ok(unsafe { (data as *const u8).add(self.shape().starts[t] * self.width * size) } as *const c_void)
+    }
+
+    /// Packed rows of every step of the sequence at `data`
+    unsafe fn read(&self, data: *const c_void) -> Result<Vec<f32>, CudnnStatus> {
+        let mut output = Vec::new();
+        for (t, step) in self.steps.iter().enumerate() {
+            output.extend(step.read(self.step(data, t)?)?);
+        }
+        Ok(output)
+    }
+
+    unsafe fn write(&self, data: *mut c_void, values: &[f32]) -> Result<(), CudnnStatus> {
+        let mut start = 0;
+        for (t, step) in self.steps.iter().enumerate() {
+            step.write(self.step(data, t)? as *mut c_void, &values[start..start + step.len()])?;
+            start += step.len();
+        }
+        Ok(())
+    }
+
+}
+
+/// Batch size and first row of each step of a sequence
+#[derive(PartialEq)]
+struct Shape {
+    batches: Vec<usize>,
+    starts: Vec<usize>,
+    rows: usize,
+}
+
+impl Shape {
+
+    /// Steps in the order direction `d` processes them
+    fn steps(&self, d: usize) -> Vec<usize> {
+        if d == 0 { (0..self.batches.len()).collect() } else { (0..self.batches.len()).rev().collect() }
+    }
+
+    /// Row processed by direction `d` just before row `b` of step `t`, if any
+    fn previous_row(&self, d: usize, t: usize, b: usize) -> Option<usize> {
+        if d == 0 {
+            if t > 0 { Some(self.starts[t - 1] + b) } else { None }
+        } else {
+            if t + 1 < self.batches.len() && b < self.batches[t + 1] { Some(self.starts[t + 1] + b) } else { None }
+        }
+    }
+
+}
+
+
+/// Position of every value saved by the forward pass, and of the gradients computed by the backward data pass,
+/// in the reserve space. Everything is stored as f32, one row per element of the batch of each step:
+/// the input of each layer, then for each pseudo layer the activated gates, the cell and hidden states,
+/// the recurrent term of the GRU new gate, and the gradients of the gates from the input and hidden sides.
+struct Reserve {
+    rows: usize,
+    inputs: Vec<usize>,
+    cells: usize,
+    hidden_size: usize,
+    nb_gates: usize,
+}
+
+impl Reserve {
+
+    fn gates_len(&self) -> usize {
+        self.nb_gates * self.hidden_size
+    }
+
+    fn cell_len(&self) -> usize {
+        self.rows * (3 * self.gates_len() + 3 * self.hidden_size)
+    }
+
+    fn len(&self, nb_pseudo_layers: usize) -> usize {
+        self.cells + nb_pseudo_layers * self.cell_len()
+    }
+
+    fn input(&self, layer: usize) -> usize {
+        self.inputs[layer]
+    }
+
+    fn gates(&self, p: usize, row: usize) -> usize {
+        self.cells + p * self.cell_len() + row * self.gates_len()
+    }
+
+    fn c(&self, p: usize, row: usize) -> usize {
+        self.cells + p * self.cell_len() + self.rows * self.gates_len() + row * self.hidden_size
+    }
+
+    fn h(&self, p: usize, row: usize) -> usize {
+        self.c(p, row) + self.rows * self.hidden_size
+    }
+
+    fn rh(&self, p: usize, row: usize) -> usize {
+        self.h(p, row) + self.rows * self.hidden_size
+    }
+
+    fn dgx(&self, p: usize, row: usize) -> usize {
+        self.cells + p * self.cell_len() + self.rows * (self.gates_len() + 3 * self.hidden_size) + row * self.gates_len()
+    }
+
+    fn dgh(&self, p: usize, row: usize) -> usize {
+        self.dgx(p, row) + self.rows * self.gates_len()
+    }
+
+}
+
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// `output += matrix * vector`, with a row-major matrix of `vector.len()` columns
+fn mul_add(matrix: &[f32], vector: &[f32], output: &mut [f32]) {
+    for (j, output) in output.iter_mut().enumerate() {
+        *output += matrix[j * vector.len()..(j + 1) * vector.len()].iter().zip(vector.iter()).map(|(m, v)| m * v).sum::<f32>();
+    }
+}
+
+/// `output += transpose(matrix) * vector`, with a row-major matrix of `output.len()` columns
+fn mul_add_transposed(matrix: &[f32], vector: &[f32], output: &mut [f32]) {
+    let cols = output.len();
+    for (j, &v) in vector.iter().enumerate() {
+        for (i, output) in output.iter_mut().enumerate() {
+            *output += matrix[j * cols + i] * v;
+        }
+    }
+}
+
+impl Rnn {
+
+    fn nb_dirs(&self) -> usize {
+        match self.direction {
+            CudnnDirectionMode::Unidirectional => 1,
+            CudnnDirectionMode::Bidirectional => 2,
+        }
+    }
+
+    fn nb_pseudo_layers(&self) -> usize {
+        self.nb_layers * self.nb_dirs()
+    }
+
+    fn nb_gates(&self) -> usize {
+        match self.mode {
+            CudnnRNNMode::Relu | CudnnRNNMode::Tanh => 1,
+            CudnnRNNMode::Lstm => 4,
+            CudnnRNNMode::Gru => 3,
+        }
+    }
+
+    fn layer_input_size(&self, input_size: usize, layer: usize) -> usize {
+        if layer == 0 { input_size } else { self.nb_dirs() * self.hidden_size }
+    }
+
+    fn pseudo_layer_len(&self, input_size: usize, layer: usize) -> usize {
+        let h = self.hidden_size;
+        self.nb_gates() * h * (self.layer_input_size(input_size, layer) + h + 2)
+    }
+
+    fn pseudo_layer_offset(&self, input_size: usize, p: usize) -> usize {
+        (0..p).map(|q| self.pseudo_layer_len(input_size, q / self.nb_dirs())).sum()
+    }
+
+    fn params_len(&self, input_size: usize) -> usize {
+        self.pseudo_layer_offset(input_size, self.nb_pseudo_layers())
+    }
+
+    /// Offset and number of columns of the matrix of a linear layer
+    fn matrix(&self, input_size: usize, p: usize, lin_layer: usize) -> (usize, usize) {
+        let (g, h) = (self.nb_gates(), self.hidden_size);
+        let cols = self.layer_input_size(input_size, p / self.nb_dirs());
+        let offset = self.pseudo_layer_offset(input_size, p);
+        if lin_layer < g {
+            (offset + lin_layer * h * cols, cols)
+        } else {
+            (offset + g * h * cols + (lin_layer - g) * h * h, h)
+        }
+    }
+
+    fn bias(&self, input_size: usize, p: usize, lin_layer: usize) -> usize {
+        let (g, h) = (self.nb_gates(), self.hidden_size);
+        let cols = self.layer_input_size(input_size, p / self.nb_dirs());
+        self.pseudo_layer_offset(input_size, p) + g * h * (cols + h) + lin_layer * h
+    }
+
+    fn reserve(&self, input_size: usize, rows: usize) -> Reserve {
+        let mut inputs = Vec::with_capacity(self.nb_layers);
+        let mut cells = 0;
+        for layer in 0..self.nb_layers {
+            inputs.push(cells);
+            cells += rows * self.layer_input_size(input_size, layer);
+        }
+        Reserve { rows, inputs, cells, hidden_size: self.hidden_size, nb_gates: self.nb_gates() }
+    }
+
+    /// Pre-activations of the gates from the input and from the hidden state, biases included
+    fn pre_activations(&self, input_size: usize, p: usize, w: &[f32], x: &[f32], h_prev: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        let (g, h) = (self.nb_gates(), self.hidden_size);
+        let mut from_x = vec![0.0; g * h];
+        let mut from_h = vec![0.0; g * h];
+        for gate in 0..g {
+            let (matrix, cols) = self.matrix(input_size, p, gate);
+            let bias = self.bias(input_size, p, gate);
+            from_x[gate * h..(gate + 1) * h].copy_from_slice(&w[bias..bias + h]);
+            mul_add(&w[matrix..matrix + h * cols], x, &mut from_x[gate * h..(gate + 1) * h]);
+            let (matrix, _) = self.matrix(input_size, p, g + gate);
+            let bias = self.bias(input_size, p, g + gate);
+            from_h[gate * h..(gate + 1) * h].copy_from_slice(&w[bias..bias + h]);
+            mul_add(&w[matrix..matrix + h * h], h_prev, &mut from_h[gate * h..(gate + 1) * h]);
+        }
+        (from_x, from_h)
+    }
+
+    /// Activated gates, new cell state, new hidden state and recurrent term of the GRU new gate
+    fn cell(&self, from_x: &[f32], from_h: &[f32], h_prev: &[f32], c_prev: &[f32]) -> (Vec<f32>, Vec<f32>, Vec<f32>, Vec<f32>) {
+        let h = self.hidden_size;
+        let pre = |gate: usize, j: usize| from_x[gate * h + j] + from_h[gate * h + j];
+        let mut gates = vec![0.0; self.nb_gates() * h];
+        let mut c = vec![0.0; h];
+        let mut hidden = vec![0.0; h];
+        let mut rh = vec![0.0; h];
+        for j in 0..h {
+            match self.mode {
+                CudnnRNNMode::Relu => { gates[j] = pre(0, j).max(0.0); hidden[j] = gates[j] },
+                CudnnRNNMode::Tanh => { gates[j] = pre(0, j).tanh(); hidden[j] = gates[j] },
+                CudnnRNNMode::Lstm => {
+                    let (i, f, g, o) = (sigmoid(pre(0, j)), sigmoid(pre(1, j)), pre(2, j).tanh(), sigmoid(pre(3, j)));
+                    gates[j] = i; gates[h + j] = f; gates[2 * h + j] = g; gates[3 * h + j] = o;
+                    c[j] = f * c_prev[j] + i * g;
+                    hidden[j] = o * c[j].tanh();
+                },
+                CudnnRNNMode::Gru => {
+                    let (r, z) = (sigmoid(pre(0, j)), sigmoid(pre(1, j)));
+                    rh[j] = from_h[2 * h + j];
+                    let n = (from_x[2 * h + j] + r * rh[j]).tanh();
+                    gates[j] = r; gates[h + j] = z; gates[2 * h + j] = n;
+                    hidden[j] = (1.0 - z) * n + z * h_prev[j];
+                },
+            }
+        }
+        (gates, c, hidden, rh)
+    }
+
+    /// Gradients of the pre-activations of the gates on the input and hidden sides, of the previous cell state,
+    /// and the part of the gradient of the previous hidden state which doesn't go through the gates.
+    fn cell_backward(&self, gates: &[f32], c: &[f32], c_prev: &[f32], h_prev: &[f32], rh: &[f32],
+                     dh: &[f32], dc: &[f32]) -> (Vec<f32>, Vec<f32>, Vec<f32>, Vec<f32>) {
+        let h = self.hidden_size;
+        let mut dgx = vec![0.0; self.nb_gates() * h];
+        let mut dc_prev = vec![0.0; h];
+        let mut dh_prev = vec![0.0; h];
+        for j in 0..h {
+            match self.mode {
+                CudnnRNNMode::Relu => dgx[j] = if gates[j] > 0.0 { dh[j] } else { 0.0 },
+                CudnnRNNMode::Tanh => dgx[j] = dh[j] * (1.0 - gates[j] * gates[j]),
+                CudnnRNNMode::Lstm => {
+                    let (i, f, g, o) = (gates[j], gates[h + j], gates[2 * h + j], gates[3 * h + j]);
+                    let tanh_c = c[j].tanh();
+                    let dc = dc[j] + dh[j] * o * (1.0 - tanh_c * tanh_c);
+                    dgx[j] = dc * g * i * (1.0 - i);
+                    dgx[h + j] = dc * c_prev[j] * f * (1.0 - f);
+                    dgx[2 * h + j] = dc * i * (1.0 - g * g);
+                    dgx[3 * h + j] = dh[j] * tanh_c * o * (1.0 - o);
+                    dc_prev[j] = dc * f;
+                },
+                CudnnRNNMode::Gru => {
+                    let (r, z, n) = (gates[j], gates[h + j], gates[2 * h + j]);
+                    let dn = dh[j] * (1.0 - z) * (1.0 - n * n);
+                    dgx[j] = dn * rh[j] * r * (1.0 - r);
+                    dgx[h + j] = dh[j] * (h_prev[j] - n) * z * (1.0 - z);
+                    dgx[2 * h + j] = dn;
+                    dh_prev[j] = dh[j] * z;
+                },
+            }
+        }
+        let mut dgh = dgx.clone();
+        if self.mode == CudnnRNNMode::Gru {
+            for j in 0..h {
+                dgh[2 * h + j] *= gates[j];
+            }
+        }
+        (dgx, dgh, dc_prev, dh_prev)
+    }
+
+    /// Runs the network, saving everything in `reserve`. Returns the output sequence and the final states.
+    fn forward(&self, input_size: usize, shape: &Shape, x: &[f32], hx: &[f32], cx: &[f32], w: &[f32],
+               reserve: &mut [f32]) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+        let (h, nb_dirs) = (self.hidden_size, self.nb_dirs());
+        let batch = shape.batches[0];
+        let layout = self.reserve(input_size, shape.rows);
+        let mut input = x.to_vec();
+        let mut hy = hx.to_vec();
+        let mut cy = cx.to_vec();
+        for layer in 0..self.nb_layers {
+            let in_size = self.layer_input_size(input_size, layer);
+            reserve[layout.input(layer)..layout.input(layer) + input.len()].copy_from_slice(&input);
+            let mut output = vec![0.0; shape.rows * nb_dirs * h];
+            for d in 0..nb_dirs {
+                let p = layer * nb_dirs + d;
+                let hidden = &mut hy[p * batch * h..(p + 1) * batch * h];
+                let cell = &mut cy[p * batch * h..(p + 1) * batch * h];
+                for t in shape.steps(d) {
+                    for b in 0..shape.batches[t] {
+                        let row = shape.starts[t] + b;
+                        let (from_x, from_h) = self.pre_activations(input_size, p, w, &input[row * in_size..(row + 1) * in_size], &hidden[b * h..(b + 1) * h]);
+                        let (gates, c, new_h, rh) = self.cell(&from_x, &from_h, &hidden[b * h..(b + 1) * h], &cell[b * h..(b + 1) * h]);
+                        reserve[layout.gates(p, row)..layout.gates(p, row) + gates.len()].copy_from_slice(&gates);
+                        reserve[layout.c(p, row)..layout.c(p, row) + h].copy_from_slice(&c);
+                        reserve[layout.h(p, row)..layout.h(p, row) + h].copy_from_slice(&new_h);
+                        reserve[layout.rh(p, row)..layout.rh(p, row) + h].copy_from_slice(&rh);
+                        output[(row * nb_dirs + d) * h..(row * nb_dirs + d + 1) * h].copy_from_slice(&new_h);
+                        hidden[b * h..(b + 1) * h].copy_from_slice(&new_h);
+                        cell[b * h..(b + 1) * h].copy_from_slice(&c);
+                    }
+                }
+            }
+            input = output;
+        }
+        (input, hy, cy)
+    }
+
+    /// Hidden and cell states before row `b` of step `t`, for pseudo layer `p` running in direction `d`
+    fn previous_states(&self, layout: &Reserve, shape: &Shape, reserve: &[f32], hx: &[f32], cx: &[f32],
+                       p: usize, d: usize, t: usize, b: usize) -> (Vec<f32>, Vec<f32>) {
+        let h = self.hidden_size;
+        match shape.previous_row(d, t, b) {
+            Some(row) => (reserve[layout.h(p, row)..layout.h(p, row) + h].to_vec(), reserve[layout.c(p, row)..layout.c(p, row) + h].to_vec()),
+            None => {
+                let offset = (p * shape.batches[0] + b) * h;
+                (hx[offset..offset + h].to_vec(), cx[offset..offset + h].to_vec())
+            },
+        }
+    }
+
+    /// Back-propagates the gradients of the output sequence and of the final states, saving the gradients
+    /// of the gates in `reserve`. Returns the gradients of the input sequence and of the initial states.
+    fn backward_data(&self, input_size: usize, shape: &Shape, dy: &[f32], dhy: &[f32], dcy: &[f32], w: &[f32],
+                     hx: &[f32], cx: &[f32], reserve: &mut [f32]) -> (Vec<f32>, Vec<f32>, Vec<f32>) {
+        let (g, h, nb_dirs) = (self.nb_gates(), self.hidden_size, self.nb_dirs());
+        let batch = shape.batches[0];
+        let layout = self.reserve(input_size, shape.rows);
+        let mut d_output = dy.to_vec();
+        let mut dhx = dhy.to_vec();
+        let mut dcx = dcy.to_vec();
+        for layer in (0..self.nb_layers).rev() {
+            let in_size = self.layer_input_size(input_size, layer);
+            let mut d_input = vec![0.0; shape.rows * in_size];
+            for d in 0..nb_dirs {
+                let p = layer * nb_dirs + d;
+                let mut steps = shape.steps(d);
+                steps.reverse();
+                for t in steps {
+                    for b in 0..shape.batches[t] {
+                        let row = shape.starts[t] + b;
+                        let state = (p * batch + b) * h;
+                        let (h_prev, c_prev) = self.previous_states(&layout, shape, reserve, hx, cx, p, d, t, b);
+                        let dh = (0..h).map(|j| dhx[state + j] + d_output[(row * nb_dirs + d) * h + j]).collect::<Vec<_>>();
+                        let (dgx, dgh, dc_prev, mut dh_prev) = {
+                            let gates = &reserve[layout.gates(p, row)..layout.gates(p, row) + g * h];
+                            let c = &reserve[layout.c(p, row)..layout.c(p, row) + h];
+                            let rh = &reserve[layout.rh(p, row)..layout.rh(p, row) + h];
+                            self.cell_backward(gates, c, &c_prev, &h_prev, rh, &dh, &dcx[state..state + h])
+                        };
+                        for gate in 0..g {
+                            let (matrix, cols) = self.matrix(input_size, p, gate);
+                            mul_add_transposed(&w[matrix..matrix + h * cols], &dgx[gate * h..(gate + 1) * h],
+                                               &mut d_input[row * in_size..(row + 1) * in_size]);
+                            let (matrix, _) = self.matrix(input_size, p, g + gate);
+                            mul_add_transposed(&w[matrix..matrix + h * h], &dgh[gate * h..(gate + 1) * h], &mut dh_prev);
+                        }
+                        reserve[layout.dgx(p, row)..layout.dgx(p, row) + g * h].copy_from_slice(&dgx);
+                        reserve[layout.dgh(p, row)..layout.dgh(p, row) + g * h].copy_from_slice(&dgh);
+                        dhx[state..state + h].copy_from_slice(&dh_prev);
+                        dcx[state..state + h].copy_from_slice(&dc_prev);
+                    }
+                }
+            }
+            d_output = d_input;
+        }
+        (d_output, dhx, dcx)
+    }
+
+    /// Adds the gradient of the weights to `dw`, from the gradients of the gates saved by `backward_data`
+    fn backward_weights(&self, input_size: usize, shape: &Shape, hx: &[f32], reserve: &[f32], dw: &mut [f32]) {
+        let (g, h, nb_dirs) = (self.nb_gates(), self.hidden_size, self.nb_dirs());
+        let layout = self.reserve(input_size, shape.rows);
+        let zeros = vec![0.0; hx.len()];
+        for layer in 0..self.nb_layers {
+            let in_size = self.layer_input_size(input_size, layer);
+            for d in 0..nb_dirs {
+                let p = layer * nb_dirs + d;
+                for t in 0..shape.batches.len() {
+                    for b in 0..shape.batches[t] {
+                        let row = shape.starts[t] + b;
+                        let (h_prev, _) = self.previous_states(&layout, shape, reserve, hx, &zeros, p, d, t, b);
+                        let input = &reserve[layout.input(layer) + row * in_size..layout.input(layer) + (row + 1) * in_size];
+                        let dgx = &reserve[layout.dgx(p, row)..layout.dgx(p, row) + g * h];
+                        let dgh = &reserve[layout.dgh(p, row)..layout.dgh(p, row) + g * h];
+                        for gate in 0..g {
+                            for (lin_layer, gradient, vector) in vec![(gate, dgx, input), (g + gate, dgh, &h_prev[..])] {
+                                let (matrix, cols) = self.matrix(input_size, p, lin_layer);
+                                let bias = self.bias(input_size, p, lin_layer);
+                                for j in 0..h {
+                                    let gradient = gradient[gate * h + j];
+                                    dw[bias + j] += gradient;
+                                    for i in 0..cols {
+                                        dw[matrix + j * cols + i] += gradient * vector[i];
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks the sequences and the weights, returning the input size of the network
+    fn check(&self, input: &Sequence, output: &Sequence, w_desc: &Filter) -> Result<usize, CudnnStatus> {
+        check(input.shape() == output.shape() && output.width == self.nb_dirs() * self.hidden_size)?;
+        check(w_desc.len() >= self.params_len(input.width))?;
+        Ok(input.width)
+    }
+
+    /// Checks the descriptor of a state, [pseudo layers, batch, hidden]
+    fn check_state(&self, desc: &Tensor, shape: &Shape) -> Result<(), CudnnStatus> {
+        check(desc.dims == [self.nb_pseudo_layers() as i32, shape.batches[0] as i32, self.hidden_size as i32])
+    }
+
+    /// State at `data`, or zeros when `data` is null or the network has no such state
+    unsafe fn read_state(&self, desc: *const _TensorDescriptorStruct, data: *const c_void, shape: &Shape, cell: bool) -> Result<Vec<f32>, CudnnStatus> {
+        let len = self.nb_pseudo_layers() * shape.batches[0] * self.hidden_size;
+        if data.is_null() || (cell && self.mode != CudnnRNNMode::Lstm) { return Ok(vec![0.0; len]) }
+        let desc = super::data(desc)?;
+        self.check_state(desc, shape)?;
+        desc.read(data)
+    }
+
+    unsafe fn write_state(&self, desc: *const _TensorDescriptorStruct, data: *mut c_void, shape: &Shape, cell: bool, values: &[f32]) -> Result<(), CudnnStatus> {
+        if data.is_null() || (cell && self.mode != CudnnRNNMode::Lstm) { return Ok(()) }
+        let desc = super::data(desc)?;
+        self.check_state(desc, shape)?;
+        desc.write(data, values)
+    }
+
+    /// Number of f32 in the reserve space of a pass over a sequence of `shape`
+    fn reserve_len(&self, input_size: usize, shape: &Shape) -> usize {
+        self.reserve(input_size, shape.rows).len(self.nb_pseudo_layers())
+    }
+
+}
+
+unsafe fn reserve_slice<'a>(rnn: &Rnn, input_size: usize, shape: &Shape, reserve: *mut c_void, size: usize) -> Result<&'a mut [f32], CudnnStatus> {
+    let len = rnn.reserve_len(input_size, shape);
+    check(!reserve.is_null() && size >= len * 4)?;
+    Ok(::std::slice::from_raw_parts_mut(reserve as *mut f32, len))
+}
+
+/// Forward pass shared by inference and training, saving everything in `reserve`
+unsafe fn forward(rnn: &Rnn, input: &Sequence, x: *const c_void,
+                  hxDesc: *const _TensorDescriptorStruct, hx: *const c_void,
+                  cxDesc: *const _TensorDescriptorStruct, cx: *const c_void,
+                  wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                  seqLength: i32, yDesc: *const*const _TensorDescriptorStruct, y: *mut c_void,
+                  hyDesc: *const _TensorDescriptorStruct, hy: *mut c_void,
+                  cyDesc: *const _TensorDescriptorStruct, cy: *mut c_void,
+                  reserve: &mut [f32]) -> Result<(), CudnnStatus> {
+    let output = Sequence::new(seqLength, yDesc)?;
+    let w_desc = data(wDesc)?;
+    let input_size = rnn.check(input, &output, w_desc)?;
+    let shape = input.shape();
+    let (hx, cx) = (rnn.read_state(hxDesc, hx, &shape, false)?, rnn.read_state(cxDesc, cx, &shape, true)?);
+    let (values, hy_values, cy_values) = rnn.forward(input_size, &shape, &input.read(x)?, &hx, &cx, &w_desc.read(w)?, reserve);
+    output.write(y, &values)?;
+    rnn.write_state(hyDesc, hy, &shape, false, &hy_values)?;
+    rnn.write_state(cyDesc, cy, &shape, true, &cy_values)
+}
+
+
+impl RNNApi for CudnnCpu {
+
+    unsafe fn cudnnCreateRNNDescriptor(rnnDesc: *mut*mut _RNNDescriptorStruct) -> CudnnStatus {
+        create(rnnDesc)
+    }
+
+    unsafe fn cudnnDestroyRNNDescriptor(rnnDesc: *mut _RNNDescriptorStruct) -> CudnnStatus {
+        destroy(rnnDesc)
+    }
+
+    unsafe fn cudnnSetRNNDescriptor(_handle: *mut _CudnnStruct, rnnDesc: *mut _RNNDescriptorStruct, hiddenSize: i32, numLayers: i32,
+                                    dropoutDesc: *const _DropoutDescriptorStruct, inputMode: CudnnRNNInputMode,
+                                    direction: CudnnDirectionMode, mode: CudnnRNNMode, algo: CudnnRNNAlgo, dataType: CudnnDataType) -> CudnnStatus {
+        run(|| {
+            check(hiddenSize > 0 && numLayers > 0)?;
+            data(dropoutDesc)?;
+            if inputMode == CudnnRNNInputMode::SkipInput { return Err(CudnnStatus::NotSupported) }
+            *data_mut(rnnDesc)? = Rnn {
+                hidden_size: hiddenSize as usize,
+                nb_layers: numLayers as usize,
+                dropout: dropoutDesc,
+                input_mode: inputMode,
+                direction,
+                mode,
+                algo,
+                data_type: dataType,
+            };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetRNNDescriptor(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, hiddenSize: *mut i32, numLayers: *mut i32,
+                                    _dropoutDesc: *mut _DropoutDescriptorStruct, inputMode: *mut CudnnRNNInputMode,
+                                    direction: *mut CudnnDirectionMode, mode: *mut CudnnRNNMode,
+                                    algo: *mut CudnnRNNAlgo, dataType: *mut CudnnDataType) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            set(hiddenSize, rnn.hidden_size as i32);
+            set(numLayers, rnn.nb_layers as i32);
+            set(inputMode, rnn.input_mode);
+            set(direction, rnn.direction);
+            set(mode, rnn.mode);
+            set(algo, rnn.algo);
+            set(dataType, rnn.data_type);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetRNNWorkspaceSize(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                       xDesc: *const*const _TensorDescriptorStruct, sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            data(rnnDesc)?;
+            Sequence::new(seqLength, xDesc)?;
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = 0;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetRNNTrainingReserveSize(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                             xDesc: *const*const _TensorDescriptorStruct, sizeInBytes: *mut usize) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            let input = Sequence::new(seqLength, xDesc)?;
+            check(!sizeInBytes.is_null())?;
+            *sizeInBytes = rnn.reserve_len(input.width, &input.shape()) * 4;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnRNNForwardInference(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                       xDesc: *const*const _TensorDescriptorStruct, x: *const c_void,
+                                       hxDesc: *const _TensorDescriptorStruct, hx: *const c_void,
+                                       cxDesc: *const _TensorDescriptorStruct, cx: *const c_void,
+                                       wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                       yDesc: *const*const _TensorDescriptorStruct, y: *mut c_void,
+                                       hyDesc: *const _TensorDescriptorStruct, hy: *mut c_void,
+                                       cyDesc: *const _TensorDescriptorStruct, cy: *mut c_void,
+                                       _workspace: *mut c_void, _workspaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            let input = Sequence::new(seqLength, xDesc)?;
+            let mut reserve = vec![0.0; rnn.reserve_len(input.width, &input.shape())];
+            forward(rnn, &input, x, hxDesc, hx, cxDesc, cx, wDesc, w, seqLength, yDesc, y, hyDesc, hy, cyDesc, cy, &mut reserve)
+        })
+    }
+
+    unsafe fn cudnnRNNForwardTraining(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                      xDesc: *const*const _TensorDescriptorStruct, x: *const c_void,
+                                      hxDesc: *const _TensorDescriptorStruct, hx: *const c_void,
+                                      cxDesc: *const _TensorDescriptorStruct, cx: *const c_void,
+                                      wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                      yDesc: *const*const _TensorDescriptorStruct, y: *mut c_void,
+                                      hyDesc: *const _TensorDescriptorStruct, hy: *mut c_void,
+                                      cyDesc: *const _TensorDescriptorStruct, cy: *mut c_void,
+                                      _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                      reserveSpace: *mut c_void, reserveSpaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            if rnn.nb_layers > 1 && data(rnn.dropout)?.dropout > 0.0 { return Err(CudnnStatus::NotSupported) }
+            let input = Sequence::new(seqLength, xDesc)?;
+            let reserve = reserve_slice(rnn, input.width, &input.shape(), reserveSpace, reserveSpaceSizeInBytes)?;
+            forward(rnn, &input, x, hxDesc, hx, cxDesc, cx, wDesc, w, seqLength, yDesc, y, hyDesc, hy, cyDesc, cy, reserve)
+        })
+    }
+
+    unsafe fn cudnnRNNBackwardData(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                   yDesc: *const*const _TensorDescriptorStruct, _y: *const c_void,
+                                   dyDesc: *const*const _TensorDescriptorStruct, dy: *const c_void,
+                                   dhyDesc: *const _TensorDescriptorStruct, dhy: *const c_void,
+                                   dcyDesc: *const _TensorDescriptorStruct, dcy: *const c_void,
+                                   wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                   hxDesc: *const _TensorDescriptorStruct, hx: *const c_void,
+                                   cxDesc: *const _TensorDescriptorStruct, cx: *const c_void,
+                                   dxDesc: *const*const _TensorDescriptorStruct, dx: *mut c_void,
+                                   dhxDesc: *const _TensorDescriptorStruct, dhx: *mut c_void,
+                                   dcxDesc: *const _TensorDescriptorStruct, dcx: *mut c_void,
+                                   _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                   reserveSpace: *mut c_void, reserveSpaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            let (output, output_signal) = (Sequence::new(seqLength, yDesc)?, Sequence::new(seqLength, dyDesc)?);
+            let input_signal = Sequence::new(seqLength, dxDesc)?;
+            check(output.shape() == output_signal.shape() && output.width == output_signal.width)?;
+            let input_size = rnn.check(&input_signal, &output_signal, data(wDesc)?)?;
+            let shape = output.shape();
+            let reserve = reserve_slice(rnn, input_size, &shape, reserveSpace, reserveSpaceSizeInBytes)?;
+            let (hx, cx) = (rnn.read_state(hxDesc, hx, &shape, false)?, rnn.read_state(cxDesc, cx, &shape, true)?);
+            let (dhy, dcy) = (rnn.read_state(dhyDesc, dhy, &shape, false)?, rnn.read_state(dcyDesc, dcy, &shape, true)?);
+            let (values, dhx_values, dcx_values) = rnn.backward_data(input_size, &shape, &output_signal.read(dy)?, &dhy, &dcy,
+                                                                     &data(wDesc)?.read(w)?, &hx, &cx, reserve);
+            input_signal.write(dx, &values)?;
+            rnn.write_state(dhxDesc, dhx, &shape, false, &dhx_values)?;
+            rnn.write_state(dcxDesc, dcx, &shape, true, &dcx_values)
+        })
+    }
+
+    unsafe fn cudnnRNNBackwardWeights(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, seqLength: i32,
+                                      xDesc: *const*const _TensorDescriptorStruct, _x: *const c_void,
+                                      hxDesc: *const _TensorDescriptorStruct, hx: *const c_void,
+                                      yDesc: *const*const _TensorDescriptorStruct, _y: *const c_void,
+                                      _workspace: *mut c_void, _workspaceSizeInBytes: usize,
+                                      dwDesc: *const _FilterDescriptorStruct, dw: *mut c_void,
+                                      reserveSpace: *mut c_void, reserveSpaceSizeInBytes: usize) -> CudnnStatus {
+        run(|| {
+            let rnn = data(rnnDesc)?;
+            let (input, output) = (Sequence::new(seqLength, xDesc)?, Sequence::new(seqLength, yDesc)?);
+            let dw_desc = data(dwDesc)?;
+            let input_size = rnn.check(&input, &output, dw_desc)?;
+            let shape = input.shape();
+            let reserve = reserve_slice(rnn, input_size, &shape, reserveSpace, reserveSpaceSizeInBytes)?;
+            let hx = rnn.read_state(hxDesc, hx, &shape, false)?;
+            let mut values = dw_desc.read(dw)?;
+            rnn.backward_weights(input_size, &shape, &hx, reserve, &mut values);
+            dw_desc.write(dw, &values)
+        })
+    }
+
+    unsafe fn cudnnGetRNNParamsSize(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, xDesc: *const _TensorDescriptorStruct,
+                                    sizeInBytes: *mut usize, dataType: CudnnDataType) -> CudnnStatus {
+        run(|| {
+            let (rnn, x_desc) = (data(rnnDesc)?, data(xDesc)?);
+            check(x_desc.dims.len() >= 2 && !sizeInBytes.is_null())?;
+            *sizeInBytes = rnn.params_len(x_desc.dims[1] as usize) * element_size(dataType)?;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetRNNLinLayerMatrixParams(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, pseudoLayer: i32,
+                                              xDesc: *const _TensorDescriptorStruct, wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                              linLayerID: i32, linLayerMatDesc: *mut _FilterDescriptorStruct, linLayerMat: *mut*mut c_void) -> CudnnStatus {
+        run(|| {
+            let (rnn, x_desc, w_desc) = (data(rnnDesc)?, data(xDesc)?, data(wDesc)?);
+            check(x_desc.dims.len() >= 2 && !w.is_null() && !linLayerMat.is_null())?;
+            check(pseudoLayer >= 0 && (pseudoLayer as usize) < rnn.nb_pseudo_layers())?;
+            check(linLayerID >= 0 && (linLayerID as usize) < 2 * rnn.nb_gates())?;
+            let (offset, cols) = rnn.matrix(x_desc.dims[1] as usize, pseudoLayer as usize, linLayerID as usize);
+            *data_mut(linLayerMatDesc)? = Filter::new(w_desc.data_type, CudnnTensorFormat::Nchw, &[1, rnn.hidden_size as i32, cols as i32])?;
+            *linLayerMat = (w as *mut u8).add(offset * element_size(w_desc.data_type)?) as *mut c_void;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetRNNLinLayerBiasParams(_handle: *mut _CudnnStruct, rnnDesc: *const _RNNDescriptorStruct, pseudoLayer: i32,
+                                            xDesc: *const _TensorDescriptorStruct, wDesc: *const _FilterDescriptorStruct, w: *const c_void,
+                                            linLayerID: i32, linLayerBiasDesc: *mut _FilterDescriptorStruct, linLayerBias: *mut*mut c_void) -> CudnnStatus {
+        run(|| {
+            let (rnn, x_desc, w_desc) = (data(rnnDesc)?, data(xDesc)?, data(wDesc)?);
+            check(x_desc.dims.len() >= 2 && !w.is_null() && !linLayerBias.is_null())?;
+            check(pseudoLayer >= 0 && (pseudoLayer as usize) < rnn.nb_pseudo_layers())?;
+            check(linLayerID >= 0 && (linLayerID as usize) < 2 * rnn.nb_gates())?;
+            let offset = rnn.bias(x_desc.dims[1] as usize, pseudoLayer as usize, linLayerID as usize);
+            *data_mut(linLayerBiasDesc)? = Filter::new(w_desc.data_type, CudnnTensorFormat::Nchw, &[1, rnn.hidden_size as i32, 1])?;
+            *linLayerBias = (w as *mut u8).add(offset * element_size(w_desc.data_type)?) as *mut c_void;
+            Ok(())
+        })
+    }
+
+}
@@ -0,0 +1,37 @@
+use super::*;
+use reference;
+
+
+
+impl SoftmaxApi for CudnnCpu {
+
+    unsafe fn cudnnSoftmaxForward(_handle: *mut _CudnnStruct, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode,
+                                  alpha: *const c_void, xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                  beta: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims && x_desc.dims.len() >= 2)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let x = x_desc.read(x)?;
+            let mut output = y_desc.read(y)?;
+            reference::softmax_forward(algo, mode, alpha, &x_desc.dims, &x, beta, &mut output);
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnSoftmaxBackward(_handle: *mut _CudnnStruct, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode,
+                                   alpha: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *const c_void,
+                                   dyDesc: *const _TensorDescriptorStruct, dy: *const c_void,
+                                   beta: *const c_void, dxDesc: *const _TensorDescriptorStruct, dx: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (y_desc, dy_desc, dx_desc) = (data(yDesc)?, data(dyDesc)?, data(dxDesc)?);
+            check(y_desc.dims == dy_desc.dims && y_desc.dims == dx_desc.dims && y_desc.dims.len() >= 2)?;
+            let (alpha, beta) = (dx_desc.scaling(alpha)?, dx_desc.scaling(beta)?);
+            let (y, dy) = (y_desc.read(y)?, dy_desc.read(dy)?);
+            let mut output = dx_desc.read(dx)?;
+            reference::softmax_backward(algo, mode, alpha, &y_desc.dims, &y, &dy, beta, &mut output);
+            dx_desc.write(dx, &output)
+        })
+    }
+
+}
@@ -0,0 +1,188 @@
+use super::*;
+use reference::{for_each_index, packed_offset, scale_into};
+
+
+
+impl Opaque for _TensorDescriptorStruct {
+    type Data = Tensor;
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Tensor {
+    pub data_type: CudnnDataType,
+    pub dims: Vec<i32>,
+    pub strides: Vec<i32>,
+}
+
+impl Default for Tensor {
+    fn default() -> Tensor {
+        Tensor { data_type: CudnnDataType::Float, dims: Vec::new(), strides: Vec::new() }
+    }
+}
+
+impl Tensor {
+
+    pub fn len(&self) -> usize {
+        self.dims.iter().product::<i32>() as usize
+    }
+
+    /// Offset of every element in memory, in row-major order of the dimensions
+    fn offsets(&self) -> Vec<usize> {
+        let mut output = Vec::with_capacity(self.len());
+        for_each_index(&self.dims, |index| {
+            output.push(index.iter().zip(self.strides.iter()).map(|(&i, &s)| (i * s) as usize).sum());
+        });
+        output
+    }
+
+    pub fn size_in_bytes(&self) -> Result<usize, CudnnStatus> {
+        if self.dims.is_empty() { return Ok(0) }
+        let last = self.dims.iter().zip(self.strides.iter()).map(|(&d, &s)| ((d - 1) * s) as usize).sum::<usize>();
+        Ok((last + 1) * element_size(self.data_type)?)
+    }
+
+    /// Packed copy of the tensor at `data`
+    pub unsafe fn read(&self, data: *const c_void) -> Result<Vec<f32>, CudnnStatus> {
+        check(!data.is_null() && !self.dims.is_empty())?;
+        element_size(self.data_type)?;
+        Ok(self.offsets().into_iter().map(|offset| load(self.data_type, data, offset)).collect())
+    }
+
+    /// Stores the packed `values` into the tensor at `data`
+    pub unsafe fn write(&self, data: *mut c_void, values: &[f32]) -> Result<(), CudnnStatus> {
+        check(!data.is_null() && !self.dims.is_empty())?;
+        element_size(self.data_type)?;
+        for (offset, &value) in self.offsets().into_iter().zip(values.iter()) {
+            store(self.data_type, data, offset, value);
+        }
+        Ok(())
+    }
+
+    /// Scaling factor of an operation writing to this tensor
+    pub unsafe fn scaling(&self, value: *const c_void) -> Result<f32, CudnnStatus> {
+        scaling(self.data_type, value)
+    }
+
+}
+
+/// Expands the packed `values` of dimensions `dims` to `output_dims`, along the dimensions of size 1.
+pub(super) fn broadcast(values: &[f32], dims: &[i32], output_dims: &[i32]) -> Result<Vec<f32>, CudnnStatus> {
+    check(dims.len() == output_dims.len())?;
+    check(dims.iter().zip(output_dims.iter()).all(|(&d, &o)| d == o || d == 1))?;
+    let mut output = Vec::with_capacity(output_dims.iter().product::<i32>() as usize);
+    let mut source = vec![0; dims.len()];
+    for_each_index(output_dims, |index| {
+        for i in 0..dims.len() {
+            source[i] = if dims[i] == 1 { 0 } else { index[i] };
+        }
+        output.push(values[packed_offset(dims, &source)]);
+    });
+    Ok(output)
+}
+
+
+impl TensorApi for CudnnCpu {
+
+    unsafe fn cudnnCreateTensorDescriptor(tensorDesc: *mut*mut _TensorDescriptorStruct) -> CudnnStatus {
+        create(tensorDesc)
+    }
+
+    unsafe fn cudnnDestroyTensorDescriptor(tensorDesc: *mut _TensorDescriptorStruct) -> CudnnStatus {
+        destroy(tensorDesc)
+    }
+
+    unsafe fn cudnnSetTensorNdDescriptor(tensorDesc: *mut _TensorDescriptorStruct, dataType: CudnnDataType,
+                                         nbDims: i32, dimA: *const i32, strideA: *const i32) -> CudnnStatus {
+        run(|| {
+            check(nbDims > 0 && nbDims <= 8)?;
+            let dims = array(dimA, nbDims)?;
+            let strides = array(strideA, nbDims)?;
+            check(dims.iter().all(|&x| x > 0) && strides.iter().all(|&x| x > 0))?;
+            *data_mut(tensorDesc)? = Tensor { data_type: dataType, dims: dims.to_vec(), strides: strides.to_vec() };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnSetTensor4dDescriptor(tensorDesc: *mut _TensorDescriptorStruct, format: CudnnTensorFormat, dataType: CudnnDataType,
+                                         n: i32, c: i32, h: i32, w: i32) -> CudnnStatus {
+        run(|| {
+            let dims = vec![n, c, h, w];
+            check(dims.iter().all(|&x| x > 0))?;
+            let strides = format_strides(format, &dims);
+            *data_mut(tensorDesc)? = Tensor { data_type: dataType, dims, strides };
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetTensorNdDescriptor(tensorDesc: *mut _TensorDescriptorStruct, nbDimsRequested: i32,
+                                         dataType: *mut CudnnDataType, nbDims: *mut i32, dimA: *mut i32, strideA: *mut i32) -> CudnnStatus {
+        run(|| {
+            let tensor = data(tensorDesc)?;
+            set(dataType, tensor.data_type);
+            set(nbDims, tensor.dims.len() as i32);
+            set_array(dimA, nbDimsRequested, &tensor.dims);
+            set_array(strideA, nbDimsRequested, &tensor.strides);
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnGetTensorSizeInBytes(tensorDesc: *mut _TensorDescriptorStruct, size: &mut usize) -> CudnnStatus {
+        run(|| {
+            *size = data(tensorDesc)?.size_in_bytes()?;
+            Ok(())
+        })
+    }
+
+    unsafe fn cudnnSetTensor(_handle: *mut _CudnnStruct, yDesc: *const _TensorDescriptorStruct,
+                             y: *mut c_void, valuePtr: *const c_void) -> CudnnStatus {
+        run(|| {
+            let y_desc = data(yDesc)?;
+            check(!valuePtr.is_null())?;
+            element_size(y_desc.data_type)?;
+            let value = load(y_desc.data_type, valuePtr, 0);
+            y_desc.write(y, &vec![value; y_desc.len()])
+        })
+    }
+
+    unsafe fn cudnnTransformTensor(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                                   xDesc: *const _TensorDescriptorStruct, x: *const c_void,
+                                   beta: *const c_void, yDesc: *const _TensorDescriptorStruct, y: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (x_desc, y_desc) = (data(xDesc)?, data(yDesc)?);
+            check(x_desc.dims == y_desc.dims)?;
+            let (alpha, beta) = (y_desc.scaling(alpha)?, y_desc.scaling(beta)?);
+            let x = x_desc.read(x)?;
+            let mut output = y_desc.read(y)?;
+            for (x, y) in x.into_iter().zip(output.iter_mut()) {
+                scale_into(alpha, x, beta, y);
+            }
+            y_desc.write(y, &output)
+        })
+    }
+
+    unsafe fn cudnnAddTensor(_handle: *mut _CudnnStruct, alpha: *const c_void,
+                             aDesc: *const _TensorDescriptorStruct, A: *const c_void,
+                             beta: *const c_void, cDesc: *const _TensorDescriptorStruct, C: *mut c_void) -> CudnnStatus {
+        run(|| {
+            let (a_desc, c_desc) = (data(aDesc)?, data(cDesc)?);
+            let (alpha, beta) = (c_desc.scaling(alpha)?, c_desc.scaling(beta)?);
+            let a = broadcast(&a_desc.read(A)?, &a_desc.dims, &c_desc.dims)?;
+            let mut output = c_desc.read(C)?;
+            for (a, c) in a.into_iter().zip(output.iter_mut()) {
+                scale_into(alpha, a, beta, c);
+            }
+            c_desc.write(C, &output)
+        })
+    }
+
+    unsafe fn cudnnScaleTensor(_handle: *mut _CudnnStruct, yDesc: *const _TensorDescriptorStruct,
+                               y: *mut c_void, alpha: *const c_void) -> CudnnStatus {
+        run(|| {
+            let y_desc = data(yDesc)?;
+            let alpha = y_desc.scaling(alpha)?;
+            let output = y_desc.read(y)?.into_iter().map(|x| alpha * x).collect::<Vec<_>>();
+            y_desc.write(y, &output)
+        })
+    }
+
+}
@@ -1,5 +1,6 @@
 
-use super::CudnnStatus;
+use super::{CudnnStatus, CudnnError};
+use super::backend::Backend;
 
 
 
@@ -8,13 +9,15 @@ pub enum _CudnnStruct {}
 
 
 
-#[allow(non_snake_case)]
-extern {
+cudnn_api! {
+    /// Creation and destruction of the cuDNN handle
+    pub trait HandleApi {
 
-    fn cudnnCreate(handle: *mut *mut _CudnnStruct) -> CudnnStatus;
+        fn cudnnCreate(handle: *mut *mut _CudnnStruct) -> CudnnStatus;
 
-    fn cudnnDestroy(handle: *mut _CudnnStruct) -> CudnnStatus;
+        fn cudnnDestroy(handle: *mut _CudnnStruct) -> CudnnStatus;
 
+    }
 }
 
 
@@ -22,22 +25,24 @@ extern {
 
 
 #[inline]
-pub fn cudnn_create(handle: *mut *mut _CudnnStruct) {
+pub fn cudnn_create(handle: *mut *mut _CudnnStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreate(handle) }.assert_success();
+        unsafe { Backend::cudnnCreate(handle) }.into_result("cudnnCreate")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreate(handle) };
+        unsafe { Backend::cudnnCreate(handle) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy(handle: *mut _CudnnStruct) {
+pub fn cudnn_destroy(handle: *mut _CudnnStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroy(handle) }.assert_success();
+        unsafe { Backend::cudnnDestroy(handle) }.into_result("cudnnDestroy")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroy(handle) };
+        unsafe { Backend::cudnnDestroy(handle) };
+        Ok(())
     }
 }
 
@@ -1,5 +1,6 @@
 
-use super::{CudnnStatus, CudnnDataType, CudnnTensorFormat};
+use super::{CudnnStatus, CudnnError, CudnnDataType, CudnnTensorFormat};
+use super::backend::Backend;
 
 
 
@@ -7,40 +8,42 @@ pub enum _FilterDescriptorStruct {}
 
 
 
-#[allow(non_snake_case)]
-extern {
+cudnn_api! {
+    /// Filter descriptors
+    pub trait FilterApi {
 
-    fn cudnnCreateFilterDescriptor(filterDesc: *mut*mut _FilterDescriptorStruct) -> CudnnStatus;
+        fn cudnnCreateFilterDescriptor(filterDesc: *mut*mut _FilterDescriptorStruct) -> CudnnStatus;
 
-    fn cudnnDestroyFilterDescriptor(filterDesc: *mut _FilterDescriptorStruct) -> CudnnStatus;
+        fn cudnnDestroyFilterDescriptor(filterDesc: *mut _FilterDescriptorStruct) -> CudnnStatus;
 
-    fn cudnnSetFilterNdDescriptor(
-        filterDesc: *mut _FilterDescriptorStruct,
-        dataType: CudnnDataType,
-        format: CudnnTensorFormat,
-        nbDims: i32,
-        filterDimA: *const i32
-    ) -> CudnnStatus;
+        fn cudnnSetFilterNdDescriptor(
+            filterDesc: *mut _FilterDescriptorStruct,
+            dataType: CudnnDataType,
+            format: CudnnTensorFormat,
+            nbDims: i32,
+            filterDimA: *const i32
+        ) -> CudnnStatus;
 
-    fn cudnnSetFilter4dDescriptor(
-        filterDesc: *mut _FilterDescriptorStruct,
-        dataType: CudnnDataType,
-        format: CudnnTensorFormat,
-        k: i32,
-        c: i32,
-        h: i32,
-        w: i32,
-    ) -> CudnnStatus;
+        fn cudnnSetFilter4dDescriptor(
+            filterDesc: *mut _FilterDescriptorStruct,
+            dataType: CudnnDataType,
+            format: CudnnTensorFormat,
+            k: i32,
+            c: i32,
+            h: i32,
+            w: i32,
+        ) -> CudnnStatus;
 
-    fn cudnnGetFilterNdDescriptor(
-        filterDesc: *const _FilterDescriptorStruct,
-        nbDimsRequested: i32,
-        dataType: *mut CudnnDataType,
-        format: *mut CudnnTensorFormat,
-        nbDims: *mut i32,
-        filterDimA: *mut i32,
-    ) -> CudnnStatus;
+        fn cudnnGetFilterNdDescriptor(
+            filterDesc: *const _FilterDescriptorStruct,
+            nbDimsRequested: i32,
+            dataType: *mut CudnnDataType,
+            format: *mut CudnnTensorFormat,
+            nbDims: *mut i32,
+            filterDimA: *mut i32,
+        ) -> CudnnStatus;
 
+    }
 }
 
 
@@ -48,52 +51,57 @@ extern {
 
 
 #[inline]
-pub fn cudnn_create_filter_descriptor(filter_desc: *mut*mut _FilterDescriptorStruct) {
+pub fn cudnn_create_filter_descriptor(filter_desc: *mut*mut _FilterDescriptorStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreateFilterDescriptor(filter_desc) }.assert_success();
+        unsafe { Backend::cudnnCreateFilterDescriptor(filter_desc) }.into_result("cudnnCreateFilterDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreateFilterDescriptor(filter_desc) };
+        unsafe { Backend::cudnnCreateFilterDescriptor(filter_desc) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy_filter_descriptor(filter_desc: *mut _FilterDescriptorStruct) {
+pub fn cudnn_destroy_filter_descriptor(filter_desc: *mut _FilterDescriptorStruct) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroyFilterDescriptor(filter_desc) }.assert_success();
+        unsafe { Backend::cudnnDestroyFilterDescriptor(filter_desc) }.into_result("cudnnDestroyFilterDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroyFilterDescriptor(filter_desc) };
+        unsafe { Backend::cudnnDestroyFilterDescriptor(filter_desc) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_filter_nd_descriptor(filter_desc: *mut _FilterDescriptorStruct, data_type: CudnnDataType, format: CudnnTensorFormat, nb_dims: i32, filter_dim_a: *const i32) {
+pub fn cudnn_set_filter_nd_descriptor(filter_desc: *mut _FilterDescriptorStruct, data_type: CudnnDataType, format: CudnnTensorFormat, nb_dims: i32, filter_dim_a: *const i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetFilterNdDescriptor(filter_desc, data_type, format, nb_dims, filter_dim_a) }.assert_success();
+        unsafe { Backend::cudnnSetFilterNdDescriptor(filter_desc, data_type, format, nb_dims, filter_dim_a) }.into_result("cudnnSetFilterNdDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetFilterNdDescriptor(filter_desc, data_type, format, nb_dims, filter_dim_a) };
+        unsafe { Backend::cudnnSetFilterNdDescriptor(filter_desc, data_type, format, nb_dims, filter_dim_a) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_filter4d_descriptor(filter_desc: *mut _FilterDescriptorStruct, data_type: CudnnDataType, format: CudnnTensorFormat, k: i32, c: i32, h: i32, w: i32) {
+pub fn cudnn_set_filter4d_descriptor(filter_desc: *mut _FilterDescriptorStruct, data_type: CudnnDataType, format: CudnnTensorFormat, k: i32, c: i32, h: i32, w: i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetFilter4dDescriptor(filter_desc, data_type, format, k, c, h, w) }.assert_success();
+        unsafe { Backend::cudnnSetFilter4dDescriptor(filter_desc, data_type, format, k, c, h, w) }.into_result("cudnnSetFilter4dDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetFilter4dDescriptor(filter_desc, data_type, format, k, c, h, w) };
+        unsafe { Backend::cudnnSetFilter4dDescriptor(filter_desc, data_type, format, k, c, h, w) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_filter_nd_descriptor(filter_desc: *const _FilterDescriptorStruct, nb_dims_requested: i32, data_type: *mut CudnnDataType, format: *mut CudnnTensorFormat, nb_dims: *mut i32, filter_dim_a: *mut i32) {
+pub fn cudnn_get_filter_nd_descriptor(filter_desc: *const _FilterDescriptorStruct, nb_dims_requested: i32, data_type: *mut CudnnDataType, format: *mut CudnnTensorFormat, nb_dims: *mut i32, filter_dim_a: *mut i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetFilterNdDescriptor(filter_desc, nb_dims_requested, data_type, format, nb_dims, filter_dim_a) }.assert_success();
+        unsafe { Backend::cudnnGetFilterNdDescriptor(filter_desc, nb_dims_requested, data_type, format, nb_dims, filter_dim_a) }.into_result("cudnnGetFilterNdDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetFilterNdDescriptor(filter_desc, nb_dims_requested, data_type, format, nb_dims, filter_dim_a) };
+        unsafe { Backend::cudnnGetFilterNdDescriptor(filter_desc, nb_dims_requested, data_type, format, nb_dims, filter_dim_a) };
+        Ok(())
     }
 }
 
@@ -1,7 +1,13 @@
 #![allow(dead_code)]
 
+use std::fmt::{self, Display};
+use std::error::Error;
 
 
+#[macro_use]
+mod backend;
+#[cfg(feature = "cpu_backend")]
+mod cpu;
 mod cudnn;
 mod tensor_descriptor;
 mod activation_descriptor;
@@ -10,6 +16,10 @@ mod reduce_tensor_descriptor;
 mod filter_descriptor;
 mod rnn_descriptor;
 mod dropout_descriptor;
+mod pooling_descriptor;
+mod softmax;
+mod batch_normalization;
+mod op_tensor_descriptor;
 
 pub use self::cudnn::*;
 pub use self::tensor_descriptor::*;
@@ -19,9 +29,13 @@ pub use self::reduce_tensor_descriptor::*;
 pub use self::filter_descriptor::*;
 pub use self::rnn_descriptor::*;
 pub use self::dropout_descriptor::*;
+pub use self::pooling_descriptor::*;
+pub use self::softmax::*;
+pub use self::batch_normalization::*;
+pub use self::op_tensor_descriptor::*;
 
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnDataType {
     Float = 0,
@@ -34,14 +48,14 @@ pub enum CudnnDataType {
     Uint8x4 = 7,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnNanPropagation {
     NotPropagate = 0,
     Propagate = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnStatus {
     Success = 0,
@@ -60,10 +74,13 @@ pub enum CudnnStatus {
     RuntimeFPOverflow = 13,
 }
 impl CudnnStatus {
-    fn assert_success(&self) {
-        assert_eq!(self, &CudnnStatus::Success);
+    fn into_result(self, function: &'static str) -> Result<(), CudnnError> {
+        match self {
+            CudnnStatus::Success => Ok(()),
+            status => Err(CudnnError::new(status, function)),
+        }
     }
-    fn get_error_str(&self) -> Option<&'static str> {
+    pub fn get_error_str(&self) -> Option<&'static str> {
         match *self {
             CudnnStatus::Success => None,
             CudnnStatus::NotInitialized => Some("NotInitialized"),
@@ -83,7 +100,43 @@ impl CudnnStatus {
     }
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone)]
+pub struct CudnnError {
+    status: CudnnStatus,
+    function: &'static str,
+    details: Option<String>,
+}
+impl CudnnError {
+    pub(crate) fn new(status: CudnnStatus, function: &'static str) -> CudnnError {
+        CudnnError { status, function, details: None }
+    }
+    /// Error raised by a check done on the Rust side, before reaching cuDNN.
+    pub(crate) fn bad_param<S: Into<String>>(function: &'static str, details: S) -> CudnnError {
+        CudnnError { status: CudnnStatus::BadParam, function, details: Some(details.into()) }
+    }
+    pub fn status(&self) -> CudnnStatus {
+        self.status
+    }
+    /// Name of the failing call (a cuDNN function, or the wrapper that rejected its arguments).
+    pub fn function(&self) -> &'static str {
+        self.function
+    }
+    pub fn details(&self) -> Option<&str> {
+        self.details.as_ref().map(|x| x.as_str())
+    }
+}
+impl Display for CudnnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} failed: {}", self.function, self.status.get_error_str().unwrap_or("Success"))?;
+        if let Some(ref details) = self.details {
+            write!(f, " ({})", details)?;
+        }
+        Ok(())
+    }
+}
+impl Error for CudnnError {}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnReduceTensorOp {
     Add = 0,
@@ -97,14 +150,14 @@ pub enum CudnnReduceTensorOp {
     MulNoZeros = 8,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnReduceTensorIndices {
     NoIndices = 0,
     FlattenedIndices = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnIndicesType {
     Indices32bit = 0,
@@ -113,14 +166,65 @@ pub enum CudnnIndicesType {
     Indices8bit = 3,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnConvolutionMode {
     Convolution = 0,
     CrossCorrelation = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnPoolingMode {
+    Max = 0,
+    AverageCountIncludePadding = 1,
+    AverageCountExcludePadding = 2,
+    MaxDeterministic = 3,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnSoftmaxAlgorithm {
+    /// Straightforward implementation, may overflow on large inputs
+    Fast = 0,
+    /// Subtracts the maximum before exponentiating
+    Accurate = 1,
+    /// Log-softmax
+    Log = 2,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnSoftmaxMode {
+    /// Over C, H and W for each image
+    Instance = 0,
+    /// Over C for each image and spatial position
+    Channel = 1,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnBatchNormMode {
+    /// One set of parameters per C, H and W position, statistics over N
+    PerActivation = 0,
+    /// One set of parameters per channel, statistics over N, H and W
+    Spatial = 1,
+    /// Spatial, with a faster implementation that may overflow on some inputs
+    SpatialPersistent = 2,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnOpTensorOp {
+    Add = 0,
+    Mul = 1,
+    Min = 2,
+    Max = 3,
+    Sqrt = 4,
+    Not = 5,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnActivationMode {
     Sigmoid = 0,
@@ -131,7 +235,7 @@ pub enum CudnnActivationMode {
     //Identity = 5, Doesn't work, but it is useless anyway
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnConvolutionFwdAlgo {
     ImplicitGemm = 0,
@@ -145,7 +249,7 @@ pub enum CudnnConvolutionFwdAlgo {
     Count = 8,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnConvolutionBwdDataAlgo {
     Algo0 = 0,
@@ -157,7 +261,7 @@ pub enum CudnnConvolutionBwdDataAlgo {
     Count = 6,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnConvolutionBwdFilterAlgo {
     Algo0 = 0,
@@ -170,14 +274,21 @@ pub enum CudnnConvolutionBwdFilterAlgo {
     Count = 7,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+#[repr(C)]
+pub enum CudnnDeterminism {
+    NonDeterministic = 0,
+    Deterministic = 1,
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnMathType {
     Default = 0,
     TensorOp = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnTensorFormat {
     Nchw = 0,
@@ -185,7 +296,7 @@ pub enum CudnnTensorFormat {
     //NchwVectC = 2,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnRNNMode {
     Relu = 0,
@@ -194,21 +305,21 @@ pub enum CudnnRNNMode {
     Gru = 3,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnDirectionMode {
     Unidirectional = 0,
     Bidirectional = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnRNNInputMode {
     LinearInput = 0,
     SkipInput = 1,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 #[repr(C)]
 pub enum CudnnRNNAlgo {
     Standard = 0,
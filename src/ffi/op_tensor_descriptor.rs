@@ -0,0 +1,115 @@
+
+use std::os::raw::c_void;
+use super::{CudnnStatus, CudnnError, CudnnOpTensorOp, CudnnDataType, CudnnNanPropagation};
+use super::cudnn::_CudnnStruct;
+use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
+
+
+
+pub enum _OpTensorDescriptorStruct {}
+
+
+
+
+cudnn_api! {
+    /// Op tensor descriptors and element-wise operations
+    pub trait OpTensorApi {
+
+        fn cudnnCreateOpTensorDescriptor(opTensorDesc: *mut*mut _OpTensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyOpTensorDescriptor(opTensorDesc: *mut _OpTensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetOpTensorDescriptor(
+            opTensorDesc: *mut _OpTensorDescriptorStruct,
+            opTensorOp: CudnnOpTensorOp,
+            opTensorCompType: CudnnDataType,
+            opTensorNanOpt: CudnnNanPropagation,
+        ) -> CudnnStatus;
+
+        fn cudnnGetOpTensorDescriptor(
+            opTensorDesc: *const _OpTensorDescriptorStruct,
+            opTensorOp: *mut CudnnOpTensorOp,
+            opTensorCompType: *mut CudnnDataType,
+            opTensorNanOpt: *mut CudnnNanPropagation,
+        ) -> CudnnStatus;
+
+        fn cudnnOpTensor(
+            handle: *mut _CudnnStruct,
+            opTensorDesc: *const _OpTensorDescriptorStruct,
+            alpha1: *const c_void,
+            aDesc: *const _TensorDescriptorStruct,
+            A: *const c_void,
+            alpha2: *const c_void,
+            bDesc: *const _TensorDescriptorStruct,
+            B: *const c_void,
+            beta: *const c_void,
+            cDesc: *const _TensorDescriptorStruct,
+            C: *mut c_void,
+        ) -> CudnnStatus;
+
+    }
+}
+
+
+
+
+
+#[inline]
+pub fn cudnn_create_op_tensor_descriptor(op_tensor_desc: *mut*mut _OpTensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnCreateOpTensorDescriptor(op_tensor_desc) }.into_result("cudnnCreateOpTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnCreateOpTensorDescriptor(op_tensor_desc) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_destroy_op_tensor_descriptor(op_tensor_desc: *mut _OpTensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDestroyOpTensorDescriptor(op_tensor_desc) }.into_result("cudnnDestroyOpTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDestroyOpTensorDescriptor(op_tensor_desc) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_set_op_tensor_descriptor(op_tensor_desc: *mut _OpTensorDescriptorStruct, op_tensor_op: CudnnOpTensorOp, op_tensor_comp_type: CudnnDataType, op_tensor_nan_opt: CudnnNanPropagation) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetOpTensorDescriptor(op_tensor_desc, op_tensor_op, op_tensor_comp_type, op_tensor_nan_opt) }.into_result("cudnnSetOpTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetOpTensorDescriptor(op_tensor_desc, op_tensor_op, op_tensor_comp_type, op_tensor_nan_opt) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_op_tensor_descriptor(op_tensor_desc: *const _OpTensorDescriptorStruct, op_tensor_op: *mut CudnnOpTensorOp, op_tensor_comp_type: *mut CudnnDataType, op_tensor_nan_opt: *mut CudnnNanPropagation) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetOpTensorDescriptor(op_tensor_desc, op_tensor_op, op_tensor_comp_type, op_tensor_nan_opt) }.into_result("cudnnGetOpTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetOpTensorDescriptor(op_tensor_desc, op_tensor_op, op_tensor_comp_type, op_tensor_nan_opt) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_op_tensor(handle: *mut _CudnnStruct, op_tensor_desc: *const _OpTensorDescriptorStruct, alpha1: *const c_void, a_desc: *const _TensorDescriptorStruct, a: *const c_void, alpha2: *const c_void, b_desc: *const _TensorDescriptorStruct, b: *const c_void, beta: *const c_void, c_desc: *const _TensorDescriptorStruct, c: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnOpTensor(handle, op_tensor_desc, alpha1, a_desc, a, alpha2, b_desc, b, beta, c_desc, c) }.into_result("cudnnOpTensor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnOpTensor(handle, op_tensor_desc, alpha1, a_desc, a, alpha2, b_desc, b, beta, c_desc, c) };
+        Ok(())
+    }
+}
+
+
+
+
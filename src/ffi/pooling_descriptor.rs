@@ -0,0 +1,186 @@
+
+use std::os::raw::c_void;
+use super::{CudnnStatus, CudnnError, CudnnPoolingMode, CudnnNanPropagation};
+use super::cudnn::_CudnnStruct;
+use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
+
+
+
+pub enum _PoolingDescriptorStruct {}
+
+
+
+
+cudnn_api! {
+    /// Pooling descriptors, forward and backward pooling
+    pub trait PoolingApi {
+
+        fn cudnnCreatePoolingDescriptor(poolingDesc: *mut*mut _PoolingDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyPoolingDescriptor(poolingDesc: *mut _PoolingDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetPooling2dDescriptor(
+            poolingDesc: *mut _PoolingDescriptorStruct,
+            mode: CudnnPoolingMode,
+            maxpoolingNanOpt: CudnnNanPropagation,
+            windowHeight: i32,
+            windowWidth: i32,
+            verticalPadding: i32,
+            horizontalPadding: i32,
+            verticalStride: i32,
+            horizontalStride: i32,
+        ) -> CudnnStatus;
+
+        fn cudnnSetPoolingNdDescriptor(
+            poolingDesc: *mut _PoolingDescriptorStruct,
+            mode: CudnnPoolingMode,
+            maxpoolingNanOpt: CudnnNanPropagation,
+            nbDims: i32,
+            windowDimA: *const i32,
+            paddingA: *const i32,
+            strideA: *const i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetPoolingNdDescriptor(
+            poolingDesc: *const _PoolingDescriptorStruct,
+            nbDimsRequested: i32,
+            mode: *mut CudnnPoolingMode,
+            maxpoolingNanOpt: *mut CudnnNanPropagation,
+            nbDims: *mut i32,
+            windowDimA: *mut i32,
+            paddingA: *mut i32,
+            strideA: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnGetPoolingNdForwardOutputDim(
+            poolingDesc: *const _PoolingDescriptorStruct,
+            inputTensorDesc: *const _TensorDescriptorStruct,
+            nbDims: i32,
+            outputTensorDimA: *mut i32,
+        ) -> CudnnStatus;
+
+        fn cudnnPoolingForward(
+            handle: *mut _CudnnStruct,
+            poolingDesc: *const _PoolingDescriptorStruct,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnPoolingBackward(
+            handle: *mut _CudnnStruct,
+            poolingDesc: *const _PoolingDescriptorStruct,
+            alpha: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            dxDesc: *const _TensorDescriptorStruct,
+            dx: *mut c_void,
+        ) -> CudnnStatus;
+
+    }
+}
+
+
+
+
+
+#[inline]
+pub fn cudnn_create_pooling_descriptor(pooling_desc: *mut*mut _PoolingDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnCreatePoolingDescriptor(pooling_desc) }.into_result("cudnnCreatePoolingDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnCreatePoolingDescriptor(pooling_desc) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_destroy_pooling_descriptor(pooling_desc: *mut _PoolingDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDestroyPoolingDescriptor(pooling_desc) }.into_result("cudnnDestroyPoolingDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDestroyPoolingDescriptor(pooling_desc) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_set_pooling2d_descriptor(pooling_desc: *mut _PoolingDescriptorStruct, mode: CudnnPoolingMode, maxpooling_nan_opt: CudnnNanPropagation, window_height: i32, window_width: i32, vertical_padding: i32, horizontal_padding: i32, vertical_stride: i32, horizontal_stride: i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetPooling2dDescriptor(pooling_desc, mode, maxpooling_nan_opt, window_height, window_width, vertical_padding, horizontal_padding, vertical_stride, horizontal_stride) }.into_result("cudnnSetPooling2dDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetPooling2dDescriptor(pooling_desc, mode, maxpooling_nan_opt, window_height, window_width, vertical_padding, horizontal_padding, vertical_stride, horizontal_stride) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_set_pooling_nd_descriptor(pooling_desc: *mut _PoolingDescriptorStruct, mode: CudnnPoolingMode, maxpooling_nan_opt: CudnnNanPropagation, nb_dims: i32, window_dim_a: *const i32, padding_a: *const i32, stride_a: *const i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetPoolingNdDescriptor(pooling_desc, mode, maxpooling_nan_opt, nb_dims, window_dim_a, padding_a, stride_a) }.into_result("cudnnSetPoolingNdDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetPoolingNdDescriptor(pooling_desc, mode, maxpooling_nan_opt, nb_dims, window_dim_a, padding_a, stride_a) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_pooling_nd_descriptor(pooling_desc: *const _PoolingDescriptorStruct, nb_dims_requested: i32, mode: *mut CudnnPoolingMode, maxpooling_nan_opt: *mut CudnnNanPropagation, nb_dims: *mut i32, window_dim_a: *mut i32, padding_a: *mut i32, stride_a: *mut i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetPoolingNdDescriptor(pooling_desc, nb_dims_requested, mode, maxpooling_nan_opt, nb_dims, window_dim_a, padding_a, stride_a) }.into_result("cudnnGetPoolingNdDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetPoolingNdDescriptor(pooling_desc, nb_dims_requested, mode, maxpooling_nan_opt, nb_dims, window_dim_a, padding_a, stride_a) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_get_pooling_nd_forward_output_dim(pooling_desc: *const _PoolingDescriptorStruct, input_tensor_desc: *const _TensorDescriptorStruct, nb_dims: i32, output_tensor_dim_a: *mut i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnGetPoolingNdForwardOutputDim(pooling_desc, input_tensor_desc, nb_dims, output_tensor_dim_a) }.into_result("cudnnGetPoolingNdForwardOutputDim")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnGetPoolingNdForwardOutputDim(pooling_desc, input_tensor_desc, nb_dims, output_tensor_dim_a) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_pooling_forward(handle: *mut _CudnnStruct, pooling_desc: *const _PoolingDescriptorStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnPoolingForward(handle, pooling_desc, alpha, x_desc, x, beta, y_desc, y) }.into_result("cudnnPoolingForward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnPoolingForward(handle, pooling_desc, alpha, x_desc, x, beta, y_desc, y) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_pooling_backward(handle: *mut _CudnnStruct, pooling_desc: *const _PoolingDescriptorStruct, alpha: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnPoolingBackward(handle, pooling_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) }.into_result("cudnnPoolingBackward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnPoolingBackward(handle, pooling_desc, alpha, y_desc, y, dy_desc, dy, x_desc, x, beta, dx_desc, dx) };
+        Ok(())
+    }
+}
+
+
+
+
@@ -1,5 +1,9 @@
 
-use super::{CudnnStatus, CudnnDataType, CudnnNanPropagation, CudnnReduceTensorOp, CudnnReduceTensorIndices, CudnnIndicesType};
+use std::os::raw::c_void;
+use super::{CudnnStatus, CudnnError, CudnnDataType, CudnnNanPropagation, CudnnReduceTensorOp, CudnnReduceTensorIndices, CudnnIndicesType};
+use super::cudnn::_CudnnStruct;
+use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
 
 
 
@@ -8,76 +12,147 @@ pub enum _ReduceTensorDescriptorStruct {}
 
 
 
-#[allow(non_snake_case)]
-extern {
+cudnn_api! {
+    /// Reduce tensor descriptors and reductions
+    pub trait ReduceTensorApi {
+
+        fn cudnnCreateReduceTensorDescriptor(reduceTensorDesc: *mut*mut _ReduceTensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyReduceTensorDescriptor(reduceTensorDesc: *mut _ReduceTensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetReduceTensorDescriptor(
+            reduceTensorDesc: *mut _ReduceTensorDescriptorStruct,
+            reduceTensorOp: CudnnReduceTensorOp,
+            reduceTensorCompType: CudnnDataType,
+            reduceTensorNanOpt: CudnnNanPropagation,
+            reduceTensorIndices: CudnnReduceTensorIndices,
+            reduceTensorIndicesType: CudnnIndicesType
+        ) -> CudnnStatus;
+
+        fn cudnnGetReduceTensorDescriptor(
+            reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+            reduceTensorOp: &mut CudnnReduceTensorOp,
+            reduceTensorCompType: &mut CudnnDataType,
+            reduceTensorNanOpt: &mut CudnnNanPropagation,
+            reduceTensorIndices: &mut CudnnReduceTensorIndices,
+            reduceTensorIndicesType: &mut CudnnIndicesType
+        ) -> CudnnStatus;
+
+        fn cudnnGetReductionIndicesSize(
+            handle: *mut _CudnnStruct,
+            reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+            aDesc: *const _TensorDescriptorStruct,
+            cDesc: *const _TensorDescriptorStruct,
+            sizeInBytes: *mut usize,
+        ) -> CudnnStatus;
+
+        fn cudnnGetReductionWorkspaceSize(
+            handle: *mut _CudnnStruct,
+            reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+            aDesc: *const _TensorDescriptorStruct,
+            cDesc: *const _TensorDescriptorStruct,
+            sizeInBytes: *mut usize,
+        ) -> CudnnStatus;
+
+        fn cudnnReduceTensor(
+            handle: *mut _CudnnStruct,
+            reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
+            indices: *mut c_void,
+            indicesSizeInBytes: usize,
+            workspace: *mut c_void,
+            workspaceSizeInBytes: usize,
+            alpha: *const c_void,
+            aDesc: *const _TensorDescriptorStruct,
+            A: *const c_void,
+            beta: *const c_void,
+            cDesc: *const _TensorDescriptorStruct,
+            C: *mut c_void,
+        ) -> CudnnStatus;
 
-    fn cudnnCreateReduceTensorDescriptor(reduceTensorDesc: *mut*mut _ReduceTensorDescriptorStruct) -> CudnnStatus;
-
-    fn cudnnDestroyReduceTensorDescriptor(reduceTensorDesc: *mut _ReduceTensorDescriptorStruct) -> CudnnStatus;
+    }
+}
 
-    fn cudnnSetReduceTensorDescriptor(
-        reduceTensorDesc: *mut _ReduceTensorDescriptorStruct,
-        reduceTensorOp: CudnnReduceTensorOp,
-        reduceTensorCompType: CudnnDataType,
-        reduceTensorNanOpt: CudnnNanPropagation,
-        reduceTensorIndices: CudnnReduceTensorIndices,
-        reduceTensorIndicesType: CudnnIndicesType
-    ) -> CudnnStatus;
 
-    fn cudnnGetReduceTensorDescriptor(
-        reduceTensorDesc: *const _ReduceTensorDescriptorStruct,
-        reduceTensorOp: &mut CudnnReduceTensorOp,
-        reduceTensorCompType: &mut CudnnDataType,
-        reduceTensorNanOpt: &mut CudnnNanPropagation,
-        reduceTensorIndices: &mut CudnnReduceTensorIndices,
-        reduceTensorIndicesType: &mut CudnnIndicesType
-    ) -> CudnnStatus;
 
-}
 
 
+#[inline]
+pub fn cudnn_create_reduce_tensor_descriptor(reduce_tensor_desc: *mut*mut _ReduceTensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnCreateReduceTensorDescriptor(reduce_tensor_desc) }.into_result("cudnnCreateReduceTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnCreateReduceTensorDescriptor(reduce_tensor_desc) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_destroy_reduce_tensor_descriptor(reduce_tensor_desc: *mut _ReduceTensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDestroyReduceTensorDescriptor(reduce_tensor_desc) }.into_result("cudnnDestroyReduceTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDestroyReduceTensorDescriptor(reduce_tensor_desc) };
+        Ok(())
+    }
+}
 
+#[inline]
+pub fn cudnn_set_reduce_tensor_descriptor(reduce_tensor_desc: *mut _ReduceTensorDescriptorStruct, reduce_tensor_op: CudnnReduceTensorOp, reduce_tensor_comp_type: CudnnDataType, reduce_tensor_nan_opt: CudnnNanPropagation, reduce_tensor_indices: CudnnReduceTensorIndices, reduce_tensor_indices_type: CudnnIndicesType) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) }.into_result("cudnnSetReduceTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) };
+        Ok(())
+    }
+}
 
 #[inline]
-pub fn cudnn_create_reduce_tensor_descriptor(reduce_tensor_desc: *mut*mut _ReduceTensorDescriptorStruct) {
+pub fn cudnn_get_reduce_tensor_descriptor(reduce_tensor_desc: *const _ReduceTensorDescriptorStruct, reduce_tensor_op: &mut CudnnReduceTensorOp, reduce_tensor_comp_type: &mut CudnnDataType, reduce_tensor_nan_opt: &mut CudnnNanPropagation, reduce_tensor_indices: &mut CudnnReduceTensorIndices, reduce_tensor_indices_type: &mut CudnnIndicesType) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreateReduceTensorDescriptor(reduce_tensor_desc) }.assert_success();
+        unsafe { Backend::cudnnGetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) }.into_result("cudnnGetReduceTensorDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreateReduceTensorDescriptor(reduce_tensor_desc) };
+        unsafe { Backend::cudnnGetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy_reduce_tensor_descriptor(reduce_tensor_desc: *mut _ReduceTensorDescriptorStruct) {
+pub fn cudnn_get_reduction_indices_size(handle: *mut _CudnnStruct, reduce_tensor_desc: *const _ReduceTensorDescriptorStruct, a_desc: *const _TensorDescriptorStruct, c_desc: *const _TensorDescriptorStruct, size_in_bytes: *mut usize) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroyReduceTensorDescriptor(reduce_tensor_desc) }.assert_success();
+        unsafe { Backend::cudnnGetReductionIndicesSize(handle, reduce_tensor_desc, a_desc, c_desc, size_in_bytes) }.into_result("cudnnGetReductionIndicesSize")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroyReduceTensorDescriptor(reduce_tensor_desc) };
+        unsafe { Backend::cudnnGetReductionIndicesSize(handle, reduce_tensor_desc, a_desc, c_desc, size_in_bytes) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_reduce_tensor_descriptor(reduce_tensor_desc: *mut _ReduceTensorDescriptorStruct, reduce_tensor_op: CudnnReduceTensorOp, reduce_tensor_comp_type: CudnnDataType, reduce_tensor_nan_opt: CudnnNanPropagation, reduce_tensor_indices: CudnnReduceTensorIndices, reduce_tensor_indices_type: CudnnIndicesType) {
+pub fn cudnn_get_reduction_workspace_size(handle: *mut _CudnnStruct, reduce_tensor_desc: *const _ReduceTensorDescriptorStruct, a_desc: *const _TensorDescriptorStruct, c_desc: *const _TensorDescriptorStruct, size_in_bytes: *mut usize) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) }.assert_success();
+        unsafe { Backend::cudnnGetReductionWorkspaceSize(handle, reduce_tensor_desc, a_desc, c_desc, size_in_bytes) }.into_result("cudnnGetReductionWorkspaceSize")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) };
+        unsafe { Backend::cudnnGetReductionWorkspaceSize(handle, reduce_tensor_desc, a_desc, c_desc, size_in_bytes) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_reduce_tensor_descriptor(reduce_tensor_desc: *const _ReduceTensorDescriptorStruct, reduce_tensor_op: &mut CudnnReduceTensorOp, reduce_tensor_comp_type: &mut CudnnDataType, reduce_tensor_nan_opt: &mut CudnnNanPropagation, reduce_tensor_indices: &mut CudnnReduceTensorIndices, reduce_tensor_indices_type: &mut CudnnIndicesType) {
+pub fn cudnn_reduce_tensor(handle: *mut _CudnnStruct, reduce_tensor_desc: *const _ReduceTensorDescriptorStruct, indices: *mut c_void, indices_size_in_bytes: usize, workspace: *mut c_void, workspace_size_in_bytes: usize, alpha: *const c_void, a_desc: *const _TensorDescriptorStruct, a: *const c_void, beta: *const c_void, c_desc: *const _TensorDescriptorStruct, c: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) }.assert_success();
+        unsafe { Backend::cudnnReduceTensor(handle, reduce_tensor_desc, indices, indices_size_in_bytes, workspace, workspace_size_in_bytes, alpha, a_desc, a, beta, c_desc, c) }.into_result("cudnnReduceTensor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetReduceTensorDescriptor(reduce_tensor_desc, reduce_tensor_op, reduce_tensor_comp_type, reduce_tensor_nan_opt, reduce_tensor_indices, reduce_tensor_indices_type) };
+        unsafe { Backend::cudnnReduceTensor(handle, reduce_tensor_desc, indices, indices_size_in_bytes, workspace, workspace_size_in_bytes, alpha, a_desc, a, beta, c_desc, c) };
+        Ok(())
     }
 }
 
 
 
+
@@ -0,0 +1,72 @@
+
+use std::os::raw::c_void;
+use super::{CudnnStatus, CudnnError, CudnnSoftmaxAlgorithm, CudnnSoftmaxMode};
+use super::cudnn::_CudnnStruct;
+use super::tensor_descriptor::_TensorDescriptorStruct;
+use super::backend::Backend;
+
+
+
+
+cudnn_api! {
+    /// Forward and backward softmax
+    pub trait SoftmaxApi {
+
+        fn cudnnSoftmaxForward(
+            handle: *mut _CudnnStruct,
+            algo: CudnnSoftmaxAlgorithm,
+            mode: CudnnSoftmaxMode,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+        ) -> CudnnStatus;
+
+        fn cudnnSoftmaxBackward(
+            handle: *mut _CudnnStruct,
+            algo: CudnnSoftmaxAlgorithm,
+            mode: CudnnSoftmaxMode,
+            alpha: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *const c_void,
+            dyDesc: *const _TensorDescriptorStruct,
+            dy: *const c_void,
+            beta: *const c_void,
+            dxDesc: *const _TensorDescriptorStruct,
+            dx: *mut c_void,
+        ) -> CudnnStatus;
+
+    }
+}
+
+
+
+
+
+#[inline]
+pub fn cudnn_softmax_forward(handle: *mut _CudnnStruct, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSoftmaxForward(handle, algo, mode, alpha, x_desc, x, beta, y_desc, y) }.into_result("cudnnSoftmaxForward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSoftmaxForward(handle, algo, mode, alpha, x_desc, x, beta, y_desc, y) };
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn cudnn_softmax_backward(handle: *mut _CudnnStruct, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, alpha: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *const c_void, dy_desc: *const _TensorDescriptorStruct, dy: *const c_void, beta: *const c_void, dx_desc: *const _TensorDescriptorStruct, dx: *mut c_void) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSoftmaxBackward(handle, algo, mode, alpha, y_desc, y, dy_desc, dy, beta, dx_desc, dx) }.into_result("cudnnSoftmaxBackward")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSoftmaxBackward(handle, algo, mode, alpha, y_desc, y, dy_desc, dy, beta, dx_desc, dx) };
+        Ok(())
+    }
+}
+
+
+
+
@@ -1,6 +1,7 @@
 
 use std::os::raw::c_void;
-use super::{CudnnStatus, CudnnDataType, CudnnTensorFormat, cudnn::_CudnnStruct};
+use super::{CudnnStatus, CudnnError, CudnnDataType, CudnnTensorFormat, cudnn::_CudnnStruct};
+use super::backend::Backend;
 
 
 
@@ -9,125 +10,194 @@ pub enum _TensorDescriptorStruct {}
 
 
 
-#[allow(non_snake_case)]
-extern {
+cudnn_api! {
+    /// Tensor descriptors, and the operations applying to a single tensor
+    pub trait TensorApi {
+
+        fn cudnnCreateTensorDescriptor(tensorDesc: *mut*mut _TensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnDestroyTensorDescriptor(tensorDesc: *mut _TensorDescriptorStruct) -> CudnnStatus;
+
+        fn cudnnSetTensorNdDescriptor(
+            tensorDesc: *mut _TensorDescriptorStruct,
+            dataType: CudnnDataType,
+            nbDims: i32,
+            dimA: *const i32,
+            strideA: *const i32
+        ) -> CudnnStatus;
+
+        fn cudnnSetTensor4dDescriptor(
+            tensorDesc: *mut _TensorDescriptorStruct,
+            format: CudnnTensorFormat,
+            dataType: CudnnDataType,
+            n: i32,
+            c: i32,
+            h: i32,
+            w: i32
+        ) -> CudnnStatus;
+
+        fn cudnnGetTensorNdDescriptor(
+            tensorDesc: *mut _TensorDescriptorStruct,
+            nbDimsRequested: i32,
+            dataType: *mut CudnnDataType,
+            nbDims: *mut i32,
+            dimA: *mut i32,
+            strideA: *mut i32
+        ) -> CudnnStatus;
+
+        fn cudnnGetTensorSizeInBytes(
+            tensorDesc: *mut _TensorDescriptorStruct,
+            size: &mut usize
+        ) -> CudnnStatus;
+
+        fn cudnnSetTensor(
+            handle: *mut _CudnnStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+            valuePtr: *const c_void
+        ) -> CudnnStatus;
+
+        fn cudnnTransformTensor(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            xDesc: *const _TensorDescriptorStruct,
+            x: *const c_void,
+            beta: *const c_void,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void
+        ) -> CudnnStatus;
+
+        fn cudnnAddTensor(
+            handle: *mut _CudnnStruct,
+            alpha: *const c_void,
+            aDesc: *const _TensorDescriptorStruct,
+            A: *const c_void,
+            beta: *const c_void,
+            cDesc: *const _TensorDescriptorStruct,
+            C: *mut c_void
+        ) -> CudnnStatus;
+
+        fn cudnnScaleTensor(
+            handle: *mut _CudnnStruct,
+            yDesc: *const _TensorDescriptorStruct,
+            y: *mut c_void,
+            alpha: *const c_void
+        ) -> CudnnStatus;
 
-    fn cudnnCreateTensorDescriptor(tensorDesc: *mut*mut _TensorDescriptorStruct) -> CudnnStatus;
-
-    fn cudnnDestroyTensorDescriptor(tensorDesc: *mut _TensorDescriptorStruct) -> CudnnStatus;
-
-    fn cudnnSetTensorNdDescriptor(
-        tensorDesc: *mut _TensorDescriptorStruct,
-        dataType: CudnnDataType,
-        nbDims: i32,
-        dimA: *const i32,
-        strideA: *const i32
-    ) -> CudnnStatus;
+    }
+}
 
-    fn cudnnSetTensor4dDescriptor(
-        tensorDesc: *mut _TensorDescriptorStruct,
-        format: CudnnTensorFormat,
-        dataType: CudnnDataType,
-        n: i32,
-        c: i32,
-        h: i32,
-        w: i32
-    ) -> CudnnStatus;
 
-    fn cudnnGetTensorNdDescriptor(
-        tensorDesc: *mut _TensorDescriptorStruct,
-        nbDimsRequested: i32,
-        dataType: *mut CudnnDataType,
-        nbDims: *mut i32,
-        dimA: *mut i32,
-        strideA: *mut i32
-    ) -> CudnnStatus;
 
-    fn cudnnGetTensorSizeInBytes(
-        tensorDesc: *mut _TensorDescriptorStruct,
-        size: &mut usize
-    ) -> CudnnStatus;
 
-    fn cudnnSetTensor(
-        handle: *mut _CudnnStruct,
-        yDesc: *const _TensorDescriptorStruct,
-        y: *mut c_void,
-        valuePtr: *const c_void
-    ) -> CudnnStatus;
 
+#[inline]
+pub fn cudnn_create_tensor_descriptor(tensor_desc: *mut*mut _TensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnCreateTensorDescriptor(tensor_desc) }.into_result("cudnnCreateTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnCreateTensorDescriptor(tensor_desc) };
+        Ok(())
+    }
 }
 
+#[inline]
+pub fn cudnn_destroy_tensor_descriptor(tensor_desc: *mut _TensorDescriptorStruct) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnDestroyTensorDescriptor(tensor_desc) }.into_result("cudnnDestroyTensorDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnDestroyTensorDescriptor(tensor_desc) };
+        Ok(())
+    }
+}
 
-
-
+#[inline]
+pub fn cudnn_set_tensor_nd_descriptor(tensor_desc: *mut _TensorDescriptorStruct, data_type: CudnnDataType, nb_dims: i32, dim_a: *const i32, stride_a: *const i32) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        unsafe { Backend::cudnnSetTensorNdDescriptor(tensor_desc, data_type, nb_dims, dim_a, stride_a) }.into_result("cudnnSetTensorNdDescriptor")
+    }
+    #[cfg(feature = "disable_checks")] {
+        unsafe { Backend::cudnnSetTensorNdDescriptor(tensor_desc, data_type, nb_dims, dim_a, stride_a) };
+        Ok(())
+    }
+}
 
 #[inline]
-pub fn cudnn_create_tensor_descriptor(tensor_desc: *mut*mut _TensorDescriptorStruct) {
+pub fn cudnn_set_tensor4d_descriptor(tensor_desc: *mut _TensorDescriptorStruct, format: CudnnTensorFormat, data_type: CudnnDataType, n: i32, c: i32, h: i32, w: i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnCreateTensorDescriptor(tensor_desc) }.assert_success();
+        unsafe { Backend::cudnnSetTensor4dDescriptor(tensor_desc, format, data_type, n, c, h, w) }.into_result("cudnnSetTensor4dDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnCreateTensorDescriptor(tensor_desc) };
+        unsafe { Backend::cudnnSetTensor4dDescriptor(tensor_desc, format, data_type, n, c, h, w) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_destroy_tensor_descriptor(tensor_desc: *mut _TensorDescriptorStruct) {
+pub fn cudnn_get_tensor_nd_descriptor(tensor_desc: *mut _TensorDescriptorStruct, nb_dims_requested: i32, data_type: *mut CudnnDataType, nb_dims: *mut i32, dim_a: *mut i32, stride_a: *mut i32) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnDestroyTensorDescriptor(tensor_desc) }.assert_success();
+        unsafe { Backend::cudnnGetTensorNdDescriptor(tensor_desc, nb_dims_requested, data_type, nb_dims, dim_a, stride_a) }.into_result("cudnnGetTensorNdDescriptor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnDestroyTensorDescriptor(tensor_desc) };
+        unsafe { Backend::cudnnGetTensorNdDescriptor(tensor_desc, nb_dims_requested, data_type, nb_dims, dim_a, stride_a) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_tensor_nd_descriptor(tensor_desc: *mut _TensorDescriptorStruct, data_type: CudnnDataType, nb_dims: i32, dim_a: *const i32, stride_a: *const i32) {
+pub fn cudnn_get_tensor_size_in_bytes(tensor_desc: *mut _TensorDescriptorStruct, size: &mut usize) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetTensorNdDescriptor(tensor_desc, data_type, nb_dims, dim_a, stride_a) }.assert_success();
+        unsafe { Backend::cudnnGetTensorSizeInBytes(tensor_desc, size) }.into_result("cudnnGetTensorSizeInBytes")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetTensorNdDescriptor(tensor_desc, data_type, nb_dims, dim_a, stride_a) };
+        unsafe { Backend::cudnnGetTensorSizeInBytes(tensor_desc, size) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_tensor4d_descriptor(tensor_desc: *mut _TensorDescriptorStruct, format: CudnnTensorFormat, data_type: CudnnDataType, n: i32, c: i32, h: i32, w: i32) {
+pub fn cudnn_set_tensor(handle: *mut _CudnnStruct, y_desc: *const _TensorDescriptorStruct, y: *mut c_void, value_ptr: *const c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetTensor4dDescriptor(tensor_desc, format, data_type, n, c, h, w) }.assert_success();
+        unsafe { Backend::cudnnSetTensor(handle, y_desc, y, value_ptr) }.into_result("cudnnSetTensor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetTensor4dDescriptor(tensor_desc, format, data_type, n, c, h, w) };
+        unsafe { Backend::cudnnSetTensor(handle, y_desc, y, value_ptr) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_tensor_nd_descriptor(tensor_desc: *mut _TensorDescriptorStruct, nb_dims_requested: i32, data_type: *mut CudnnDataType, nb_dims: *mut i32, dim_a: *mut i32, stride_a: *mut i32) {
+pub fn cudnn_transform_tensor(handle: *mut _CudnnStruct, alpha: *const c_void, x_desc: *const _TensorDescriptorStruct, x: *const c_void, beta: *const c_void, y_desc: *const _TensorDescriptorStruct, y: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetTensorNdDescriptor(tensor_desc, nb_dims_requested, data_type, nb_dims, dim_a, stride_a) }.assert_success();
+        unsafe { Backend::cudnnTransformTensor(handle, alpha, x_desc, x, beta, y_desc, y) }.into_result("cudnnTransformTensor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetTensorNdDescriptor(tensor_desc, nb_dims_requested, data_type, nb_dims, dim_a, stride_a) };
+        unsafe { Backend::cudnnTransformTensor(handle, alpha, x_desc, x, beta, y_desc, y) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_get_tensor_size_in_bytes(tensor_desc: *mut _TensorDescriptorStruct, size: &mut usize) {
+pub fn cudnn_add_tensor(handle: *mut _CudnnStruct, alpha: *const c_void, a_desc: *const _TensorDescriptorStruct, a: *const c_void, beta: *const c_void, c_desc: *const _TensorDescriptorStruct, c: *mut c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnGetTensorSizeInBytes(tensor_desc, size) }.assert_success();
+        unsafe { Backend::cudnnAddTensor(handle, alpha, a_desc, a, beta, c_desc, c) }.into_result("cudnnAddTensor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnGetTensorSizeInBytes(tensor_desc, size) };
+        unsafe { Backend::cudnnAddTensor(handle, alpha, a_desc, a, beta, c_desc, c) };
+        Ok(())
     }
 }
 
 #[inline]
-pub fn cudnn_set_tensor(handle: *mut _CudnnStruct, y_desc: *const _TensorDescriptorStruct, y: *mut c_void, value_ptr: *const c_void) {
+pub fn cudnn_scale_tensor(handle: *mut _CudnnStruct, y_desc: *const _TensorDescriptorStruct, y: *mut c_void, alpha: *const c_void) -> Result<(), CudnnError> {
     #[cfg(not(feature = "disable_checks"))] {
-        unsafe { cudnnSetTensor(handle, y_desc, y, value_ptr) }.assert_success();
+        unsafe { Backend::cudnnScaleTensor(handle, y_desc, y, alpha) }.into_result("cudnnScaleTensor")
     }
     #[cfg(feature = "disable_checks")] {
-        unsafe { cudnnSetTensor(handle, y_desc, y, value_ptr) };
+        unsafe { Backend::cudnnScaleTensor(handle, y_desc, y, alpha) };
+        Ok(())
     }
 }
 
@@ -2,67 +2,90 @@
 use std::ptr;
 use std::marker::PhantomData;
 use std::fmt::{self, Debug};
-use cumath::CuDataType;
+use cumath::{CuDataType, CuVectorDeref};
+use cudnn_type::CudnnType;
 use ffi::*;
+use super::*;
 
 
-pub struct CuFilterDescriptor<T: CuDataType> {
+pub struct CuFilterDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
     pub(crate) data: *mut _FilterDescriptorStruct,
+    data_len: usize,
 }
 
-impl<T: CuDataType> Drop for CuFilterDescriptor<T> {
+impl<T: CudnnType> Drop for CuFilterDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_filter_descriptor(self.data)
+        let _ = cudnn_destroy_filter_descriptor(self.data);
     }
 }
 
-impl<T: CuDataType> CuFilterDescriptor<T> {
+impl<T: CudnnType> CuFilterDescriptor<T> {
 
-    /*pub fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> CuTensor<'a, T> {
-        #[cfg(not(feature = "disable_checks"))] {
-            assert_eq!(data.len(), self.data_len(), "data.len() != self.data_len()");
-        }
-        CuTensor { deref: CuTensorDeref { descriptor: self, data: data.as_ptr() as *mut T } }
+    pub(crate) fn create(data_len: usize) -> Result<CuFilterDescriptor<T>, CudnnError> {
+        let mut data = ptr::null_mut();
+        cudnn_create_filter_descriptor(&mut data)?;
+        Ok(CuFilterDescriptor { _phantom: PhantomData, data, data_len })
     }
 
-    pub fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> CuTensorMut<'a, T> {
-        #[cfg(not(feature = "disable_checks"))] {
-            assert_eq!(data.len(), self.data_len(), "data.len() != self.data_len()");
-        }
-        CuTensorMut { deref: CuTensorDeref { descriptor: self, data: data.as_mut_ptr() } }
-    }*/
+    /// Recomputes the data len of a descriptor that was set by cuDNN
+    pub(crate) fn update_data_len(&mut self) -> Result<(), CudnnError> {
+        let info = self.get_info(8)?;
+        self.data_len = info.filter_dims[..info.nb_dims as usize].iter().product::<i32>() as usize;
+        Ok(())
+    }
+
+    pub fn data_len(&self) -> usize {
+        self.data_len
+    }
 
-    pub fn get_info(&self, nb_dims_requested: i32) -> CuFilterDescriptorInfo {
+    pub fn get_info(&self, nb_dims_requested: i32) -> Result<CuFilterDescriptorInfo, CudnnError> {
         let mut data_type = CudnnDataType::Int8x4;
         let mut format = CudnnTensorFormat::Nchw;
         let mut nb_dims = -1;
         let mut filter_dims = vec![-1; nb_dims_requested as usize];
-        cudnn_get_filter_nd_descriptor(self.data, nb_dims_requested, &mut data_type, &mut format, &mut nb_dims, filter_dims.as_mut_ptr());
-        CuFilterDescriptorInfo { data_type, format, nb_dims, filter_dims }
+        cudnn_get_filter_nd_descriptor(self.data, nb_dims_requested, &mut data_type, &mut format, &mut nb_dims, filter_dims.as_mut_ptr())?;
+        Ok(CuFilterDescriptorInfo { data_type, format, nb_dims, filter_dims })
+    }
+
+    pub fn new(format: CudnnTensorFormat, filter_dims: &[i32]) -> Result<CuFilterDescriptor<T>, CudnnError> {
+        let output = Self::create(filter_dims.iter().product::<i32>() as usize)?;
+        cudnn_set_filter_nd_descriptor(output.data, T::data_type(), format, filter_dims.len() as i32, filter_dims.as_ptr())?;
+        Ok(output)
+    }
+
+    pub fn new_4d(format: CudnnTensorFormat, k: i32, c: i32, h: i32, w: i32) -> Result<CuFilterDescriptor<T>, CudnnError> {
+        let output = Self::create((k * c * h * w) as usize)?;
+        cudnn_set_filter4d_descriptor(output.data, T::data_type(), format, k, c, h, w)?;
+        Ok(output)
     }
 
 }
 
-impl CuFilterDescriptor<f32> {
+impl<T: CudnnType + CuDataType> CuFilterDescriptor<T> {
 
-    pub fn new(format: CudnnTensorFormat, filter_dims: &[i32]) -> CuFilterDescriptor<f32> {
-        let mut data = ptr::null_mut();
-        cudnn_create_filter_descriptor(&mut data);
-        cudnn_set_filter_nd_descriptor(data, CudnnDataType::Float, format, filter_dims.len() as i32, filter_dims.as_ptr());
-        CuFilterDescriptor { _phantom: PhantomData, data }
+    pub fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> Result<CuFilter<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuFilterDescriptor::link", "data.len() != self.data_len()"))
+            }
+        }
+        Ok(CuFilter { deref: CuFilterDeref { descriptor: self, data: data.as_ptr() as *mut T } })
     }
 
-    pub fn new_4d(format: CudnnTensorFormat, k: i32, c: i32, h: i32, w: i32) -> CuFilterDescriptor<f32> {
-        let mut data = ptr::null_mut();
-        cudnn_create_filter_descriptor(&mut data);
-        cudnn_set_filter4d_descriptor(data, CudnnDataType::Float, format, k, c, h, w);
-        CuFilterDescriptor { _phantom: PhantomData, data }
+    pub fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> Result<CuFilterMut<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuFilterDescriptor::link_mut", "data.len() != self.data_len()"))
+            }
+        }
+        Ok(CuFilterMut { deref: CuFilterDeref { descriptor: self, data: data.as_mut_ptr() } })
     }
 
 }
 
 
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub struct CuFilterDescriptorInfo {
     pub data_type: CudnnDataType,
     pub format: CudnnTensorFormat,
@@ -81,11 +104,12 @@ impl Debug for CuFilterDescriptorInfo {
 mod tests {
 
     use super::*;
+    use cudnn_type::Half;
 
     #[test]
     fn init_nchw() {
-        let descriptor = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[2, 4, 1, 7]);
-        let info = descriptor.get_info(4);
+        let descriptor = CuFilterDescriptor::<f32>::new(CudnnTensorFormat::Nchw, &[2, 4, 1, 7]).unwrap();
+        let info = descriptor.get_info(4).unwrap();
         assert_eq!(info.data_type, CudnnDataType::Float);
         assert_eq!(info.format, CudnnTensorFormat::Nchw);
         assert_eq!(info.nb_dims, 4);
@@ -94,6 +118,33 @@ mod tests {
         assert_eq!(info.filter_dims[1], 4);
         assert_eq!(info.filter_dims[2], 1);
         assert_eq!(info.filter_dims[3], 7);
+        assert_eq!(descriptor.data_len(), 56);
+    }
+
+    #[test]
+    fn init_data_types() {
+        let descriptor = CuFilterDescriptor::<f64>::new(CudnnTensorFormat::Nchw, &[2, 4, 1, 7]).unwrap();
+        assert_eq!(descriptor.get_info(4).unwrap().data_type, CudnnDataType::Double);
+        let descriptor = CuFilterDescriptor::<Half>::new_4d(CudnnTensorFormat::Nchw, 3, 2, 3, 3).unwrap();
+        assert_eq!(descriptor.get_info(4).unwrap().data_type, CudnnDataType::Half);
+        let descriptor = CuFilterDescriptor::<i8>::new_4d(CudnnTensorFormat::Nhwc, 3, 4, 3, 3).unwrap();
+        assert_eq!(descriptor.get_info(4).unwrap().data_type, CudnnDataType::Int8);
+    }
+
+    #[test]
+    fn link() {
+        use cumath::CuVector;
+
+        let descriptor = CuFilterDescriptor::<f32>::new_4d(CudnnTensorFormat::Nchw, 3, 2, 3, 3).unwrap();
+        let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
+
+        {
+            let _filter = descriptor.link(&data).unwrap();
+        }
+        let _filter = descriptor.link_mut(&mut data).unwrap();
+
+        let wrong_data = CuVector::<f32>::new(1.0, 9);
+        assert!(descriptor.link(&wrong_data).is_err());
     }
 
 }
\ No newline at end of file
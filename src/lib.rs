@@ -5,22 +5,42 @@ extern crate cumath;
 
 mod ffi;
 mod cudnn;
+mod cudnn_type;
 mod tensor;
 mod reduce_tensor_descriptor;
 mod activation_descriptor;
 mod convolution_descriptor;
-//mod rnn_descriptor;
+mod convolution_algorithm;
+mod pooling_descriptor;
+mod softmax;
+mod batch_normalization;
+mod op_tensor_descriptor;
+mod rnn_descriptor;
 mod filter;
-//mod dropout_descriptor;
+mod dropout_descriptor;
 
+#[cfg(any(test, feature = "cpu_reference"))]
+pub mod reference;
+#[cfg(test)]
+mod test_utils;
 
-pub use self::ffi::CudnnActivationMode;
+
+pub use self::ffi::{CudnnActivationMode, CudnnPoolingMode, CudnnSoftmaxAlgorithm, CudnnSoftmaxMode, CudnnBatchNormMode, CudnnOpTensorOp, CudnnReduceTensorOp, CudnnReduceTensorIndices, CudnnIndicesType, CudnnNanPropagation, CudnnStatus, CudnnError};
+pub use self::ffi::{CudnnConvolutionFwdAlgo, CudnnConvolutionBwdDataAlgo, CudnnConvolutionBwdFilterAlgo, CudnnDeterminism, CudnnMathType};
+pub use self::ffi::{CudnnConvolutionMode, CudnnTensorFormat, CudnnDataType};
+pub use self::ffi::{CudnnRNNMode, CudnnDirectionMode, CudnnRNNInputMode, CudnnRNNAlgo};
 
 pub use self::cudnn::*;
+pub use self::cudnn_type::*;
 pub use self::tensor::*;
 pub use self::reduce_tensor_descriptor::*;
 pub use self::activation_descriptor::*;
 pub use self::convolution_descriptor::*;
-//pub use self::rnn_descriptor::*;
+pub use self::convolution_algorithm::*;
+pub use self::pooling_descriptor::*;
+pub use self::softmax::*;
+pub use self::batch_normalization::*;
+pub use self::op_tensor_descriptor::*;
+pub use self::rnn_descriptor::*;
 pub use self::filter::*;
-//pub use self::dropout_descriptor::*;
\ No newline at end of file
+pub use self::dropout_descriptor::*;
\ No newline at end of file
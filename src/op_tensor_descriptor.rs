@@ -0,0 +1,142 @@
+
+use super::*;
+use super::ffi::*;
+use std::ptr;
+use std::marker::PhantomData;
+use std::os::raw::c_void;
+
+
+
+pub struct CuOpTensorDescriptor<T: CudnnType> {
+    _phantom: PhantomData<T>,
+    data: *mut _OpTensorDescriptorStruct,
+}
+
+impl<T: CudnnType> Drop for CuOpTensorDescriptor<T> {
+    fn drop(&mut self) {
+        let _ = cudnn_destroy_op_tensor_descriptor(self.data);
+    }
+}
+
+impl<T: CudnnType> CuOpTensorDescriptor<T> {
+
+    /// Operation on tensors of `T`, computing in doubles for f64 tensors and in floats for every other type,
+    /// integers included, as cuDNN doesn't support other compute types here.
+    pub fn new(op: CudnnOpTensorOp) -> Result<CuOpTensorDescriptor<T>, CudnnError> {
+        let mut data = ptr::null_mut();
+        cudnn_create_op_tensor_descriptor(&mut data)?;
+        let output = CuOpTensorDescriptor { _phantom: PhantomData, data };
+        let comp_type = match T::compute_type() {
+            CudnnDataType::Double => CudnnDataType::Double,
+            _ => CudnnDataType::Float,
+        };
+        cudnn_set_op_tensor_descriptor(output.data, op, comp_type, CudnnNanPropagation::Propagate)?;
+        Ok(output)
+    }
+
+    pub fn get_info(&self) -> Result<CuOpTensorDescriptorInfo, CudnnError> {
+        let mut op = CudnnOpTensorOp::Add;
+        let mut comp_type = CudnnDataType::Int8x4;
+        let mut nan_opt = CudnnNanPropagation::NotPropagate;
+        cudnn_get_op_tensor_descriptor(self.data, &mut op, &mut comp_type, &mut nan_opt)?;
+        Ok(CuOpTensorDescriptorInfo { op, comp_type, nan_opt })
+    }
+
+    /// Computes `c = op(alpha1 * a, alpha2 * b) + beta * c`.
+    /// `a` must have the dimensions of `c`, `b` may have dimensions of size 1 which are broadcast.
+    /// `b` is ignored by the unary Sqrt and Not operations.
+    pub fn apply(&self, cudnn: &mut Cudnn, alpha1: T::Scaling, a: &CuTensorDeref<T>, alpha2: T::Scaling, b: &CuTensorDeref<T>,
+                 beta: T::Scaling, c: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            let c_dims = c.descriptor.get_info()?.dimensions;
+            let a_dims = a.descriptor.get_info()?.dimensions;
+            if a_dims != c_dims {
+                return Err(CudnnError::bad_param("CuOpTensorDescriptor::apply", format!("a dimensions {:?} != c dimensions {:?}", a_dims, c_dims)))
+            }
+            check_broadcast("CuOpTensorDescriptor::apply", "b", &b.descriptor.get_info()?.dimensions, &c_dims)?;
+        }
+        cudnn_op_tensor(cudnn.handle, self.data,
+                        &alpha1 as *const T::Scaling as *const c_void, a.descriptor.data, a.data as *const c_void,
+                        &alpha2 as *const T::Scaling as *const c_void, b.descriptor.data, b.data as *const c_void,
+                        &beta as *const T::Scaling as *const c_void, c.descriptor.data, c.data as *mut c_void)
+    }
+
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CuOpTensorDescriptorInfo {
+    pub op: CudnnOpTensorOp,
+    pub comp_type: CudnnDataType,
+    pub nan_opt: CudnnNanPropagation,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::*;
+    use test_utils::{host_data, assert_close};
+
+    #[test]
+    fn get_info() {
+        let op_tensor = CuOpTensorDescriptor::<f32>::new(CudnnOpTensorOp::Mul).unwrap();
+        assert_eq!(op_tensor.get_info().unwrap(), CuOpTensorDescriptorInfo {
+            op: CudnnOpTensorOp::Mul,
+            comp_type: CudnnDataType::Float,
+            nan_opt: CudnnNanPropagation::Propagate,
+        });
+        let op_tensor = CuOpTensorDescriptor::<f64>::new(CudnnOpTensorOp::Add).unwrap();
+        assert_eq!(op_tensor.get_info().unwrap().comp_type, CudnnDataType::Double);
+        let op_tensor = CuOpTensorDescriptor::<i8>::new(CudnnOpTensorOp::Add).unwrap();
+        assert_eq!(op_tensor.get_info().unwrap().comp_type, CudnnDataType::Float);
+    }
+
+    #[test]
+    fn apply() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let dims = [2, 3, 2, 2];
+        let desc = CuTensorDescriptor::<f32>::fully_packed(&dims).unwrap();
+
+        let a_host = host_data(desc.data_len(), 1).iter().map(|x| x + 1.0).collect::<Vec<_>>();
+        let c_host = host_data(desc.data_len(), 3);
+        let a = CuVector::<f32>::from_host_data(&a_host);
+
+        for &b_dims in &[[2, 3, 2, 2], [1, 3, 1, 1], [2, 1, 2, 1]] {
+            let b_desc = CuTensorDescriptor::<f32>::fully_packed(&b_dims).unwrap();
+            let b_host = host_data(b_desc.data_len(), 2);
+            let b = CuVector::<f32>::from_host_data(&b_host);
+            for &op in &[CudnnOpTensorOp::Add, CudnnOpTensorOp::Mul, CudnnOpTensorOp::Min,
+                         CudnnOpTensorOp::Max, CudnnOpTensorOp::Sqrt, CudnnOpTensorOp::Not] {
+                let op_tensor = CuOpTensorDescriptor::new(op).unwrap();
+                let mut c = CuVector::<f32>::from_host_data(&c_host);
+                op_tensor.apply(&mut cudnn, 0.5, &desc.link(&a).unwrap(), 2.0, &b_desc.link(&b).unwrap(),
+                                0.25, &mut desc.link_mut(&mut c).unwrap()).unwrap();
+                let mut expected = c_host.clone();
+                reference::op_tensor(op, 0.5, &dims, &a_host, 2.0, &b_dims, &b_host, 0.25, &mut expected);
+                assert_close(&format!("{:?} {:?}", op, b_dims), &c, &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn apply_invalid_broadcast() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 2, 2]).unwrap();
+        let b_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 1, 1]).unwrap();
+        let a = CuVector::<f32>::zero(desc.data_len());
+        let b = CuVector::<f32>::zero(b_desc.data_len());
+        let mut c = CuVector::<f32>::zero(desc.data_len());
+
+        let op_tensor = CuOpTensorDescriptor::new(CudnnOpTensorOp::Add).unwrap();
+        let error = op_tensor.apply(&mut cudnn, 1.0, &desc.link(&a).unwrap(), 1.0, &b_desc.link(&b).unwrap(),
+                                    0.0, &mut desc.link_mut(&mut c).unwrap()).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+        let error = op_tensor.apply(&mut cudnn, 1.0, &b_desc.link(&b).unwrap(), 1.0, &desc.link(&a).unwrap(),
+                                    0.0, &mut desc.link_mut(&mut c).unwrap()).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+}
@@ -0,0 +1,285 @@
+
+use super::*;
+use super::ffi::*;
+use std::ptr;
+use std::os::raw::c_void;
+
+
+
+pub struct CuPoolingDescriptor {
+    data: *mut _PoolingDescriptorStruct,
+    nb_dims: i32,
+}
+
+impl Drop for CuPoolingDescriptor {
+    fn drop(&mut self) {
+        let _ = cudnn_destroy_pooling_descriptor(self.data);
+    }
+}
+
+impl CuPoolingDescriptor {
+
+    fn create(nb_dims: i32) -> Result<CuPoolingDescriptor, CudnnError> {
+        let mut data = ptr::null_mut();
+        cudnn_create_pooling_descriptor(&mut data)?;
+        Ok(CuPoolingDescriptor { data, nb_dims })
+    }
+
+    /// Pooling over `window_dims.len()` spatial dimensions, propagating NaNs.
+    /// See `CuPoolingDescriptorBuilder` for the other options.
+    pub fn new(mode: CudnnPoolingMode, window_dims: &[i32], paddings: &[i32], strides: &[i32]) -> Result<CuPoolingDescriptor, CudnnError> {
+        CuPoolingDescriptorBuilder::new(mode, window_dims).paddings(paddings).strides(strides).build()
+    }
+
+    pub fn new_2d(mode: CudnnPoolingMode, window_h: i32, window_w: i32, pad_h: i32, pad_w: i32, stride_h: i32, stride_w: i32) -> Result<CuPoolingDescriptor, CudnnError> {
+        let output = Self::create(2)?;
+        cudnn_set_pooling2d_descriptor(output.data, mode, CudnnNanPropagation::Propagate, window_h, window_w, pad_h, pad_w, stride_h, stride_w)?;
+        Ok(output)
+    }
+
+    pub fn get_info(&self) -> Result<CuPoolingDescriptorInfo, CudnnError> {
+        let len = self.nb_dims as usize;
+        let mut mode = CudnnPoolingMode::Max;
+        let mut maxpooling_nan_opt = CudnnNanPropagation::NotPropagate;
+        let mut nb_dims = -1;
+        let mut window_dims = vec![-1; len];
+        let mut paddings = vec![-1; len];
+        let mut strides = vec![-1; len];
+        cudnn_get_pooling_nd_descriptor(self.data, self.nb_dims, &mut mode, &mut maxpooling_nan_opt, &mut nb_dims,
+                                        window_dims.as_mut_ptr(), paddings.as_mut_ptr(), strides.as_mut_ptr())?;
+        Ok(CuPoolingDescriptorInfo { mode, maxpooling_nan_opt, nb_dims, window_dims, paddings, strides })
+    }
+
+    /// Dimensions of the output of the pooling, as computed by cuDNN.
+    pub fn get_forward_output_dims<T: CudnnType>(&self, input_desc: &CuTensorDescriptor<T>) -> Result<Vec<i32>, CudnnError> {
+        let nb_dims = self.nb_dims + 2;
+        let mut output = vec![-1; nb_dims as usize];
+        cudnn_get_pooling_nd_forward_output_dim(self.data, input_desc.data, nb_dims, output.as_mut_ptr())?;
+        Ok(output)
+    }
+
+    /// Fully packed descriptor of the output of the pooling.
+    pub fn get_forward_output_descriptor<T: CudnnType>(&self, input_desc: &CuTensorDescriptor<T>) -> Result<CuTensorDescriptor<T>, CudnnError> {
+        CuTensorDescriptor::<T>::fully_packed(&self.get_forward_output_dims(input_desc)?)
+    }
+
+    pub fn forward<T: CudnnType>(&self, cudnn: &Cudnn, input: &CuTensorDeref<T>, input_scale: T::Scaling, output: &mut CuTensorDeref<T>, output_scale: T::Scaling) -> Result<(), CudnnError> {
+        cudnn_pooling_forward(cudnn.handle, self.data,
+                              &input_scale as *const T::Scaling as *const c_void, input.descriptor.data, input.data as *const c_void,
+                              &output_scale as *const T::Scaling as *const c_void, output.descriptor.data, output.data as *mut c_void)
+    }
+    pub fn backward<T: CudnnType>(&self, cudnn: &Cudnn, alpha: T::Scaling, beta: T::Scaling,
+                                   input: &CuTensorDeref<T>,
+                                   output: &CuTensorDeref<T>,
+                                   output_signal: &CuTensorDeref<T>,
+                                   input_signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+        cudnn_pooling_backward(cudnn.handle, self.data,
+                               (&alpha) as *const T::Scaling as *const c_void,
+                               output.descriptor.data, output.data as *const c_void,
+                               output_signal.descriptor.data, output_signal.data as *const c_void,
+                               input.descriptor.data, input.data as *const c_void,
+                               (&beta) as *const T::Scaling as *const c_void,
+                               input_signal.descriptor.data, input_signal.data as *mut c_void)
+    }
+
+}
+
+
+/// Builds a `CuPoolingDescriptor` over `window_dims.len()` spatial dimensions.
+/// Defaults to no padding, unit strides, and NaN propagation by max pooling.
+pub struct CuPoolingDescriptorBuilder {
+    mode: CudnnPoolingMode,
+    window_dims: Vec<i32>,
+    paddings: Vec<i32>,
+    strides: Vec<i32>,
+    nan_opt: CudnnNanPropagation,
+}
+
+impl CuPoolingDescriptorBuilder {
+
+    pub fn new(mode: CudnnPoolingMode, window_dims: &[i32]) -> CuPoolingDescriptorBuilder {
+        CuPoolingDescriptorBuilder {
+            mode,
+            window_dims: window_dims.to_vec(),
+            paddings: vec![0; window_dims.len()],
+            strides: vec![1; window_dims.len()],
+            nan_opt: CudnnNanPropagation::Propagate,
+        }
+    }
+
+    pub fn paddings(mut self, paddings: &[i32]) -> CuPoolingDescriptorBuilder {
+        self.paddings = paddings.to_vec();
+        self
+    }
+
+    pub fn strides(mut self, strides: &[i32]) -> CuPoolingDescriptorBuilder {
+        self.strides = strides.to_vec();
+        self
+    }
+
+    /// Whether max pooling outputs NaN when its window contains one, or ignores it
+    pub fn nan_opt(mut self, nan_opt: CudnnNanPropagation) -> CuPoolingDescriptorBuilder {
+        self.nan_opt = nan_opt;
+        self
+    }
+
+    pub fn build(&self) -> Result<CuPoolingDescriptor, CudnnError> {
+        let len = self.window_dims.len();
+        #[cfg(not(feature = "disable_checks"))] {
+            if len != self.paddings.len() {
+                return Err(CudnnError::bad_param("CuPoolingDescriptorBuilder::build", "window_dims.len() != paddings.len()"))
+            }
+            if len != self.strides.len() {
+                return Err(CudnnError::bad_param("CuPoolingDescriptorBuilder::build", "window_dims.len() != strides.len()"))
+            }
+        }
+        let output = CuPoolingDescriptor::create(len as i32)?;
+        cudnn_set_pooling_nd_descriptor(output.data, self.mode, self.nan_opt, len as i32,
+                                        self.window_dims.as_ptr(), self.paddings.as_ptr(), self.strides.as_ptr())?;
+        Ok(output)
+    }
+
+}
+
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CuPoolingDescriptorInfo {
+    pub mode: CudnnPoolingMode,
+    pub maxpooling_nan_opt: CudnnNanPropagation,
+    pub nb_dims: i32,
+    pub window_dims: Vec<i32>,
+    pub paddings: Vec<i32>,
+    pub strides: Vec<i32>,
+}
+
+impl CuPoolingDescriptorInfo {
+
+    /// Dimensions of the output of the pooling, computed on the host.
+    /// Each spatial dimension is `1 + (input + 2 * padding - window) / stride`.
+    pub fn forward_output_dims(&self, input_dims: &[i32]) -> Result<Vec<i32>, CudnnError> {
+        let function = "CuPoolingDescriptorInfo::forward_output_dims";
+        if input_dims.len() != self.nb_dims as usize + 2 {
+            return Err(CudnnError::bad_param(function, format!("expected {} input dimensions, got {:?}", self.nb_dims + 2, input_dims)))
+        }
+        let mut output = input_dims[..2].to_vec();
+        for i in 0..self.nb_dims as usize {
+            let padded = input_dims[i+2] + 2 * self.paddings[i];
+            if padded < self.window_dims[i] {
+                return Err(CudnnError::bad_param(function, format!("window {} exceeds padded input size {} in spatial dimension {}", self.window_dims[i], padded, i)))
+            }
+            output.push(1 + (padded - self.window_dims[i]) / self.strides[i]);
+        }
+        Ok(output)
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::*;
+    use test_utils::assert_close;
+
+    #[test]
+    fn compare_2d_nd() {
+        let pooling_2d = CuPoolingDescriptor::new_2d(CudnnPoolingMode::AverageCountExcludePadding, 3, 2, 1, 0, 2, 1).unwrap();
+        let pooling_nd = CuPoolingDescriptor::new(CudnnPoolingMode::AverageCountExcludePadding, &[3, 2], &[1, 0], &[2, 1]).unwrap();
+        let info = pooling_2d.get_info().unwrap();
+        assert_eq!(info, pooling_nd.get_info().unwrap());
+        assert_eq!(info.mode, CudnnPoolingMode::AverageCountExcludePadding);
+        assert_eq!(info.window_dims, vec![3, 2]);
+        assert_eq!(info.paddings, vec![1, 0]);
+        assert_eq!(info.strides, vec![2, 1]);
+
+        assert!(CuPoolingDescriptor::new(CudnnPoolingMode::Max, &[3, 2], &[1], &[2, 1]).is_err());
+    }
+
+    #[test]
+    fn builder() {
+        let pooling = CuPoolingDescriptorBuilder::new(CudnnPoolingMode::Max, &[2, 2]).build().unwrap();
+        assert_eq!(pooling.get_info().unwrap(), CuPoolingDescriptor::new(CudnnPoolingMode::Max, &[2, 2], &[0, 0], &[1, 1]).unwrap().get_info().unwrap());
+        assert_eq!(pooling.get_info().unwrap().maxpooling_nan_opt, CudnnNanPropagation::Propagate);
+        assert!(CuPoolingDescriptorBuilder::new(CudnnPoolingMode::Max, &[2, 2]).strides(&[1]).build().is_err());
+
+        let cudnn = Cudnn::new().unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 2, 2]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 1, 1]).unwrap();
+        let input = CuVector::<f32>::from_host_data(&[1.0, ::std::f32::NAN, 3.0, 2.0]);
+        let mut output = CuVector::<f32>::zero(1);
+        for &(nan_opt, expected) in &[(CudnnNanPropagation::Propagate, ::std::f32::NAN), (CudnnNanPropagation::NotPropagate, 3.0)] {
+            let pooling = CuPoolingDescriptorBuilder::new(CudnnPoolingMode::Max, &[2, 2]).strides(&[2, 2]).nan_opt(nan_opt).build().unwrap();
+            assert_eq!(pooling.get_info().unwrap().maxpooling_nan_opt, nan_opt);
+            pooling.forward(&cudnn, &input_desc.link(&input).unwrap(), 1.0, &mut output_desc.link_mut(&mut output).unwrap(), 0.0).unwrap();
+            let mut buffer = [0.0];
+            output.clone_to_host(&mut buffer);
+            assert!(buffer[0] == expected || buffer[0].is_nan() && expected.is_nan(), "{:?} : {} != {}", nan_opt, buffer[0], expected);
+        }
+    }
+
+    #[test]
+    fn forward_output_dims() {
+        let pooling = CuPoolingDescriptor::new(CudnnPoolingMode::Max, &[3, 2, 2], &[1, 0, 1], &[2, 1, 3]).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 7, 5, 6]).unwrap();
+        let expected = pooling.get_info().unwrap().forward_output_dims(&[2, 3, 7, 5, 6]).unwrap();
+        assert_eq!(expected, vec![2, 3, 4, 4, 3]);
+        assert_eq!(pooling.get_forward_output_dims(&input_desc).unwrap(), expected);
+        assert_eq!(pooling.get_forward_output_descriptor(&input_desc).unwrap().get_info().unwrap().dimensions, expected);
+
+        assert!(pooling.get_info().unwrap().forward_output_dims(&[2, 3, 7, 5]).is_err());
+    }
+
+    fn test_pooling(mode: CudnnPoolingMode) {
+        let cudnn = Cudnn::new().unwrap();
+
+        let pooling = CuPoolingDescriptor::new_2d(mode, 3, 3, 1, 1, 2, 2).unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 2, 5, 6]).unwrap();
+        let output_desc = pooling.get_forward_output_descriptor(&input_desc).unwrap();
+        let info = pooling.get_info().unwrap();
+
+        // Distinct values, so that max pooling has no ties
+        let input_host = (0..input_desc.data_len()).map(|i| ((i * 37) % 127) as f32 / 10.0 - 6.0).collect::<Vec<_>>();
+        let output_signal_host = (0..output_desc.data_len()).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+        let input = CuVector::<f32>::from_host_data(&input_host);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_host);
+        let mut output = CuVector::<f32>::zero(output_desc.data_len());
+        let mut input_signal = CuVector::<f32>::new(1.0, input_desc.data_len());
+
+        pooling.forward(&cudnn, &input_desc.link(&input).unwrap(), 1.0, &mut output_desc.link_mut(&mut output).unwrap(), 0.0).unwrap();
+        let mut expected = vec![0.0; output_desc.data_len()];
+        reference::pooling_forward(&info, 1.0, &[2, 2, 5, 6], &input_host, 0.0, &[2, 2, 3, 3], &mut expected);
+        assert_close("pooling forward", &output, &expected);
+
+        pooling.backward(&cudnn, 1.0, 0.5,
+                         &input_desc.link(&input).unwrap(),
+                         &output_desc.link(&output).unwrap(),
+                         &output_desc.link(&output_signal).unwrap(),
+                         &mut input_desc.link_mut(&mut input_signal).unwrap()).unwrap();
+        let mut expected = vec![1.0; input_desc.data_len()];
+        reference::pooling_backward(&info, 1.0, &[2, 2, 3, 3], &output_signal_host, &[2, 2, 5, 6], &input_host, 0.5, &mut expected);
+        assert_close("pooling backward", &input_signal, &expected);
+    }
+
+    #[test]
+    fn max() {
+        test_pooling(CudnnPoolingMode::Max);
+    }
+
+    #[test]
+    fn max_deterministic() {
+        test_pooling(CudnnPoolingMode::MaxDeterministic);
+    }
+
+    #[test]
+    fn average_count_include_padding() {
+        test_pooling(CudnnPoolingMode::AverageCountIncludePadding);
+    }
+
+    #[test]
+    fn average_count_exclude_padding() {
+        test_pooling(CudnnPoolingMode::AverageCountExcludePadding);
+    }
+
+}
@@ -1,36 +1,178 @@
-
+use super::*;
 use super::ffi::*;
 use std::ptr;
+use std::mem::size_of;
 use std::marker::PhantomData;
-use cumath::CuDataType;
+use std::os::raw::c_void;
+use cumath::*;
 
 
-pub struct CuReduceTensorDescriptor<T: CuDataType> {
+pub struct CuReduceTensorDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
     data: *mut _ReduceTensorDescriptorStruct,
 }
 
-impl<T: CuDataType> Drop for CuReduceTensorDescriptor<T> {
+impl<T: CudnnType> Drop for CuReduceTensorDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_reduce_tensor_descriptor(self.data)
+        let _ = cudnn_destroy_reduce_tensor_descriptor(self.data);
+    }
+}
+
+impl<T: CudnnType> CuReduceTensorDescriptor<T> {
+
+    pub fn new(op: CudnnReduceTensorOp) -> Result<CuReduceTensorDescriptor<T>, CudnnError> {
+        CuReduceTensorDescriptorBuilder::new(op).build()
+    }
+
+    /// A Min, Max or Amax reduction which also outputs the flattened position of the selected element in each reduced slice.
+    pub fn with_indices(op: CudnnReduceTensorOp) -> Result<CuReduceTensorDescriptor<T>, CudnnError> {
+        CuReduceTensorDescriptorBuilder::new(op).indices(CudnnReduceTensorIndices::FlattenedIndices).build()
+    }
+
+    pub fn get_info(&self) -> Result<CuReduceTensorDescriptorInfo, CudnnError> {
+        let mut op = CudnnReduceTensorOp::Add;
+        let mut comp_type = CudnnDataType::Int8x4;
+        let mut nan_opt = CudnnNanPropagation::NotPropagate;
+        let mut indices = CudnnReduceTensorIndices::NoIndices;
+        let mut indices_type = CudnnIndicesType::Indices8bit;
+        cudnn_get_reduce_tensor_descriptor(self.data, &mut op, &mut comp_type, &mut nan_opt, &mut indices, &mut indices_type)?;
+        Ok(CuReduceTensorDescriptorInfo { op, comp_type, nan_opt, indices, indices_type })
+    }
+
+    /// Number of indices needed to reduce `input_desc` into `output_desc`, i.e. the length of the `i32` vector given to `reduce`
+    pub fn get_indices_len(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, output_desc: &CuTensorDescriptor<T>) -> Result<usize, CudnnError> {
+        let mut output = 0;
+        cudnn_get_reduction_indices_size(cudnn.handle, self.data, input_desc.data, output_desc.data, &mut output)?;
+        Ok(output / size_of::<i32>())
+    }
+
+    /// Length of the `f32` workspace vector given to `reduce`. cuDNN returns a size in bytes, which is rounded up to whole elements.
+    pub fn get_workspace_len(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, output_desc: &CuTensorDescriptor<T>) -> Result<usize, CudnnError> {
+        let mut output = 0;
+        cudnn_get_reduction_workspace_size(cudnn.handle, self.data, input_desc.data, output_desc.data, &mut output)?;
+        Ok((output + size_of::<f32>() - 1) / size_of::<f32>())
+    }
+
+    /// Reduces `input` along the dimensions where `output` has size 1.
+    /// `indices` must be given if and only if the descriptor was created `with_indices`.
+    /// Both `indices` and `workspace` are sized in elements, by `get_indices_len` and `get_workspace_len`.
+    pub fn reduce(&self, cudnn: &mut Cudnn, alpha: T::Scaling, input: &CuTensorDeref<T>, beta: T::Scaling, output: &mut CuTensorDeref<T>,
+                  indices: Option<&mut CuVectorDeref<i32>>, workspace: &mut CuVectorDeref<f32>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_broadcast("CuReduceTensorDescriptor::reduce", "output",
+                            &output.descriptor.get_info()?.dimensions, &input.descriptor.get_info()?.dimensions)?;
+            let with_indices = self.get_info()?.indices == CudnnReduceTensorIndices::FlattenedIndices;
+            if with_indices != indices.is_some() {
+                return Err(CudnnError::bad_param("CuReduceTensorDescriptor::reduce", if with_indices {
+                    "the descriptor computes indices, but no indices buffer was given"
+                } else {
+                    "an indices buffer was given, but the descriptor doesn't compute indices"
+                }))
+            }
+        }
+        let (indices_ptr, indices_size) = match indices {
+            Some(indices) => (indices.as_mut_ptr() as *mut c_void, indices.len() * size_of::<i32>()),
+            None => (ptr::null_mut(), 0),
+        };
+        cudnn_reduce_tensor(cudnn.handle, self.data,
+                            indices_ptr, indices_size,
+                            workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                            &alpha as *const T::Scaling as *const c_void, input.descriptor.data, input.data as *const c_void,
+                            &beta as *const T::Scaling as *const c_void, output.descriptor.data, output.data as *mut c_void)
     }
+
+}
+
+
+/// Builds a `CuReduceTensorDescriptor`.
+/// Defaults to a computation in Double for f64 tensors and in Float otherwise, propagating NaNs, without indices.
+pub struct CuReduceTensorDescriptorBuilder {
+    op: CudnnReduceTensorOp,
+    comp_type: Option<CudnnDataType>,
+    nan_opt: CudnnNanPropagation,
+    indices: CudnnReduceTensorIndices,
+    indices_type: CudnnIndicesType,
 }
 
-impl CuReduceTensorDescriptor<f32> {
+impl CuReduceTensorDescriptorBuilder {
+
+    pub fn new(op: CudnnReduceTensorOp) -> CuReduceTensorDescriptorBuilder {
+        CuReduceTensorDescriptorBuilder {
+            op,
+            comp_type: None,
+            nan_opt: CudnnNanPropagation::Propagate,
+            indices: CudnnReduceTensorIndices::NoIndices,
+            indices_type: CudnnIndicesType::Indices32bit,
+        }
+    }
+
+    /// Data type of the intermediate results, one of Float, Double or Half
+    pub fn comp_type(mut self, comp_type: CudnnDataType) -> CuReduceTensorDescriptorBuilder {
+        self.comp_type = Some(comp_type);
+        self
+    }
+
+    pub fn nan_opt(mut self, nan_opt: CudnnNanPropagation) -> CuReduceTensorDescriptorBuilder {
+        self.nan_opt = nan_opt;
+        self
+    }
+
+    /// FlattenedIndices is only supported by the Min, Max and Amax reductions
+    pub fn indices(mut self, indices: CudnnReduceTensorIndices) -> CuReduceTensorDescriptorBuilder {
+        self.indices = indices;
+        self
+    }
+
+    /// Only Indices32bit is supported by cuDNN
+    pub fn indices_type(mut self, indices_type: CudnnIndicesType) -> CuReduceTensorDescriptorBuilder {
+        self.indices_type = indices_type;
+        self
+    }
+
+    #[cfg_attr(feature = "disable_checks", allow(dead_code))]
+    fn validate(&self) -> Result<(), CudnnError> {
+        let function = "CuReduceTensorDescriptorBuilder::build";
+        match self.comp_type {
+            None | Some(CudnnDataType::Float) | Some(CudnnDataType::Double) | Some(CudnnDataType::Half) => {},
+            Some(comp_type) => return Err(CudnnError::bad_param(function, format!("unsupported compute type {:?}, expected Float, Double or Half", comp_type))),
+        }
+        if self.indices == CudnnReduceTensorIndices::FlattenedIndices {
+            match self.op {
+                CudnnReduceTensorOp::Min | CudnnReduceTensorOp::Max | CudnnReduceTensorOp::Amax => {},
+                op => return Err(CudnnError::bad_param(function, format!("{:?} reductions can't compute indices, only Min, Max and Amax can", op))),
+            }
+        }
+        if self.indices_type != CudnnIndicesType::Indices32bit {
+            return Err(CudnnError::bad_param(function, format!("unsupported indices type {:?}, only Indices32bit is supported", self.indices_type)))
+        }
+        Ok(())
+    }
 
-    pub fn new(op: CudnnReduceTensorOp) -> CuReduceTensorDescriptor<f32> {
+    pub fn build<T: CudnnType>(&self) -> Result<CuReduceTensorDescriptor<T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.validate()?;
+        }
         let mut data = ptr::null_mut();
-        cudnn_create_reduce_tensor_descriptor(&mut data);
-        cudnn_set_reduce_tensor_descriptor(data, op,
-                                           CudnnDataType::Float,
-                                           CudnnNanPropagation::Propagate,
-                                           CudnnReduceTensorIndices::NoIndices,
-                                           CudnnIndicesType::Indices32bit);
-        CuReduceTensorDescriptor { _phantom: PhantomData, data }
+        cudnn_create_reduce_tensor_descriptor(&mut data)?;
+        let output = CuReduceTensorDescriptor { _phantom: PhantomData, data };
+        let comp_type = self.comp_type.unwrap_or(match T::compute_type() {
+            CudnnDataType::Double => CudnnDataType::Double,
+            _ => CudnnDataType::Float,
+        });
+        cudnn_set_reduce_tensor_descriptor(output.data, self.op, comp_type, self.nan_opt, self.indices, self.indices_type)?;
+        Ok(output)
     }
 
+}
 
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct CuReduceTensorDescriptorInfo {
+    pub op: CudnnReduceTensorOp,
+    pub comp_type: CudnnDataType,
+    pub nan_opt: CudnnNanPropagation,
+    pub indices: CudnnReduceTensorIndices,
+    pub indices_type: CudnnIndicesType,
 }
 
 
@@ -38,10 +180,147 @@ impl CuReduceTensorDescriptor<f32> {
 mod tests {
 
     use super::*;
+    use test_utils::assert_close;
 
     #[test]
     fn init() {
-        let _descriptor = CuReduceTensorDescriptor::new(CudnnReduceTensorOp::Max);
+        let descriptor = CuReduceTensorDescriptor::<f32>::new(CudnnReduceTensorOp::Max).unwrap();
+        assert_eq!(descriptor.get_info().unwrap(), CuReduceTensorDescriptorInfo {
+            op: CudnnReduceTensorOp::Max,
+            comp_type: CudnnDataType::Float,
+            nan_opt: CudnnNanPropagation::Propagate,
+            indices: CudnnReduceTensorIndices::NoIndices,
+            indices_type: CudnnIndicesType::Indices32bit,
+        });
+        let descriptor = CuReduceTensorDescriptor::<f32>::with_indices(CudnnReduceTensorOp::Amax).unwrap();
+        assert_eq!(descriptor.get_info().unwrap().indices, CudnnReduceTensorIndices::FlattenedIndices);
+    }
+
+    #[test]
+    fn default_comp_type() {
+        assert_eq!(CuReduceTensorDescriptor::<f64>::new(CudnnReduceTensorOp::Add).unwrap().get_info().unwrap().comp_type, CudnnDataType::Double);
+        assert_eq!(CuReduceTensorDescriptor::<Half>::new(CudnnReduceTensorOp::Add).unwrap().get_info().unwrap().comp_type, CudnnDataType::Float);
+        let descriptor = CuReduceTensorDescriptorBuilder::new(CudnnReduceTensorOp::Add).comp_type(CudnnDataType::Half).build::<Half>().unwrap();
+        assert_eq!(descriptor.get_info().unwrap().comp_type, CudnnDataType::Half);
+    }
+
+    #[test]
+    fn builder() {
+        let descriptor = CuReduceTensorDescriptorBuilder::new(CudnnReduceTensorOp::Amax)
+            .comp_type(CudnnDataType::Double)
+            .nan_opt(CudnnNanPropagation::NotPropagate)
+            .indices(CudnnReduceTensorIndices::FlattenedIndices)
+            .indices_type(CudnnIndicesType::Indices32bit)
+            .build::<f32>().unwrap();
+        assert_eq!(descriptor.get_info().unwrap(), CuReduceTensorDescriptorInfo {
+            op: CudnnReduceTensorOp::Amax,
+            comp_type: CudnnDataType::Double,
+            nan_opt: CudnnNanPropagation::NotPropagate,
+            indices: CudnnReduceTensorIndices::FlattenedIndices,
+            indices_type: CudnnIndicesType::Indices32bit,
+        });
+    }
+
+    #[test]
+    fn builder_validation() {
+        for &op in &[CudnnReduceTensorOp::Min, CudnnReduceTensorOp::Max, CudnnReduceTensorOp::Amax] {
+            assert!(CuReduceTensorDescriptorBuilder::new(op).indices(CudnnReduceTensorIndices::FlattenedIndices).validate().is_ok());
+        }
+        for &op in &[CudnnReduceTensorOp::Add, CudnnReduceTensorOp::Mul, CudnnReduceTensorOp::Avg, CudnnReduceTensorOp::Norm1,
+                     CudnnReduceTensorOp::Norm2, CudnnReduceTensorOp::MulNoZeros] {
+            let builder = CuReduceTensorDescriptorBuilder::new(op);
+            assert!(builder.validate().is_ok());
+            let error = builder.indices(CudnnReduceTensorIndices::FlattenedIndices).validate().unwrap_err();
+            assert_eq!(error.status(), CudnnStatus::BadParam);
+        }
+
+        for &comp_type in &[CudnnDataType::Float, CudnnDataType::Double, CudnnDataType::Half] {
+            assert!(CuReduceTensorDescriptorBuilder::new(CudnnReduceTensorOp::Add).comp_type(comp_type).validate().is_ok());
+        }
+        for &comp_type in &[CudnnDataType::Int8, CudnnDataType::Int32, CudnnDataType::Int8x4] {
+            assert!(CuReduceTensorDescriptorBuilder::new(CudnnReduceTensorOp::Add).comp_type(comp_type).validate().is_err());
+        }
+
+        for &indices_type in &[CudnnIndicesType::Indices64bit, CudnnIndicesType::Indices16bit, CudnnIndicesType::Indices8bit] {
+            assert!(CuReduceTensorDescriptorBuilder::new(CudnnReduceTensorOp::Max).indices_type(indices_type).validate().is_err());
+        }
+    }
+
+    fn test_reduce(op: CudnnReduceTensorOp, with_indices: bool) {
+        let mut cudnn = Cudnn::new().unwrap();
+        let input_dims = [2, 3, 4, 5];
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&input_dims).unwrap();
+        // Distinct values, so that Min, Max and Amax have no ties
+        let input_host = (0..input_desc.data_len()).map(|i| ((i * 37) % 127) as f32 / 64.0 - 1.01).collect::<Vec<_>>();
+        let input = CuVector::<f32>::from_host_data(&input_host);
+
+        let descriptor = if with_indices {
+            CuReduceTensorDescriptor::<f32>::with_indices(op).unwrap()
+        } else {
+            CuReduceTensorDescriptor::<f32>::new(op).unwrap()
+        };
+        for output_dims in &[[1, 3, 1, 1], [2, 1, 4, 5], [1, 1, 1, 1], [2, 3, 4, 5]] {
+            let output_desc = CuTensorDescriptor::<f32>::fully_packed(output_dims).unwrap();
+            let output_host = (0..output_desc.data_len()).map(|i| i as f32).collect::<Vec<_>>();
+            let mut output = CuVector::<f32>::from_host_data(&output_host);
+            let mut indices = CuVector::<i32>::new(0, descriptor.get_indices_len(&cudnn, &input_desc, &output_desc).unwrap().max(1));
+            let mut workspace = CuVector::<f32>::zero(descriptor.get_workspace_len(&cudnn, &input_desc, &output_desc).unwrap().max(1));
+
+            descriptor.reduce(&mut cudnn, 1.0, &input_desc.link(&input).unwrap(), 0.5, &mut output_desc.link_mut(&mut output).unwrap(),
+                              if with_indices { Some(&mut indices) } else { None }, &mut workspace).unwrap();
+
+            let mut expected = output_host.clone();
+            let mut expected_indices = vec![0; output_desc.data_len()];
+            reference::reduce_tensor(op, 1.0, &input_dims, &input_host, 0.5, output_dims, &mut expected, Some(&mut expected_indices));
+            assert_close(&format!("{:?} {:?}", op, output_dims), &output, &expected);
+            if with_indices && output_dims != &input_dims {
+                let mut buffer = vec![0; output_desc.data_len()];
+                indices.clone_to_host(&mut buffer[..]);
+                assert_eq!(buffer, expected_indices, "{:?} {:?} indices", op, output_dims);
+            }
+        }
+    }
+
+    #[test]
+    fn reduce() {
+        for &op in &[CudnnReduceTensorOp::Add, CudnnReduceTensorOp::Mul, CudnnReduceTensorOp::Min, CudnnReduceTensorOp::Max,
+                     CudnnReduceTensorOp::Amax, CudnnReduceTensorOp::Avg, CudnnReduceTensorOp::Norm1, CudnnReduceTensorOp::Norm2,
+                     CudnnReduceTensorOp::MulNoZeros] {
+            test_reduce(op, false);
+        }
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn reduce_with_indices() {
+        for &op in &[CudnnReduceTensorOp::Min, CudnnReduceTensorOp::Max, CudnnReduceTensorOp::Amax] {
+            test_reduce(op, true);
+        }
+    }
+
+    #[test]
+    fn reduce_invalid() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let input_desc = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 1, 1]).unwrap();
+        let output_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 1, 1]).unwrap();
+        let wrong_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 1, 1]).unwrap();
+        let input = CuVector::<f32>::zero(6);
+        let mut output = CuVector::<f32>::zero(3);
+        let mut wrong = CuVector::<f32>::zero(2);
+        let mut indices = CuVector::<i32>::new(0, 3);
+        let mut workspace = CuVector::<f32>::zero(16);
+
+        let descriptor = CuReduceTensorDescriptor::<f32>::new(CudnnReduceTensorOp::Max).unwrap();
+        let error = descriptor.reduce(&mut cudnn, 1.0, &input_desc.link(&input).unwrap(), 0.0, &mut wrong_desc.link_mut(&mut wrong).unwrap(),
+                                      None, &mut workspace).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+        let error = descriptor.reduce(&mut cudnn, 1.0, &input_desc.link(&input).unwrap(), 0.0, &mut output_desc.link_mut(&mut output).unwrap(),
+                                      Some(&mut indices), &mut workspace).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+
+        let descriptor = CuReduceTensorDescriptor::<f32>::with_indices(CudnnReduceTensorOp::Max).unwrap();
+        let error = descriptor.reduce(&mut cudnn, 1.0, &input_desc.link(&input).unwrap(), 0.0, &mut output_desc.link_mut(&mut output).unwrap(),
+                                      None, &mut workspace).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+}
@@ -0,0 +1,88 @@
+use ffi::CudnnActivationMode;
+use super::scale_into;
+
+
+
+fn activation(mode: CudnnActivationMode, coef: f64, x: f32) -> f32 {
+    let coef = coef as f32;
+    match mode {
+        CudnnActivationMode::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+        CudnnActivationMode::Relu => x.max(0.0),
+        CudnnActivationMode::Tanh => x.tanh(),
+        CudnnActivationMode::ClippedRelu => x.max(0.0).min(coef),
+        CudnnActivationMode::Elu => if x > 0.0 { x } else { coef * (x.exp() - 1.0) },
+    }
+}
+
+fn activation_derivative(mode: CudnnActivationMode, coef: f64, x: f32, y: f32) -> f32 {
+    let coef = coef as f32;
+    match mode {
+        CudnnActivationMode::Sigmoid => y * (1.0 - y),
+        CudnnActivationMode::Relu => if x > 0.0 { 1.0 } else { 0.0 },
+        CudnnActivationMode::Tanh => 1.0 - y * y,
+        CudnnActivationMode::ClippedRelu => if x > 0.0 && x < coef { 1.0 } else { 0.0 },
+        CudnnActivationMode::Elu => if x > 0.0 { 1.0 } else { y + coef },
+    }
+}
+
+/// Reference for `cudnnActivationForward`.
+pub fn activation_forward(mode: CudnnActivationMode, coef: f64, alpha: f32, x: &[f32], beta: f32, y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    for (x, y) in x.iter().zip(y.iter_mut()) {
+        scale_into(alpha, activation(mode, coef, *x), beta, y);
+    }
+}
+
+/// Reference for `cudnnActivationBackward`.
+pub fn activation_backward(mode: CudnnActivationMode, coef: f64, alpha: f32,
+                           y: &[f32], dy: &[f32], x: &[f32],
+                           beta: f32, dx: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    assert_eq!(x.len(), dy.len());
+    assert_eq!(x.len(), dx.len());
+    for i in 0..dx.len() {
+        scale_into(alpha, dy[i] * activation_derivative(mode, coef, x[i], y[i]), beta, &mut dx[i]);
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn forward() {
+        let x = [-2.0, -0.5, 0.0, 0.25, 3.0];
+        let mut y = [1.0; 5];
+
+        activation_forward(CudnnActivationMode::Relu, 1.0, 1.0, &x, 0.0, &mut y);
+        assert_eq!(y, [0.0, 0.0, 0.0, 0.25, 3.0]);
+
+        activation_forward(CudnnActivationMode::ClippedRelu, 1.0, 2.0, &x, 1.0, &mut y);
+        assert_eq!(y, [0.0, 0.0, 0.0, 0.75, 5.0]);
+
+        activation_forward(CudnnActivationMode::Sigmoid, 1.0, 1.0, &x, 0.0, &mut y);
+        assert_eq!(y[2], 0.5);
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let modes = [(CudnnActivationMode::Sigmoid, 1.0), (CudnnActivationMode::Tanh, 1.0), (CudnnActivationMode::Elu, 0.5)];
+        let x = [-1.5, -0.3, 0.4, 2.0];
+        let dy = [1.0; 4];
+        let epsilon = 1e-3;
+        for &(mode, coef) in modes.iter() {
+            let mut y = [0.0; 4];
+            let mut dx = [0.0; 4];
+            activation_forward(mode, coef, 1.0, &x, 0.0, &mut y);
+            activation_backward(mode, coef, 1.0, &y, &dy, &x, 0.0, &mut dx);
+            for i in 0..x.len() {
+                let numeric = (activation(mode, coef, x[i] + epsilon) - activation(mode, coef, x[i] - epsilon)) / (2.0 * epsilon);
+                assert!((numeric - dx[i]).abs() < 1e-2, "{:?} : {} != {}", mode, numeric, dx[i]);
+            }
+        }
+    }
+
+}
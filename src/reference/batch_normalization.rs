@@ -0,0 +1,186 @@
+
+use ffi::CudnnBatchNormMode;
+use super::scale_into;
+
+
+
+/// Index of the parameter used by each element of a NCHW packed tensor, and number of parameters.
+fn param_indices(mode: CudnnBatchNormMode, dims: &[i32]) -> (Vec<usize>, usize) {
+    let len = dims.iter().product::<i32>() as usize;
+    let c = dims[1] as usize;
+    let spatial = dims[2..].iter().product::<i32>() as usize;
+    match mode {
+        CudnnBatchNormMode::PerActivation => ((0..len).map(|i| i % (c * spatial)).collect(), c * spatial),
+        _ => ((0..len).map(|i| i / spatial % c).collect(), c),
+    }
+}
+
+/// Mean and biased variance of every parameter group.
+fn statistics(indices: &[usize], nb_params: usize, x: &[f32]) -> (Vec<f64>, Vec<f64>, f64) {
+    let count = (indices.len() / nb_params) as f64;
+    let mut mean = vec![0.0; nb_params];
+    let mut variance = vec![0.0; nb_params];
+    for (&p, &x) in indices.iter().zip(x.iter()) { mean[p] += x as f64 / count }
+    for (&p, &x) in indices.iter().zip(x.iter()) { variance[p] += (x as f64 - mean[p]).powi(2) / count }
+    (mean, variance, count)
+}
+
+/// Reference for `cudnnBatchNormalizationForwardTraining`, with NCHW packed `x` and `y`.
+pub fn batch_normalization_forward_training(mode: CudnnBatchNormMode, alpha: f32, beta: f32,
+                                            dims: &[i32], x: &[f32], y: &mut [f32],
+                                            scale: &[f32], bias: &[f32],
+                                            exponential_average_factor: f64,
+                                            running_mean: &mut [f32], running_variance: &mut [f32],
+                                            epsilon: f64, save_mean: &mut [f32], save_inv_variance: &mut [f32]) {
+    let (indices, nb_params) = param_indices(mode, dims);
+    let (mean, variance, count) = statistics(&indices, nb_params, x);
+    for p in 0..nb_params {
+        let unbiased = variance[p] * count / (count - 1.0);
+        running_mean[p] = ((1.0 - exponential_average_factor) * running_mean[p] as f64 + exponential_average_factor * mean[p]) as f32;
+        running_variance[p] = ((1.0 - exponential_average_factor) * running_variance[p] as f64 + exponential_average_factor * unbiased) as f32;
+        save_mean[p] = mean[p] as f32;
+        save_inv_variance[p] = (1.0 / (variance[p] + epsilon).sqrt()) as f32;
+    }
+    for (i, &p) in indices.iter().enumerate() {
+        let value = scale[p] as f64 * (x[i] as f64 - mean[p]) * save_inv_variance[p] as f64 + bias[p] as f64;
+        scale_into(alpha, value as f32, beta, &mut y[i]);
+    }
+}
+
+/// Reference for `cudnnBatchNormalizationForwardInference`, with NCHW packed `x` and `y`.
+pub fn batch_normalization_forward_inference(mode: CudnnBatchNormMode, alpha: f32, beta: f32,
+                                             dims: &[i32], x: &[f32], y: &mut [f32],
+                                             scale: &[f32], bias: &[f32],
+                                             estimated_mean: &[f32], estimated_variance: &[f32], epsilon: f64) {
+    let (indices, _) = param_indices(mode, dims);
+    for (i, &p) in indices.iter().enumerate() {
+        let normalized = (x[i] as f64 - estimated_mean[p] as f64) / (estimated_variance[p] as f64 + epsilon).sqrt();
+        scale_into(alpha, (scale[p] as f64 * normalized + bias[p] as f64) as f32, beta, &mut y[i]);
+    }
+}
+
+/// Reference for `cudnnBatchNormalizationBackward`: gradients of `x`, of the scale and of the bias.
+pub fn batch_normalization_backward(mode: CudnnBatchNormMode,
+                                    alpha_data: f32, beta_data: f32, alpha_param: f32, beta_param: f32,
+                                    dims: &[i32], x: &[f32], dy: &[f32], dx: &mut [f32],
+                                    scale: &[f32], scale_diff: &mut [f32], bias_diff: &mut [f32],
+                                    saved_mean: &[f32], saved_inv_variance: &[f32]) {
+    let (indices, nb_params) = param_indices(mode, dims);
+    let count = (indices.len() / nb_params) as f64;
+    let normalized = |i: usize, p: usize| (x[i] as f64 - saved_mean[p] as f64) * saved_inv_variance[p] as f64;
+    let mut dscale = vec![0.0; nb_params];
+    let mut dbias = vec![0.0; nb_params];
+    for (i, &p) in indices.iter().enumerate() {
+        dscale[p] += dy[i] as f64 * normalized(i, p);
+        dbias[p] += dy[i] as f64;
+    }
+    for (i, &p) in indices.iter().enumerate() {
+        let value = scale[p] as f64 * saved_inv_variance[p] as f64 / count
+            * (count * dy[i] as f64 - dbias[p] - normalized(i, p) * dscale[p]);
+        scale_into(alpha_data, value as f32, beta_data, &mut dx[i]);
+    }
+    for p in 0..nb_params {
+        scale_into(alpha_param, dscale[p] as f32, beta_param, &mut scale_diff[p]);
+        scale_into(alpha_param, dbias[p] as f32, beta_param, &mut bias_diff[p]);
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const DIMS: [i32; 4] = [3, 2, 1, 2];
+    const X: [f32; 12] = [1.0, 2.0, -1.0, 0.5, 3.0, 0.0, 2.0, 1.5, -2.0, 4.0, 0.0, 1.0];
+
+    #[test]
+    fn training_statistics() {
+        let mut y = [0.0; 12];
+        let (mut running_mean, mut running_variance) = ([1.0; 2], [1.0; 2]);
+        let (mut save_mean, mut save_inv_variance) = ([0.0; 2], [0.0; 2]);
+        batch_normalization_forward_training(CudnnBatchNormMode::Spatial, 1.0, 0.0, &DIMS, &X, &mut y,
+                                             &[1.0, 1.0], &[0.0, 0.0], 0.5,
+                                             &mut running_mean, &mut running_variance,
+                                             0.0, &mut save_mean, &mut save_inv_variance);
+        // Channel 0 holds [1, 2, 3, 0, -2, 4], channel 1 holds [-1, 0.5, 2, 1.5, 0, 1]
+        assert!((save_mean[0] - 4.0 / 3.0).abs() < 1e-6);
+        assert!((save_mean[1] - 2.0 / 3.0).abs() < 1e-6);
+        assert!((running_mean[0] - (0.5 + 2.0 / 3.0)).abs() < 1e-6);
+        for c in 0..2 {
+            let values = (0..12).filter(|i| i / 2 % 2 == c).map(|i| y[i]).collect::<Vec<_>>();
+            let mean = values.iter().sum::<f32>() / 6.0;
+            let variance = values.iter().map(|x| (x - mean) * (x - mean)).sum::<f32>() / 6.0;
+            assert!(mean.abs() < 1e-6 && (variance - 1.0).abs() < 1e-5, "{} {}", mean, variance);
+        }
+    }
+
+    #[test]
+    fn inference_matches_training_with_saved_statistics() {
+        for &mode in &[CudnnBatchNormMode::PerActivation, CudnnBatchNormMode::Spatial] {
+            let (scale, bias) = ([0.5, 2.0, -1.0, 1.0], [0.0, 1.0, 0.5, -0.5]);
+            let (mut running_mean, mut running_variance) = ([0.0; 4], [0.0; 4]);
+            let (mut save_mean, mut save_inv_variance) = ([0.0; 4], [0.0; 4]);
+            let mut training = [0.0; 12];
+            batch_normalization_forward_training(mode, 1.0, 0.0, &DIMS, &X, &mut training, &scale, &bias, 1.0,
+                                                 &mut running_mean, &mut running_variance,
+                                                 1e-5, &mut save_mean, &mut save_inv_variance);
+            let variance = save_inv_variance.iter().map(|x| 1.0 / (x * x) - 1e-5).collect::<Vec<_>>();
+            let mut inference = [0.0; 12];
+            batch_normalization_forward_inference(mode, 1.0, 0.0, &DIMS, &X, &mut inference, &scale, &bias,
+                                                  &save_mean, &variance, 1e-5);
+            for i in 0..12 {
+                assert!((training[i] - inference[i]).abs() < 1e-3, "{:?} : {:?} != {:?}", mode, training, inference);
+            }
+        }
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let dy = [1.0, -2.0, 0.5, 0.0, 3.0, 1.0, -1.0, 2.0, 0.0, 1.5, -0.5, 1.0];
+        for &mode in &[CudnnBatchNormMode::PerActivation, CudnnBatchNormMode::Spatial] {
+            let nb_params = if mode == CudnnBatchNormMode::Spatial { 2 } else { 4 };
+            let scale = [0.5, 2.0, -1.0, 1.0];
+            let loss = |x: &[f32], scale: &[f32], bias: &[f32]| {
+                let mut y = [0.0; 12];
+                let (mut running_mean, mut running_variance) = ([0.0; 4], [0.0; 4]);
+                let (mut save_mean, mut save_inv_variance) = ([0.0; 4], [0.0; 4]);
+                batch_normalization_forward_training(mode, 1.0, 0.0, &DIMS, x, &mut y, scale, bias, 1.0,
+                                                     &mut running_mean, &mut running_variance,
+                                                     1e-5, &mut save_mean, &mut save_inv_variance);
+                y.iter().zip(dy.iter()).map(|(y, dy)| (y * dy) as f64).sum::<f64>()
+            };
+
+            let mut y = [0.0; 12];
+            let (mut running_mean, mut running_variance) = ([0.0; 4], [0.0; 4]);
+            let (mut save_mean, mut save_inv_variance) = ([0.0; 4], [0.0; 4]);
+            batch_normalization_forward_training(mode, 1.0, 0.0, &DIMS, &X, &mut y, &scale, &[0.0; 4], 1.0,
+                                                 &mut running_mean, &mut running_variance,
+                                                 1e-5, &mut save_mean, &mut save_inv_variance);
+            let (mut dx, mut dscale, mut dbias) = ([0.0; 12], [0.0; 4], [0.0; 4]);
+            batch_normalization_backward(mode, 1.0, 0.0, 1.0, 0.0, &DIMS, &X, &dy, &mut dx,
+                                         &scale, &mut dscale, &mut dbias, &save_mean, &save_inv_variance);
+
+            for i in 0..12 {
+                let (mut plus, mut minus) = (X, X);
+                plus[i] += 1e-2;
+                minus[i] -= 1e-2;
+                let expected = (loss(&plus, &scale, &[0.0; 4]) - loss(&minus, &scale, &[0.0; 4])) / 2e-2;
+                assert!((dx[i] as f64 - expected).abs() < 1e-2, "{:?} dx : {:?} != {}", mode, dx, expected);
+            }
+            for p in 0..nb_params {
+                let (mut plus, mut minus) = (scale, scale);
+                plus[p] += 1e-2;
+                minus[p] -= 1e-2;
+                let expected = (loss(&X, &plus, &[0.0; 4]) - loss(&X, &minus, &[0.0; 4])) / 2e-2;
+                assert!((dscale[p] as f64 - expected).abs() < 1e-2, "{:?} dscale : {:?} != {}", mode, dscale, expected);
+                let mut bias = [0.0; 4];
+                bias[p] = 1.0;
+                let expected = loss(&X, &scale, &bias) - loss(&X, &scale, &[0.0; 4]);
+                assert!((dbias[p] as f64 - expected).abs() < 1e-3, "{:?} dbias : {:?} != {}", mode, dbias, expected);
+            }
+        }
+    }
+
+}
@@ -0,0 +1,231 @@
+use ffi::CudnnConvolutionMode;
+use CuConvolutionDescriptorInfo;
+use super::{for_each_index, packed_offset, scale_into};
+
+
+
+/// Calls `f(x_offset, w_offset, y_offset)` for every (input, filter, output) triple
+/// taking part in the convolution, skipping the taps that fall in the padding.
+/// With `group_count` groups, output channel `k` only sees the input channels of group `k / (K / group_count)`.
+fn for_each_tap<F: FnMut(usize, usize, usize)>(info: &CuConvolutionDescriptorInfo,
+                                               x_dims: &[i32], w_dims: &[i32], y_dims: &[i32], mut f: F) {
+    assert_eq!(x_dims.len(), w_dims.len());
+    assert_eq!(x_dims.len(), y_dims.len());
+    assert_eq!(x_dims.len() - 2, info.array_length as usize);
+    assert_eq!(x_dims[0], y_dims[0]);
+    assert_eq!(x_dims[1], w_dims[1] * info.group_count);
+    assert_eq!(y_dims[1], w_dims[0]);
+    assert_eq!(w_dims[0] % info.group_count, 0);
+
+    let nb_spatial = x_dims.len() - 2;
+    let filter_len = w_dims[1..].iter().product::<i32>() as usize;
+    let filters_per_group = w_dims[0] / info.group_count;
+    let mut x_index = vec![0; x_dims.len()];
+    for_each_index(y_dims, |y_index| {
+        let y_offset = packed_offset(y_dims, y_index);
+        let group = y_index[1] / filters_per_group;
+        for_each_index(&w_dims[1..], |w_index| {
+            x_index[0] = y_index[0];
+            x_index[1] = group * w_dims[1] + w_index[0];
+            for d in 0..nb_spatial {
+                let tap = match info.mode {
+                    CudnnConvolutionMode::Convolution => w_dims[d + 2] - 1 - w_index[d + 1],
+                    CudnnConvolutionMode::CrossCorrelation => w_index[d + 1],
+                };
+                let position = y_index[d + 2] * info.filter_strides[d] - info.pads[d] + tap * info.dilatations[d];
+                if position < 0 || position >= x_dims[d + 2] { return }
+                x_index[d + 2] = position;
+            }
+            let w_offset = y_index[1] as usize * filter_len + packed_offset(&w_dims[1..], w_index);
+            f(packed_offset(x_dims, &x_index), w_offset, y_offset);
+        });
+    });
+}
+
+/// Reference for `cudnnConvolutionForward`, with NCHW packed `x`, `y` and KCRS packed `w`.
+pub fn convolution_forward(info: &CuConvolutionDescriptorInfo, alpha: f32,
+                           x_dims: &[i32], x: &[f32],
+                           w_dims: &[i32], w: &[f32],
+                           beta: f32, y_dims: &[i32], y: &mut [f32]) {
+    let mut accumulator = vec![0.0; y.len()];
+    for_each_tap(info, x_dims, w_dims, y_dims, |x_offset, w_offset, y_offset| {
+        accumulator[y_offset] += x[x_offset] * w[w_offset];
+    });
+    for (value, y) in accumulator.into_iter().zip(y.iter_mut()) {
+        scale_into(alpha, value, beta, y);
+    }
+}
+
+
+
+/// Reference for `cudnnConvolutionBackwardData`: gradient of `x` from the gradient of `y`.
+pub fn convolution_backward_data(info: &CuConvolutionDescriptorInfo, alpha: f32,
+                                 w_dims: &[i32], w: &[f32],
+                                 dy_dims: &[i32], dy: &[f32],
+                                 beta: f32, dx_dims: &[i32], dx: &mut [f32]) {
+    let mut accumulator = vec![0.0; dx.len()];
+    for_each_tap(info, dx_dims, w_dims, dy_dims, |x_offset, w_offset, y_offset| {
+        accumulator[x_offset] += dy[y_offset] * w[w_offset];
+    });
+    for (value, dx) in accumulator.into_iter().zip(dx.iter_mut()) {
+        scale_into(alpha, value, beta, dx);
+    }
+}
+
+
+/// Reference for `cudnnConvolutionBackwardFilter`: gradient of `w` from `x` and the gradient of `y`.
+pub fn convolution_backward_filter(info: &CuConvolutionDescriptorInfo, alpha: f32,
+                                   x_dims: &[i32], x: &[f32],
+                                   dy_dims: &[i32], dy: &[f32],
+                                   beta: f32, dw_dims: &[i32], dw: &mut [f32]) {
+    let mut accumulator = vec![0.0; dw.len()];
+    for_each_tap(info, x_dims, dw_dims, dy_dims, |x_offset, w_offset, y_offset| {
+        accumulator[w_offset] += dy[y_offset] * x[x_offset];
+    });
+    for (value, dw) in accumulator.into_iter().zip(dw.iter_mut()) {
+        scale_into(alpha, value, beta, dw);
+    }
+}
+
+
+/// Reference for `cudnnConvolutionBackwardBias`: sum of the gradient of `y` over every dimension but the channels.
+pub fn convolution_backward_bias(alpha: f32, dy_dims: &[i32], dy: &[f32], beta: f32, db: &mut [f32]) {
+    assert_eq!(db.len(), dy_dims[1] as usize);
+    let mut accumulator = vec![0.0; db.len()];
+    for_each_index(dy_dims, |dy_index| {
+        accumulator[dy_index[1] as usize] += dy[packed_offset(dy_dims, dy_index)];
+    });
+    for (value, db) in accumulator.into_iter().zip(db.iter_mut()) {
+        scale_into(alpha, value, beta, db);
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use ffi::{CudnnDataType, CudnnMathType};
+
+    pub(crate) fn info_2d(pad: i32, stride: i32, dilatation: i32, mode: CudnnConvolutionMode) -> CuConvolutionDescriptorInfo {
+        CuConvolutionDescriptorInfo {
+            array_length: 2,
+            pads: vec![pad; 2],
+            filter_strides: vec![stride; 2],
+            dilatations: vec![dilatation; 2],
+            mode,
+            data_type: CudnnDataType::Float,
+            group_count: 1,
+            math_type: CudnnMathType::Default,
+        }
+    }
+
+    #[test]
+    fn forward_padded() {
+        let info = info_2d(1, 1, 1, CudnnConvolutionMode::CrossCorrelation);
+        let x = [1.0; 9];
+        let w = [1.0; 9];
+        let mut y = [0.0; 9];
+        convolution_forward(&info, 1.0, &[1, 1, 3, 3], &x, &[1, 1, 3, 3], &w, 0.0, &[1, 1, 3, 3], &mut y);
+        assert_eq!(y, [4.0, 6.0, 4.0, 6.0, 9.0, 6.0, 4.0, 6.0, 4.0]);
+    }
+
+    #[test]
+    fn forward_flips_filter_in_convolution_mode() {
+        let x = [1.0, 2.0, 3.0, 4.0];
+        let w = [1.0, 0.0, 0.0, 0.0];
+        let mut y = [0.0; 1];
+        convolution_forward(&info_2d(0, 1, 1, CudnnConvolutionMode::CrossCorrelation), 1.0,
+                            &[1, 1, 2, 2], &x, &[1, 1, 2, 2], &w, 0.0, &[1, 1, 1, 1], &mut y);
+        assert_eq!(y, [1.0]);
+        convolution_forward(&info_2d(0, 1, 1, CudnnConvolutionMode::Convolution), 1.0,
+                            &[1, 1, 2, 2], &x, &[1, 1, 2, 2], &w, 0.0, &[1, 1, 1, 1], &mut y);
+        assert_eq!(y, [4.0]);
+    }
+
+    #[test]
+    fn forward_strided_multichannel() {
+        let info = info_2d(0, 2, 1, CudnnConvolutionMode::CrossCorrelation);
+        // 2 input channels, 2 filters of 1x1
+        let x = (0..32).map(|x| x as f32).collect::<Vec<_>>();
+        let w = [1.0, 1.0, 1.0, -1.0];
+        let mut y = [0.0; 8];
+        convolution_forward(&info, 1.0, &[1, 2, 4, 4], &x, &[2, 2, 1, 1], &w, 0.0, &[1, 2, 2, 2], &mut y);
+        assert_eq!(y, [16.0, 20.0, 32.0, 36.0, -16.0, -16.0, -16.0, -16.0]);
+    }
+
+    #[test]
+    fn forward_grouped() {
+        // 2 groups of 2 input channels, 1 filter of 1x1 per group summing its channels
+        let info = CuConvolutionDescriptorInfo { group_count: 2, ..info_2d(0, 1, 1, CudnnConvolutionMode::CrossCorrelation) };
+        let x = [1.0, 2.0, 10.0, 20.0, 100.0, 200.0, 1000.0, 2000.0];
+        let w = [1.0, 1.0, 1.0, -1.0];
+        let mut y = [0.0; 4];
+        convolution_forward(&info, 1.0, &[1, 4, 1, 2], &x, &[2, 2, 1, 1], &w, 0.0, &[1, 2, 1, 2], &mut y);
+        assert_eq!(y, [11.0, 22.0, -900.0, -1800.0]);
+    }
+
+    #[test]
+    fn grouped_backward_is_adjoint_of_forward() {
+        let info = CuConvolutionDescriptorInfo { group_count: 2, ..info_2d(1, 1, 1, CudnnConvolutionMode::CrossCorrelation) };
+        let (x_dims, w_dims, y_dims) = ([1, 4, 3, 3], [4, 2, 3, 3], [1, 4, 3, 3]);
+        let x = (0..36).map(|i| (i % 7) as f32 - 3.0).collect::<Vec<_>>();
+        let w = (0..72).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+        let dy = (0..36).map(|i| (i % 3) as f32 - 1.0).collect::<Vec<_>>();
+
+        let mut y = vec![0.0; 36];
+        convolution_forward(&info, 1.0, &x_dims, &x, &w_dims, &w, 0.0, &y_dims, &mut y);
+        let mut dx = vec![0.0; 36];
+        convolution_backward_data(&info, 1.0, &w_dims, &w, &y_dims, &dy, 0.0, &x_dims, &mut dx);
+        let mut dw = vec![0.0; 72];
+        convolution_backward_filter(&info, 1.0, &x_dims, &x, &y_dims, &dy, 0.0, &w_dims, &mut dw);
+        assert_eq!(dot(&y, &dy), dot(&x, &dx));
+        assert_eq!(dot(&y, &dy), dot(&w, &dw));
+    }
+
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+    }
+
+    #[test]
+    fn backward_data_is_adjoint_of_forward() {
+        // <forward(x), dy> == <x, backward_data(dy)> for every x and dy
+        let info = info_2d(1, 2, 1, CudnnConvolutionMode::Convolution);
+        let (x_dims, w_dims, y_dims) = ([2, 2, 5, 5], [3, 2, 3, 3], [2, 3, 3, 3]);
+        let x = (0..100).map(|i| (i % 7) as f32 - 3.0).collect::<Vec<_>>();
+        let w = (0..54).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+        let dy = (0..54).map(|i| (i % 3) as f32 - 1.0).collect::<Vec<_>>();
+
+        let mut y = vec![0.0; 54];
+        convolution_forward(&info, 1.0, &x_dims, &x, &w_dims, &w, 0.0, &y_dims, &mut y);
+        let mut dx = vec![0.0; 100];
+        convolution_backward_data(&info, 1.0, &w_dims, &w, &y_dims, &dy, 0.0, &x_dims, &mut dx);
+        assert_eq!(dot(&y, &dy), dot(&x, &dx));
+    }
+
+    #[test]
+    fn backward_filter_is_adjoint_of_forward() {
+        // <forward(w), dy> == <w, backward_filter(dy)> for every w and dy
+        let info = info_2d(1, 2, 1, CudnnConvolutionMode::Convolution);
+        let (x_dims, w_dims, y_dims) = ([2, 2, 5, 5], [3, 2, 3, 3], [2, 3, 3, 3]);
+        let x = (0..100).map(|i| (i % 7) as f32 - 3.0).collect::<Vec<_>>();
+        let w = (0..54).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+        let dy = (0..54).map(|i| (i % 3) as f32 - 1.0).collect::<Vec<_>>();
+
+        let mut y = vec![0.0; 54];
+        convolution_forward(&info, 1.0, &x_dims, &x, &w_dims, &w, 0.0, &y_dims, &mut y);
+        let mut dw = vec![0.0; 54];
+        convolution_backward_filter(&info, 1.0, &x_dims, &x, &y_dims, &dy, 0.0, &w_dims, &mut dw);
+        assert_eq!(dot(&y, &dy), dot(&w, &dw));
+    }
+
+    #[test]
+    fn backward_bias() {
+        let dy = (0..12).map(|x| x as f32).collect::<Vec<_>>();
+        let mut db = [1.0; 3];
+        convolution_backward_bias(1.0, &[2, 3, 2, 1], &dy, 1.0, &mut db);
+        assert_eq!(db, [1.0 + 0.0 + 1.0 + 6.0 + 7.0, 1.0 + 2.0 + 3.0 + 8.0 + 9.0, 1.0 + 4.0 + 5.0 + 10.0 + 11.0]);
+    }
+
+}
@@ -0,0 +1,65 @@
+
+//! Pure-Rust, host-side reference implementations of the cuDNN operations wrapped by this crate.
+//!
+//! Every function works on fully packed host buffers and follows the cuDNN scaling convention
+//! `dst = alpha * op(src) + beta * dst`. They are slow and only meant to check the GPU results.
+
+mod activation;
+mod convolution;
+mod pooling;
+mod softmax;
+mod batch_normalization;
+mod op_tensor;
+mod reduce;
+
+pub use self::activation::*;
+pub use self::convolution::*;
+pub use self::pooling::*;
+pub use self::softmax::*;
+pub use self::batch_normalization::*;
+pub use self::op_tensor::*;
+pub use self::reduce::*;
+
+
+
+/// Calls `f` on every index of a tensor of dimensions `dims`, in row-major order.
+pub(crate) fn for_each_index<F: FnMut(&[i32])>(dims: &[i32], mut f: F) {
+    if dims.iter().any(|&x| x <= 0) { return }
+    let mut index = vec![0; dims.len()];
+    loop {
+        f(&index);
+        let mut i = dims.len();
+        loop {
+            if i == 0 { return }
+            i -= 1;
+            index[i] += 1;
+            if index[i] < dims[i] { break }
+            index[i] = 0;
+        }
+    }
+}
+
+/// Offset of `index` in a fully packed tensor of dimensions `dims`.
+pub(crate) fn packed_offset(dims: &[i32], index: &[i32]) -> usize {
+    index.iter().zip(dims.iter()).fold(0, |acc, (&i, &d)| acc * d as usize + i as usize)
+}
+
+pub(crate) fn scale_into(alpha: f32, value: f32, beta: f32, dst: &mut f32) {
+    *dst = if beta == 0.0 { alpha * value } else { alpha * value + beta * *dst };
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn index_iteration() {
+        let mut offsets = Vec::new();
+        for_each_index(&[2, 3, 2], |index| offsets.push(packed_offset(&[2, 3, 2], index)));
+        assert_eq!(offsets, (0..12).collect::<Vec<_>>());
+    }
+
+}
@@ -0,0 +1,70 @@
+
+use ffi::CudnnOpTensorOp;
+use super::{for_each_index, packed_offset, scale_into};
+
+
+
+/// Reference for `cudnnOpTensor`: `c = op(alpha1 * a, alpha2 * b) + beta * c`, with NCHW packed tensors.
+/// `a` has the dimensions of `c`, `b` is broadcast along its dimensions of size 1.
+pub fn op_tensor(op: CudnnOpTensorOp, alpha1: f32, dims: &[i32], a: &[f32],
+                 alpha2: f32, b_dims: &[i32], b: &[f32], beta: f32, c: &mut [f32]) {
+    assert_eq!(dims.len(), b_dims.len());
+    let mut b_index = vec![0; dims.len()];
+    for_each_index(dims, |index| {
+        for i in 0..dims.len() {
+            b_index[i] = if b_dims[i] == 1 { 0 } else { index[i] };
+        }
+        let offset = packed_offset(dims, index);
+        let a = alpha1 * a[offset];
+        let b = alpha2 * b[packed_offset(b_dims, &b_index)];
+        let value = match op {
+            CudnnOpTensorOp::Add => a + b,
+            CudnnOpTensorOp::Mul => a * b,
+            CudnnOpTensorOp::Min => a.min(b),
+            CudnnOpTensorOp::Max => a.max(b),
+            CudnnOpTensorOp::Sqrt => a.sqrt(),
+            CudnnOpTensorOp::Not => 1.0 - a,
+        };
+        scale_into(1.0, value, beta, &mut c[offset]);
+    });
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn broadcast_bias() {
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let b = [10.0, 20.0];
+        let mut c = [1.0; 6];
+        op_tensor(CudnnOpTensorOp::Add, 1.0, &[1, 2, 1, 3], &a, 0.5, &[1, 2, 1, 1], &b, 2.0, &mut c);
+        assert_eq!(c, [8.0, 9.0, 10.0, 16.0, 17.0, 18.0]);
+    }
+
+    #[test]
+    fn ops() {
+        let a = [4.0, -1.0, 0.25];
+        let b = [2.0, 3.0, -1.0];
+        let dims = [1, 3, 1, 1];
+        let expected: [(CudnnOpTensorOp, [f32; 3]); 6] = [
+            (CudnnOpTensorOp::Add, [6.0, 2.0, -0.75]),
+            (CudnnOpTensorOp::Mul, [8.0, -3.0, -0.25]),
+            (CudnnOpTensorOp::Min, [2.0, -1.0, -1.0]),
+            (CudnnOpTensorOp::Max, [4.0, 3.0, 0.25]),
+            (CudnnOpTensorOp::Sqrt, [2.0, ::std::f32::NAN, 0.5]),
+            (CudnnOpTensorOp::Not, [-3.0, 2.0, 0.75]),
+        ];
+        for &(op, expected) in &expected {
+            let mut c = [0.0; 3];
+            op_tensor(op, 1.0, &dims, &a, 1.0, &dims, &b, 0.0, &mut c);
+            for i in 0..3 {
+                assert!(c[i] == expected[i] || (c[i].is_nan() && expected[i].is_nan()), "{:?} : {:?} != {:?}", op, c, expected);
+            }
+        }
+    }
+
+}
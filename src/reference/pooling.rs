@@ -0,0 +1,178 @@
+
+use ffi::{CudnnPoolingMode, CudnnNanPropagation};
+use CuPoolingDescriptorInfo;
+use super::{for_each_index, packed_offset, scale_into};
+
+
+
+/// Calls `f(y_offset, x_offsets, window_len)` for every output element, with the offsets of the inputs
+/// covered by its window (the padding excluded) and the size of the window (the padding included).
+fn for_each_window<F: FnMut(usize, &[usize], usize)>(info: &CuPoolingDescriptorInfo, x_dims: &[i32], y_dims: &[i32], mut f: F) {
+    assert_eq!(x_dims.len(), y_dims.len());
+    assert_eq!(x_dims.len() - 2, info.nb_dims as usize);
+    assert_eq!(x_dims[..2], y_dims[..2]);
+
+    let nb_spatial = x_dims.len() - 2;
+    let window_len = info.window_dims.iter().product::<i32>() as usize;
+    let mut x_index = vec![0; x_dims.len()];
+    let mut x_offsets = Vec::with_capacity(window_len);
+    for_each_index(y_dims, |y_index| {
+        x_offsets.clear();
+        x_index[0] = y_index[0];
+        x_index[1] = y_index[1];
+        for_each_index(&info.window_dims, |window_index| {
+            for d in 0..nb_spatial {
+                let position = y_index[d + 2] * info.strides[d] - info.paddings[d] + window_index[d];
+                if position < 0 || position >= x_dims[d + 2] { return }
+                x_index[d + 2] = position;
+            }
+            x_offsets.push(packed_offset(x_dims, &x_index));
+        });
+        f(packed_offset(y_dims, y_index), &x_offsets, window_len);
+    });
+}
+
+/// Offset of the first largest input of the window.
+/// NaNs are the largest values with `Propagate`, and are skipped otherwise.
+fn argmax(nan_opt: CudnnNanPropagation, x: &[f32], x_offsets: &[usize]) -> Option<usize> {
+    if nan_opt == CudnnNanPropagation::Propagate {
+        if let Some(&offset) = x_offsets.iter().find(|&&i| x[i].is_nan()) { return Some(offset) }
+    }
+    x_offsets.iter().cloned().filter(|&i| !x[i].is_nan()).fold(None, |acc, offset| match acc {
+        Some(best) if x[best] >= x[offset] => Some(best),
+        _ => Some(offset),
+    })
+}
+
+fn divisor(mode: CudnnPoolingMode, x_offsets: &[usize], window_len: usize) -> f32 {
+    match mode {
+        CudnnPoolingMode::AverageCountExcludePadding => x_offsets.len() as f32,
+        _ => window_len as f32,
+    }
+}
+
+/// Reference for `cudnnPoolingForward`, with NCHW packed `x` and `y`.
+pub fn pooling_forward(info: &CuPoolingDescriptorInfo, alpha: f32,
+                       x_dims: &[i32], x: &[f32],
+                       beta: f32, y_dims: &[i32], y: &mut [f32]) {
+    for_each_window(info, x_dims, y_dims, |y_offset, x_offsets, window_len| {
+        let value = match info.mode {
+            CudnnPoolingMode::Max | CudnnPoolingMode::MaxDeterministic => argmax(info.maxpooling_nan_opt, x, x_offsets).map_or(0.0, |i| x[i]),
+            mode => x_offsets.iter().map(|&i| x[i]).sum::<f32>() / divisor(mode, x_offsets, window_len),
+        };
+        scale_into(alpha, value, beta, &mut y[y_offset]);
+    });
+}
+
+/// Reference for `cudnnPoolingBackward`: gradient of `x` from `x` and the gradient of `y`.
+/// Max pooling routes the gradient to the first largest input of each window.
+pub fn pooling_backward(info: &CuPoolingDescriptorInfo, alpha: f32,
+                        dy_dims: &[i32], dy: &[f32],
+                        x_dims: &[i32], x: &[f32],
+                        beta: f32, dx: &mut [f32]) {
+    let mut accumulator = vec![0.0; dx.len()];
+    for_each_window(info, x_dims, dy_dims, |y_offset, x_offsets, window_len| {
+        match info.mode {
+            CudnnPoolingMode::Max | CudnnPoolingMode::MaxDeterministic => {
+                if let Some(i) = argmax(info.maxpooling_nan_opt, x, x_offsets) { accumulator[i] += dy[y_offset] }
+            },
+            mode => {
+                let value = dy[y_offset] / divisor(mode, x_offsets, window_len);
+                for &i in x_offsets { accumulator[i] += value }
+            },
+        }
+    });
+    for (value, dx) in accumulator.into_iter().zip(dx.iter_mut()) {
+        scale_into(alpha, value, beta, dx);
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    pub(crate) fn info_2d(mode: CudnnPoolingMode, window: i32, pad: i32, stride: i32) -> CuPoolingDescriptorInfo {
+        CuPoolingDescriptorInfo {
+            mode,
+            maxpooling_nan_opt: CudnnNanPropagation::Propagate,
+            nb_dims: 2,
+            window_dims: vec![window; 2],
+            paddings: vec![pad; 2],
+            strides: vec![stride; 2],
+        }
+    }
+
+    const X: [f32; 16] = [
+        1.0, 2.0, 3.0, 4.0,
+        8.0, 7.0, 6.0, 5.0,
+        9.0, 9.0, 0.0, -1.0,
+        -2.0, 0.0, 3.0, 1.0,
+    ];
+
+    #[test]
+    fn forward_max() {
+        let mut y = [0.0; 4];
+        pooling_forward(&info_2d(CudnnPoolingMode::Max, 2, 0, 2), 1.0, &[1, 1, 4, 4], &X, 0.0, &[1, 1, 2, 2], &mut y);
+        assert_eq!(y, [8.0, 6.0, 9.0, 3.0]);
+    }
+
+    #[test]
+    fn forward_max_nan() {
+        let mut x = X;
+        x[0] = ::std::f32::NAN;
+        let mut info = info_2d(CudnnPoolingMode::Max, 2, 0, 2);
+        let mut y = [0.0; 4];
+        pooling_forward(&info, 1.0, &[1, 1, 4, 4], &x, 0.0, &[1, 1, 2, 2], &mut y);
+        assert!(y[0].is_nan());
+        assert_eq!(y[1..], [6.0, 9.0, 3.0]);
+        info.maxpooling_nan_opt = CudnnNanPropagation::NotPropagate;
+        pooling_forward(&info, 1.0, &[1, 1, 4, 4], &x, 0.0, &[1, 1, 2, 2], &mut y);
+        assert_eq!(y, [8.0, 6.0, 9.0, 3.0]);
+    }
+
+    #[test]
+    fn forward_average_padding() {
+        let mut include = [0.0; 9];
+        let mut exclude = [0.0; 9];
+        pooling_forward(&info_2d(CudnnPoolingMode::AverageCountIncludePadding, 2, 1, 2), 1.0, &[1, 1, 4, 4], &X, 0.0, &[1, 1, 3, 3], &mut include);
+        pooling_forward(&info_2d(CudnnPoolingMode::AverageCountExcludePadding, 2, 1, 2), 1.0, &[1, 1, 4, 4], &X, 0.0, &[1, 1, 3, 3], &mut exclude);
+        assert_eq!(include, [0.25, 1.25, 1.0, 4.25, 5.5, 1.0, -0.5, 0.75, 0.25]);
+        assert_eq!(exclude, [1.0, 2.5, 4.0, 8.5, 5.5, 2.0, -2.0, 1.5, 1.0]);
+    }
+
+    #[test]
+    fn backward_max_routes_to_first_maximum() {
+        let dy = [1.0, 2.0, 3.0, 4.0];
+        let mut dx = [0.5; 16];
+        pooling_backward(&info_2d(CudnnPoolingMode::Max, 2, 0, 2), 1.0, &[1, 1, 2, 2], &dy, &[1, 1, 4, 4], &X, 1.0, &mut dx);
+        assert_eq!(dx, [
+            0.5, 0.5, 0.5, 0.5,
+            1.5, 0.5, 2.5, 0.5,
+            3.5, 0.5, 0.5, 0.5,
+            0.5, 0.5, 4.5, 0.5,
+        ]);
+    }
+
+    #[test]
+    fn backward_average_is_adjoint_of_forward() {
+        // <forward(x), dy> == <x, backward(dy)> for every x and dy
+        for &mode in &[CudnnPoolingMode::AverageCountIncludePadding, CudnnPoolingMode::AverageCountExcludePadding] {
+            let info = info_2d(mode, 3, 1, 2);
+            let (x_dims, y_dims) = ([2, 2, 5, 5], [2, 2, 3, 3]);
+            let x = (0..100).map(|i| (i % 7) as f32 - 3.0).collect::<Vec<_>>();
+            let dy = (0..36).map(|i| (i % 3) as f32 - 1.0).collect::<Vec<_>>();
+
+            let mut y = vec![0.0; 36];
+            pooling_forward(&info, 1.0, &x_dims, &x, 0.0, &y_dims, &mut y);
+            let mut dx = vec![0.0; 100];
+            pooling_backward(&info, 1.0, &y_dims, &dy, &x_dims, &x, 0.0, &mut dx);
+            let lhs: f32 = y.iter().zip(dy.iter()).map(|(a, b)| a * b).sum();
+            let rhs: f32 = x.iter().zip(dx.iter()).map(|(a, b)| a * b).sum();
+            assert!((lhs - rhs).abs() < 1e-4, "{:?} : {} != {}", mode, lhs, rhs);
+        }
+    }
+
+}
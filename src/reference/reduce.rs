@@ -0,0 +1,96 @@
+
+use ffi::CudnnReduceTensorOp;
+use super::{for_each_index, packed_offset, scale_into};
+
+
+
+/// First (position, value) of the slice that no other value is `better` than.
+fn select<F: Fn(f32, f32) -> bool>(slice: &[(usize, f32)], better: F) -> (usize, f32) {
+    slice.iter().fold(slice[0], |best, &x| if better(x.1, best.1) { x } else { best })
+}
+
+/// Reference for `cudnnReduceTensor`: reduces the NCHW packed `a` along the dimensions where `c` has size 1.
+/// `indices` receives, for Min, Max and Amax, the position of the first selected element in its reduced slice.
+pub fn reduce_tensor(op: CudnnReduceTensorOp, alpha: f32, a_dims: &[i32], a: &[f32],
+                     beta: f32, c_dims: &[i32], c: &mut [f32], indices: Option<&mut [i32]>) {
+    assert_eq!(a_dims.len(), c_dims.len());
+    let slice_dims = a_dims.iter().zip(c_dims.iter()).map(|(&a, &c)| if c == 1 { a } else { 1 }).collect::<Vec<_>>();
+    let slice_len = slice_dims.iter().product::<i32>() as f32;
+
+    let mut slices = vec![Vec::new(); c.len()];
+    let mut c_index = vec![0; a_dims.len()];
+    let mut slice_index = vec![0; a_dims.len()];
+    for_each_index(a_dims, |index| {
+        for i in 0..index.len() {
+            c_index[i] = if c_dims[i] == 1 { 0 } else { index[i] };
+            slice_index[i] = if c_dims[i] == 1 { index[i] } else { 0 };
+        }
+        slices[packed_offset(c_dims, &c_index)].push((packed_offset(&slice_dims, &slice_index), a[packed_offset(a_dims, index)]));
+    });
+
+    let mut selected = vec![0; c.len()];
+    for (i, slice) in slices.iter().enumerate() {
+        let value = match op {
+            CudnnReduceTensorOp::Add => slice.iter().map(|x| x.1).sum(),
+            CudnnReduceTensorOp::Mul => slice.iter().map(|x| x.1).product(),
+            CudnnReduceTensorOp::MulNoZeros => slice.iter().map(|x| x.1).filter(|&x| x != 0.0).product(),
+            CudnnReduceTensorOp::Avg => slice.iter().map(|x| x.1).sum::<f32>() / slice_len,
+            CudnnReduceTensorOp::Norm1 => slice.iter().map(|x| x.1.abs()).sum(),
+            CudnnReduceTensorOp::Norm2 => slice.iter().map(|x| x.1 * x.1).sum::<f32>().sqrt(),
+            CudnnReduceTensorOp::Min => { let x = select(slice, |a, b| a < b); selected[i] = x.0; x.1 },
+            CudnnReduceTensorOp::Max => { let x = select(slice, |a, b| a > b); selected[i] = x.0; x.1 },
+            CudnnReduceTensorOp::Amax => { let x = select(slice, |a, b| a.abs() > b.abs()); selected[i] = x.0; x.1.abs() },
+        };
+        scale_into(alpha, value, beta, &mut c[i]);
+    }
+    if let Some(indices) = indices {
+        for (index, selected) in indices.iter_mut().zip(selected.into_iter()) {
+            *index = selected as i32;
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const A: [f32; 6] = [1.0, -4.0, 2.0, 3.0, 0.0, -1.0];
+
+    #[test]
+    fn reduce_columns() {
+        let expected: [(CudnnReduceTensorOp, [f32; 3]); 9] = [
+            (CudnnReduceTensorOp::Add, [4.0, -4.0, 1.0]),
+            (CudnnReduceTensorOp::Mul, [3.0, 0.0, -2.0]),
+            (CudnnReduceTensorOp::MulNoZeros, [3.0, -4.0, -2.0]),
+            (CudnnReduceTensorOp::Avg, [2.0, -2.0, 0.5]),
+            (CudnnReduceTensorOp::Norm1, [4.0, 4.0, 3.0]),
+            (CudnnReduceTensorOp::Norm2, [10.0f32.sqrt(), 4.0, 5.0f32.sqrt()]),
+            (CudnnReduceTensorOp::Min, [1.0, -4.0, -1.0]),
+            (CudnnReduceTensorOp::Max, [3.0, 0.0, 2.0]),
+            (CudnnReduceTensorOp::Amax, [3.0, 4.0, 2.0]),
+        ];
+        for &(op, expected) in &expected {
+            let mut c = [0.0; 3];
+            reduce_tensor(op, 1.0, &[2, 3, 1, 1], &A, 0.0, &[1, 3, 1, 1], &mut c, None);
+            assert_eq!(c, expected, "{:?}", op);
+        }
+    }
+
+    #[test]
+    fn indices() {
+        let mut c = [1.0; 2];
+        let mut indices = [-1; 2];
+        reduce_tensor(CudnnReduceTensorOp::Amax, 2.0, &[2, 3, 1, 1], &A, 1.0, &[2, 1, 1, 1], &mut c, Some(&mut indices));
+        assert_eq!(c, [9.0, 7.0]);
+        assert_eq!(indices, [1, 0]);
+
+        let mut c = [0.0; 1];
+        let mut indices = [-1; 1];
+        reduce_tensor(CudnnReduceTensorOp::Min, 1.0, &[2, 3, 1, 1], &A, 0.0, &[1, 1, 1, 1], &mut c, Some(&mut indices));
+        assert_eq!((c, indices), ([-4.0], [1]));
+    }
+
+}
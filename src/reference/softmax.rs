@@ -0,0 +1,138 @@
+
+use ffi::{CudnnSoftmaxAlgorithm, CudnnSoftmaxMode};
+use super::scale_into;
+
+
+
+/// Calls `f` with the offsets of the elements normalized together, for NCHW packed tensors.
+fn for_each_group<F: FnMut(&[usize])>(mode: CudnnSoftmaxMode, dims: &[i32], mut f: F) {
+    assert!(dims.len() >= 2);
+    let n = dims[0] as usize;
+    let c = dims[1] as usize;
+    let spatial = dims[2..].iter().product::<i32>() as usize;
+    match mode {
+        CudnnSoftmaxMode::Instance => {
+            for i in 0..n {
+                f(&(i * c * spatial..(i + 1) * c * spatial).collect::<Vec<_>>());
+            }
+        },
+        CudnnSoftmaxMode::Channel => {
+            for i in 0..n {
+                for s in 0..spatial {
+                    f(&(0..c).map(|j| (i * c + j) * spatial + s).collect::<Vec<_>>());
+                }
+            }
+        },
+    }
+}
+
+/// Reference for `cudnnSoftmaxForward`, with a NCHW packed `x` and `y`.
+/// Computed in f64 after subtracting the maximum, so it doesn't overflow for any algorithm.
+pub fn softmax_forward(algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, alpha: f32,
+                       dims: &[i32], x: &[f32], beta: f32, y: &mut [f32]) {
+    assert_eq!(x.len(), y.len());
+    for_each_group(mode, dims, |offsets| {
+        let max = offsets.iter().map(|&i| x[i] as f64).fold(::std::f64::NEG_INFINITY, f64::max);
+        let sum = offsets.iter().map(|&i| (x[i] as f64 - max).exp()).sum::<f64>();
+        for &i in offsets {
+            let value = match algo {
+                CudnnSoftmaxAlgorithm::Log => x[i] as f64 - max - sum.ln(),
+                _ => (x[i] as f64 - max).exp() / sum,
+            };
+            scale_into(alpha, value as f32, beta, &mut y[i]);
+        }
+    });
+}
+
+/// Reference for `cudnnSoftmaxBackward`: gradient of `x` from the output `y` and its gradient.
+pub fn softmax_backward(algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, alpha: f32,
+                        dims: &[i32], y: &[f32], dy: &[f32], beta: f32, dx: &mut [f32]) {
+    assert_eq!(y.len(), dy.len());
+    assert_eq!(y.len(), dx.len());
+    for_each_group(mode, dims, |offsets| {
+        match algo {
+            CudnnSoftmaxAlgorithm::Log => {
+                let sum = offsets.iter().map(|&i| dy[i] as f64).sum::<f64>();
+                for &i in offsets {
+                    let value = dy[i] as f64 - (y[i] as f64).exp() * sum;
+                    scale_into(alpha, value as f32, beta, &mut dx[i]);
+                }
+            },
+            _ => {
+                let dot = offsets.iter().map(|&i| dy[i] as f64 * y[i] as f64).sum::<f64>();
+                for &i in offsets {
+                    let value = y[i] as f64 * (dy[i] as f64 - dot);
+                    scale_into(alpha, value as f32, beta, &mut dx[i]);
+                }
+            },
+        }
+    });
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn modes() {
+        let x = [0.0, 1.0, 2.0, 3.0, 2.0f32.ln(), 0.0, 0.0, 0.0];
+        let mut y = [0.0; 8];
+
+        // Over the 4 channels of each of the 2 positions
+        softmax_forward(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Channel, 1.0, &[1, 4, 1, 2], &x, 0.0, &mut y);
+        assert!((y[0] + y[2] + y[4] + y[6] - 1.0).abs() < 1e-6);
+        assert!((y[1] + y[3] + y[5] + y[7] - 1.0).abs() < 1e-6);
+        assert!((y[4] / y[6] - 2.0).abs() < 1e-5);
+
+        // Over the whole image
+        softmax_forward(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Instance, 1.0, &[1, 4, 1, 2], &x, 0.0, &mut y);
+        assert!((y.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!((y[3] / y[1] - 2.0f32.exp()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn extreme_inputs() {
+        let x = [1e30, -1e30, 0.0, 1e30, 89.0, -89.0];
+        let mut y = [0.0; 6];
+        let mut log_y = [0.0; 6];
+        softmax_forward(CudnnSoftmaxAlgorithm::Fast, CudnnSoftmaxMode::Instance, 1.0, &[2, 3, 1, 1], &x, 0.0, &mut y);
+        softmax_forward(CudnnSoftmaxAlgorithm::Log, CudnnSoftmaxMode::Instance, 1.0, &[2, 3, 1, 1], &x, 0.0, &mut log_y);
+        assert_eq!(y[..3], [1.0, 0.0, 0.0]);
+        assert_eq!(log_y[0], 0.0);
+        assert!((log_y[2] + 1e30).abs() < 1e25);
+        assert!(y.iter().chain(log_y.iter()).all(|x| x.is_finite()), "{:?} {:?}", y, log_y);
+        assert_eq!(y[3], 1.0);
+        assert!((log_y[4] - (-1e30 as f32)).abs() < 1e25);
+    }
+
+    #[test]
+    fn backward_matches_finite_differences() {
+        let dims = [1, 3, 1, 2];
+        let x = [0.5, -1.0, 2.0, 0.25, 1.0, 0.0];
+        let dy = [1.0, -2.0, 0.5, 0.0, 3.0, 1.0];
+        for &algo in &[CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxAlgorithm::Log] {
+            for &mode in &[CudnnSoftmaxMode::Instance, CudnnSoftmaxMode::Channel] {
+                let loss = |x: &[f32]| {
+                    let mut y = [0.0; 6];
+                    softmax_forward(algo, mode, 1.0, &dims, x, 0.0, &mut y);
+                    y.iter().zip(dy.iter()).map(|(y, dy)| (y * dy) as f64).sum::<f64>()
+                };
+                let mut y = [0.0; 6];
+                softmax_forward(algo, mode, 1.0, &dims, &x, 0.0, &mut y);
+                let mut dx = [0.0; 6];
+                softmax_backward(algo, mode, 1.0, &dims, &y, &dy, 0.0, &mut dx);
+                for i in 0..6 {
+                    let (mut plus, mut minus) = (x, x);
+                    plus[i] += 1e-2;
+                    minus[i] -= 1e-2;
+                    let expected = (loss(&plus) - loss(&minus)) / 2e-2;
+                    assert!((dx[i] as f64 - expected).abs() < 1e-3, "{:?} {:?} : {:?} != {}", algo, mode, dx, expected);
+                }
+            }
+        }
+    }
+
+}
@@ -1,44 +1,63 @@
 
 use super::ffi::*;
-use super::{Cudnn, CuDropoutDescriptor, CuTensorDescriptor, CuTensorDeref, CuTensorArrayDeref};
+use super::{Cudnn, CudnnError, CuDropoutDescriptor, CuTensorDescriptor, CuTensorDeref, CuTensorArrayDeref, CuTensorArrayMut, CuFilterDescriptor, CuFilterDeref};
 use std::ptr;
+use std::mem::size_of;
 use std::marker::PhantomData;
-use cumath::CuDataType;
+use std::os::raw::c_void;
+use cumath::CuVectorDeref;
+use cudnn_type::CudnnType;
+#[cfg_attr(feature = "disable_checks", allow(unused_imports))]
+use tensor::check_same_dims;
 
 
-pub struct CuRNNDescriptor<T: CuDataType> {
+pub struct CuRNNDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
     _dropout: CuDropoutDescriptor<T>,
     pub(crate) data: *mut _RNNDescriptorStruct,
+    hidden_size: usize,
+    nb_layers: usize,
+    direction: CudnnDirectionMode,
+    mode: CudnnRNNMode,
 }
 
-impl<T: CuDataType> Drop for CuRNNDescriptor<T> {
+impl<T: CudnnType> Drop for CuRNNDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_rnn_descriptor(self.data)
+        let _ = cudnn_destroy_rnn_descriptor(self.data);
     }
 }
 
-impl CuRNNDescriptor<f32> {
+impl<T: CudnnType> CuRNNDescriptor<T> {
 
+    /// Network over sequences of `T`, computing in `T::compute_type()` (floats for halves).
     pub fn new(cudnn: &Cudnn, hidden_size: usize, nb_layers: usize,
                input_mode: CudnnRNNInputMode, direction: CudnnDirectionMode,
-               mode: CudnnRNNMode, algo: CudnnRNNAlgo, dropout: f32, seed: u64) -> CuRNNDescriptor<f32> {
+               mode: CudnnRNNMode, algo: CudnnRNNAlgo, dropout: f32, seed: u64) -> Result<CuRNNDescriptor<T>, CudnnError> {
 
-        let dropout = CuDropoutDescriptor::<f32>::new(cudnn, dropout, seed);
+        let dropout = CuDropoutDescriptor::<T>::new(cudnn, dropout, seed)?;
         let mut data = ptr::null_mut();
-        cudnn_create_rnn_descriptor(&mut data);
-        cudnn_set_rnn_descriptor(cudnn.handle, data,
-                                 hidden_size as i32,
-                                 nb_layers as i32, dropout.data, input_mode, direction, mode, algo, CudnnDataType::Float);
-        CuRNNDescriptor {
+        cudnn_create_rnn_descriptor(&mut data)?;
+        let output = CuRNNDescriptor {
             _phantom: PhantomData,
             _dropout: dropout,
-            data
-        }
+            data,
+            hidden_size,
+            nb_layers,
+            direction,
+            mode,
+        };
+        cudnn_set_rnn_descriptor(cudnn.handle, output.data,
+                                 hidden_size as i32,
+                                 nb_layers as i32, output._dropout.data, input_mode, direction, mode, algo, T::compute_type())?;
+        Ok(output)
     }
 
-    pub fn get_workspace_size(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<f32>]) -> usize {
-        assert_eq!(input_descriptor.len(), sequence_len);
+    pub fn get_workspace_size(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<T>]) -> Result<usize, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if input_descriptor.len() != sequence_len {
+                return Err(CudnnError::bad_param("CuRNNDescriptor::get_workspace_size", "input_descriptor.len() != sequence_len"))
+            }
+        }
         let mut result = 0;
         cudnn_get_rnn_workspace_size(
             cudnn.handle,
@@ -46,12 +65,16 @@ impl CuRNNDescriptor<f32> {
             sequence_len as i32,
             input_descriptor.iter().map(|x| x.data).collect::<Vec<_>>().as_ptr() as *const*const _TensorDescriptorStruct,
             &mut result
-        );
-        result
+        )?;
+        Ok(result)
     }
 
-    pub fn get_training_reserve_size(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<f32>]) -> usize {
-        assert_eq!(input_descriptor.len(), sequence_len);
+    pub fn get_training_reserve_size(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<T>]) -> Result<usize, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if input_descriptor.len() != sequence_len {
+                return Err(CudnnError::bad_param("CuRNNDescriptor::get_training_reserve_size", "input_descriptor.len() != sequence_len"))
+            }
+        }
         let mut result = 0;
         cudnn_get_rnn_training_reserve_size(
             cudnn.handle,
@@ -59,15 +82,257 @@ impl CuRNNDescriptor<f32> {
             sequence_len as i32,
             input_descriptor.iter().map(|x| x.data).collect::<Vec<_>>().as_ptr() as *const*const _TensorDescriptorStruct,
             &mut result
-        );
-        result
+        )?;
+        Ok(result)
+    }
+
+    /// Length of the `f32` workspace vector given to the forward and backward passes.
+    /// cuDNN returns a size in bytes, which is rounded up to whole elements.
+    pub fn get_workspace_len(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<T>]) -> Result<usize, CudnnError> {
+        Ok((self.get_workspace_size(cudnn, sequence_len, input_descriptor)? + size_of::<f32>() - 1) / size_of::<f32>())
+    }
+
+    /// Length of the `f32` reserve vector given to `forward_training` and the backward passes, rounded up like `get_workspace_len`.
+    pub fn get_training_reserve_len(&self, cudnn: &Cudnn, sequence_len: usize, input_descriptor: &[CuTensorDescriptor<T>]) -> Result<usize, CudnnError> {
+        Ok((self.get_training_reserve_size(cudnn, sequence_len, input_descriptor)? + size_of::<f32>() - 1) / size_of::<f32>())
+    }
+
+    /// Size in bytes of the weights of the network, for sequence steps described by `input_desc`
+    pub fn get_params_size(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>) -> Result<usize, CudnnError> {
+        let mut result = 0;
+        cudnn_get_rnn_params_size(cudnn.handle, self.data, input_desc.data, &mut result, T::data_type())?;
+        Ok(result)
+    }
+
+    /// Descriptor of the weights of the network, as a single [len, 1, 1] filter
+    pub fn get_params_descriptor(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>) -> Result<CuFilterDescriptor<T>, CudnnError> {
+        let len = self.get_params_size(cudnn, input_desc)? / size_of::<T>();
+        CuFilterDescriptor::<T>::new(CudnnTensorFormat::Nchw, &[len as i32, 1, 1])
+    }
+
+    pub fn hidden_size(&self) -> usize {
+        self.hidden_size
+    }
+
+    /// Number of pseudo layers, each layer of a bidirectional network being made of two pseudo layers
+    pub fn nb_pseudo_layers(&self) -> usize {
+        match self.direction {
+            CudnnDirectionMode::Unidirectional => self.nb_layers,
+            CudnnDirectionMode::Bidirectional => 2 * self.nb_layers,
+        }
+    }
+
+    /// Number of linear layers in each pseudo layer, the first half applying to the input and the second
+    /// half to the recurrent hidden state.
+    pub fn nb_lin_layers(&self) -> usize {
+        match self.mode {
+            CudnnRNNMode::Relu | CudnnRNNMode::Tanh => 2,
+            CudnnRNNMode::Lstm => 8,
+            CudnnRNNMode::Gru => 6,
+        }
+    }
+
+    /// Location of the matrix of a linear layer inside `weights`
+    pub fn get_lin_layer_matrix_params(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, weights: &CuFilterDeref<T>,
+                                       pseudo_layer: usize, lin_layer_id: usize) -> Result<CuRNNLinLayerParams<T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_lin_layer("CuRNNDescriptor::get_lin_layer_matrix_params", pseudo_layer, lin_layer_id)?;
+        }
+        let mut descriptor = CuFilterDescriptor::<T>::create(0)?;
+        let mut params = ptr::null_mut();
+        cudnn_get_rnn_lin_layer_matrix_params(cudnn.handle, self.data, pseudo_layer as i32, input_desc.data,
+                                              weights.descriptor.data, weights.data as *const c_void, lin_layer_id as i32,
+                                              descriptor.data, &mut params)?;
+        descriptor.update_data_len()?;
+        Ok(CuRNNLinLayerParams { descriptor, offset: params_offset(weights, params) })
+    }
+
+    /// Location of the bias of a linear layer inside `weights`
+    pub fn get_lin_layer_bias_params(&self, cudnn: &Cudnn, input_desc: &CuTensorDescriptor<T>, weights: &CuFilterDeref<T>,
+                                     pseudo_layer: usize, lin_layer_id: usize) -> Result<CuRNNLinLayerParams<T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_lin_layer("CuRNNDescriptor::get_lin_layer_bias_params", pseudo_layer, lin_layer_id)?;
+        }
+        let mut descriptor = CuFilterDescriptor::<T>::create(0)?;
+        let mut params = ptr::null_mut();
+        cudnn_get_rnn_lin_layer_bias_params(cudnn.handle, self.data, pseudo_layer as i32, input_desc.data,
+                                            weights.descriptor.data, weights.data as *const c_void, lin_layer_id as i32,
+                                            descriptor.data, &mut params)?;
+        descriptor.update_data_len()?;
+        Ok(CuRNNLinLayerParams { descriptor, offset: params_offset(weights, params) })
     }
 
-    /*pub fn forward_inference(&self, cudnn: &Cudnn, sequence_len: usize,
-                             inputs_descriptor: &CuTensorArrayDeref<f32>,
-                             hidden_state_input: &CuTensorDeref<f32>,
-                             cell_state_input: &CuTensorDeref<f32>, weights: &CuFi)*/
+    #[cfg(not(feature = "disable_checks"))]
+    fn check_lin_layer(&self, function: &'static str, pseudo_layer: usize, lin_layer_id: usize) -> Result<(), CudnnError> {
+        if pseudo_layer >= self.nb_pseudo_layers() {
+            return Err(CudnnError::bad_param(function, format!("pseudo layer {} out of {}", pseudo_layer, self.nb_pseudo_layers())))
+        }
+        if lin_layer_id >= self.nb_lin_layers() {
+            return Err(CudnnError::bad_param(function, format!("linear layer {} out of {} for {:?}", lin_layer_id, self.nb_lin_layers(), self.mode)))
+        }
+        Ok(())
+    }
+
+    /// Checks that both sequences have the same number of steps, and that the given `states` have dimensions
+    /// [nb_pseudo_layers, batch size of the first step, hidden_size].
+    #[cfg(not(feature = "disable_checks"))]
+    fn check_sequences(&self, function: &'static str, input: &CuTensorArrayDeref<T>, output: &CuTensorArrayDeref<T>,
+                       states: &[(&str, Option<&CuTensorDescriptor<T>>)]) -> Result<(), CudnnError> {
+        if input.descriptors.len() != output.descriptors.len() {
+            return Err(CudnnError::bad_param(function, format!("input sequence has {} steps but output sequence has {}",
+                                                               input.descriptors.len(), output.descriptors.len())))
+        }
+        if let Some(&first_step) = input.descriptors.first() {
+            let mut data_type = CudnnDataType::Int8x4;
+            let mut nb_dims = -1;
+            let mut dimensions = [-1; 8];
+            let mut strides = [-1; 8];
+            cudnn_get_tensor_nd_descriptor(first_step as *mut _, 8, &mut data_type, &mut nb_dims, dimensions.as_mut_ptr(), strides.as_mut_ptr())?;
+            let expected = [self.nb_pseudo_layers() as i32, dimensions[0], self.hidden_size as i32];
+            for &(name, state) in states {
+                if let Some(state) = state {
+                    check_same_dims(function, name, &state.get_info()?.dimensions, &expected)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the network over the `input` sequence, starting from the hidden state `hx` and, for LSTMs, the cell state `cx`.
+    /// The final states are written to `hy` and `cy`. Missing cell states are considered null.
+    pub fn forward_inference(&self, cudnn: &mut Cudnn,
+                             input: &CuTensorArrayDeref<T>,
+                             hx: &CuTensorDeref<T>, cx: Option<&CuTensorDeref<T>>,
+                             weights: &CuFilterDeref<T>,
+                             output: &mut CuTensorArrayMut<T>,
+                             hy: &mut CuTensorDeref<T>, cy: Option<&mut CuTensorDeref<T>>,
+                             workspace: &mut CuVectorDeref<f32>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_sequences("CuRNNDescriptor::forward_inference", input, output,
+                                 &[("hx", Some(hx.descriptor())), ("cx", cx.map(|x| x.descriptor())),
+                                   ("hy", Some(hy.descriptor())), ("cy", cy.as_ref().map(|x| x.descriptor()))])?;
+        }
+        let (cx_desc, cx) = state(cx, hx.descriptor());
+        let (cy_desc, cy) = state_mut(cy, hy.descriptor());
+        cudnn_rnn_forward_inference(cudnn.handle, self.data, input.descriptors.len() as i32,
+                                    input.descriptors.as_ptr(), input.data as *const c_void,
+                                    hx.descriptor.data, hx.data as *const c_void,
+                                    cx_desc, cx,
+                                    weights.descriptor.data, weights.data as *const c_void,
+                                    output.descriptors.as_ptr(), output.as_mut_ptr() as *mut c_void,
+                                    hy.descriptor.data, hy.data as *mut c_void,
+                                    cy_desc, cy,
+                                    workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>())
+    }
+
+    /// Same as `forward_inference`, also saving in `reserve` what the backward passes need.
+    pub fn forward_training(&self, cudnn: &mut Cudnn,
+                            input: &CuTensorArrayDeref<T>,
+                            hx: &CuTensorDeref<T>, cx: Option<&CuTensorDeref<T>>,
+                            weights: &CuFilterDeref<T>,
+                            output: &mut CuTensorArrayMut<T>,
+                            hy: &mut CuTensorDeref<T>, cy: Option<&mut CuTensorDeref<T>>,
+                            workspace: &mut CuVectorDeref<f32>, reserve: &mut CuVectorDeref<f32>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_sequences("CuRNNDescriptor::forward_training", input, output,
+                                 &[("hx", Some(hx.descriptor())), ("cx", cx.map(|x| x.descriptor())),
+                                   ("hy", Some(hy.descriptor())), ("cy", cy.as_ref().map(|x| x.descriptor()))])?;
+        }
+        let (cx_desc, cx) = state(cx, hx.descriptor());
+        let (cy_desc, cy) = state_mut(cy, hy.descriptor());
+        cudnn_rnn_forward_training(cudnn.handle, self.data, input.descriptors.len() as i32,
+                                   input.descriptors.as_ptr(), input.data as *const c_void,
+                                   hx.descriptor.data, hx.data as *const c_void,
+                                   cx_desc, cx,
+                                   weights.descriptor.data, weights.data as *const c_void,
+                                   output.descriptors.as_ptr(), output.as_mut_ptr() as *mut c_void,
+                                   hy.descriptor.data, hy.data as *mut c_void,
+                                   cy_desc, cy,
+                                   workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                   reserve.as_mut_ptr() as *mut c_void, reserve.len() * size_of::<f32>())
+    }
+
+    /// Computes the gradients of the input sequence and of the initial states, from the gradients of the output
+    /// sequence and of the final states. `reserve` must come from the matching `forward_training`.
+    pub fn backward_data(&self, cudnn: &mut Cudnn,
+                         output: &CuTensorArrayDeref<T>, output_signal: &CuTensorArrayDeref<T>,
+                         hy_signal: &CuTensorDeref<T>, cy_signal: Option<&CuTensorDeref<T>>,
+                         weights: &CuFilterDeref<T>,
+                         hx: &CuTensorDeref<T>, cx: Option<&CuTensorDeref<T>>,
+                         input_signal: &mut CuTensorArrayMut<T>,
+                         hx_signal: &mut CuTensorDeref<T>, cx_signal: Option<&mut CuTensorDeref<T>>,
+                         workspace: &mut CuVectorDeref<f32>, reserve: &mut CuVectorDeref<f32>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_sequences("CuRNNDescriptor::backward_data", input_signal, output,
+                                 &[("hy_signal", Some(hy_signal.descriptor())), ("cy_signal", cy_signal.map(|x| x.descriptor())),
+                                   ("hx", Some(hx.descriptor())), ("cx", cx.map(|x| x.descriptor())),
+                                   ("hx_signal", Some(hx_signal.descriptor())), ("cx_signal", cx_signal.as_ref().map(|x| x.descriptor()))])?;
+            self.check_sequences("CuRNNDescriptor::backward_data", input_signal, output_signal, &[])?;
+        }
+        let (cy_signal_desc, cy_signal) = state(cy_signal, hy_signal.descriptor());
+        let (cx_desc, cx) = state(cx, hx.descriptor());
+        let (cx_signal_desc, cx_signal) = state_mut(cx_signal, hx_signal.descriptor());
+        cudnn_rnn_backward_data(cudnn.handle, self.data, output.descriptors.len() as i32,
+                                output.descriptors.as_ptr(), output.data as *const c_void,
+                                output_signal.descriptors.as_ptr(), output_signal.data as *const c_void,
+                                hy_signal.descriptor.data, hy_signal.data as *const c_void,
+                                cy_signal_desc, cy_signal,
+                                weights.descriptor.data, weights.data as *const c_void,
+                                hx.descriptor.data, hx.data as *const c_void,
+                                cx_desc, cx,
+                                input_signal.descriptors.as_ptr(), input_signal.as_mut_ptr() as *mut c_void,
+                                hx_signal.descriptor.data, hx_signal.data as *mut c_void,
+                                cx_signal_desc, cx_signal,
+                                workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                reserve.as_mut_ptr() as *mut c_void, reserve.len() * size_of::<f32>())
+    }
+
+    /// Accumulates the gradient of the weights into `weights_signal`. Must be called after `backward_data`.
+    pub fn backward_weights(&self, cudnn: &mut Cudnn,
+                            input: &CuTensorArrayDeref<T>,
+                            hx: &CuTensorDeref<T>,
+                            output: &CuTensorArrayDeref<T>,
+                            workspace: &mut CuVectorDeref<f32>,
+                            weights_signal: &mut CuFilterDeref<T>,
+                            reserve: &mut CuVectorDeref<f32>) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            self.check_sequences("CuRNNDescriptor::backward_weights", input, output, &[("hx", Some(hx.descriptor()))])?;
+        }
+        cudnn_rnn_backward_weights(cudnn.handle, self.data, input.descriptors.len() as i32,
+                                   input.descriptors.as_ptr(), input.data as *const c_void,
+                                   hx.descriptor.data, hx.data as *const c_void,
+                                   output.descriptors.as_ptr(), output.data as *const c_void,
+                                   workspace.as_mut_ptr() as *mut c_void, workspace.len() * size_of::<f32>(),
+                                   weights_signal.descriptor.data, weights_signal.data as *mut c_void,
+                                   reserve.as_mut_ptr() as *mut c_void, reserve.len() * size_of::<f32>())
+    }
+
+}
+
+/// Matrix or bias of a linear layer, located `offset` elements into the packed weights of a network
+pub struct CuRNNLinLayerParams<T: CudnnType> {
+    pub descriptor: CuFilterDescriptor<T>,
+    pub offset: usize,
+}
+
+fn params_offset<T: CudnnType>(weights: &CuFilterDeref<T>, params: *mut c_void) -> usize {
+    (params as usize - weights.data as usize) / size_of::<T>()
+}
+
+/// Descriptor and data of an optional state, null data standing for a null state.
+fn state<T: CudnnType>(tensor: Option<&CuTensorDeref<T>>, fallback: &CuTensorDescriptor<T>) -> (*const _TensorDescriptorStruct, *const c_void) {
+    match tensor {
+        Some(tensor) => (tensor.descriptor.data, tensor.data as *const c_void),
+        None => (fallback.data, ptr::null()),
+    }
+}
 
+/// Descriptor and data of an optional output state, null data meaning that the state isn't saved.
+fn state_mut<T: CudnnType>(tensor: Option<&mut CuTensorDeref<T>>, fallback: &CuTensorDescriptor<T>) -> (*const _TensorDescriptorStruct, *mut c_void) {
+    match tensor {
+        Some(tensor) => (tensor.descriptor.data, tensor.data as *mut c_void),
+        None => (fallback.data, ptr::null_mut()),
+    }
 }
 
 
@@ -75,22 +340,295 @@ impl CuRNNDescriptor<f32> {
 mod tests {
 
     use super::*;
+    use cumath::CuVector;
+    use tensor::CuTensorDescriptorArray;
+    use test_utils::{host_data, assert_close};
+
+    const BATCH: i32 = 2;
+    const INPUT_SIZE: i32 = 3;
+    const HIDDEN_SIZE: i32 = 4;
+    const SEQUENCE_LEN: usize = 3;
+
+    fn lstm(cudnn: &Cudnn) -> CuRNNDescriptor<f32> {
+        CuRNNDescriptor::new(cudnn, HIDDEN_SIZE as usize, 1,
+                             CudnnRNNInputMode::LinearInput,
+                             CudnnDirectionMode::Unidirectional,
+                             CudnnRNNMode::Lstm,
+                             CudnnRNNAlgo::Standard,
+                             0.0, 100).unwrap()
+    }
+
+    fn sequence(size: i32) -> Vec<CuTensorDescriptor<f32>> {
+        (0..SEQUENCE_LEN).map(|_| CuTensorDescriptor::<f32>::fully_packed(&[BATCH, size, 1]).unwrap()).collect()
+    }
+
+    /// Host LSTM in f64, reading its weights at the offsets given by the lin-layer queries.
+    struct HostLstm {
+        matrices: Vec<usize>,
+        biases: Vec<usize>,
+    }
+
+    impl HostLstm {
+
+        fn new(cudnn: &Cudnn, rnn: &CuRNNDescriptor<f32>, input_desc: &CuTensorDescriptor<f32>, weights: &CuFilterDeref<f32>) -> HostLstm {
+            let matrices = (0..8).map(|id| rnn.get_lin_layer_matrix_params(cudnn, input_desc, weights, 0, id).unwrap().offset).collect();
+            let biases = (0..8).map(|id| rnn.get_lin_layer_bias_params(cudnn, input_desc, weights, 0, id).unwrap().offset).collect();
+            HostLstm { matrices, biases }
+        }
+
+        /// Output sequence, hy and cy. Gates are ordered input, forget, new memory, output,
+        /// the first 4 lin layers applying to the input and the last 4 to the hidden state.
+        fn forward(&self, weights: &[f64], x: &[f64], hx: &[f64], cx: &[f64]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+            let (batch, input_size, hidden_size) = (BATCH as usize, INPUT_SIZE as usize, HIDDEN_SIZE as usize);
+            let sigmoid = |x: f64| 1.0 / (1.0 + (-x).exp());
+            let (mut h, mut c) = (hx.to_vec(), cx.to_vec());
+            let mut y = Vec::new();
+            for t in 0..SEQUENCE_LEN {
+                let mut next_h = vec![0.0; h.len()];
+                for b in 0..batch {
+                    let x = &x[(t * batch + b) * input_size..][..input_size];
+                    let previous_h = &h[b * hidden_size..][..hidden_size];
+                    for r in 0..hidden_size {
+                        let gate = |g: usize| {
+                            let w = &weights[self.matrices[g] + r * input_size..][..input_size];
+                            let u = &weights[self.matrices[g + 4] + r * hidden_size..][..hidden_size];
+                            weights[self.biases[g] + r] + weights[self.biases[g + 4] + r]
+                                + w.iter().zip(x).map(|(w, x)| w * x).sum::<f64>()
+                                + u.iter().zip(previous_h).map(|(u, h)| u * h).sum::<f64>()
+                        };
+                        let i = b * hidden_size + r;
+                        c[i] = sigmoid(gate(1)) * c[i] + sigmoid(gate(0)) * gate(2).tanh();
+                        next_h[i] = sigmoid(gate(3)) * c[i].tanh();
+                    }
+                }
+                h = next_h;
+                y.extend_from_slice(&h);
+            }
+            (y, h, c)
+        }
+
+        /// Loss `<y, dy> + <hy, dhy>` whose gradients the backward passes compute
+        fn loss(&self, weights: &[f64], x: &[f64], hx: &[f64], cx: &[f64], dy: &[f64], dhy: &[f64]) -> f64 {
+            let (y, hy, _) = self.forward(weights, x, hx, cx);
+            y.iter().zip(dy).chain(hy.iter().zip(dhy)).map(|(a, b)| a * b).sum()
+        }
+
+    }
+
+    fn to_f64(x: &[f32]) -> Vec<f64> {
+        x.iter().map(|&x| x as f64).collect()
+    }
+
+    fn to_f32(x: &[f64]) -> Vec<f32> {
+        x.iter().map(|&x| x as f32).collect()
+    }
+
+    /// Central differences of `f` around `x`
+    fn numerical_gradient<F: Fn(&[f64]) -> f64>(x: &[f64], f: F) -> Vec<f32> {
+        let epsilon = 1e-4;
+        (0..x.len()).map(|i| {
+            let mut x = x.to_vec();
+            x[i] += epsilon;
+            let plus = f(&x);
+            x[i] -= 2.0 * epsilon;
+            ((plus - f(&x)) / (2.0 * epsilon)) as f32
+        }).collect()
+    }
 
     #[test]
     fn test() {
 
-        let cudnn = Cudnn::new();
+        let cudnn = Cudnn::new().unwrap();
         let rnn = CuRNNDescriptor::new(&cudnn, 512, 64,
                                        CudnnRNNInputMode::LinearInput,
                                        CudnnDirectionMode::Unidirectional,
                                        CudnnRNNMode::Gru,
                                        CudnnRNNAlgo::Standard,
-                                       0.5, 100);
-        let input_desc = [CuTensorDescriptor::<f32>::new(&[1, 1, 12, 6])];
+                                       0.5, 100).unwrap();
+        let input_desc = [CuTensorDescriptor::<f32>::fully_packed(&[1, 12, 6]).unwrap()];
+
+        let workspace_size = rnn.get_workspace_size(&cudnn, 1, &input_desc).unwrap();
+        assert_eq!(rnn.get_workspace_len(&cudnn, 1, &input_desc).unwrap(), (workspace_size + 3) / 4);
+        let reserve_size = rnn.get_training_reserve_size(&cudnn, 1, &input_desc).unwrap();
+        assert_eq!(rnn.get_training_reserve_len(&cudnn, 1, &input_desc).unwrap(), (reserve_size + 3) / 4);
+
+    }
+
+    #[test]
+    fn lstm_forward() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let rnn = lstm(&cudnn);
+
+        let input_desc = sequence(INPUT_SIZE);
+        let output_desc = sequence(HIDDEN_SIZE);
+        let state_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, BATCH, HIDDEN_SIZE]).unwrap();
+        let weights_desc = rnn.get_params_descriptor(&cudnn, &input_desc[0]).unwrap();
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let weights_host = host_data(weights_desc.data_len(), 5);
+        let hx_host = host_data(state_desc.data_len(), 2);
+        let cx_host = host_data(state_desc.data_len(), 3);
+        let input = CuVector::<f32>::from_host_data(&input_host);
+        let weights = CuVector::<f32>::from_host_data(&weights_host);
+        let hx = CuVector::<f32>::from_host_data(&hx_host);
+        let cx = CuVector::<f32>::from_host_data(&cx_host);
+        let mut output = CuVector::<f32>::zero(output_desc.data_len());
+        let mut hy = CuVector::<f32>::zero(state_desc.data_len());
+        let mut cy = CuVector::<f32>::zero(state_desc.data_len());
+        let mut workspace = CuVector::<f32>::zero(rnn.get_workspace_len(&cudnn, SEQUENCE_LEN, &input_desc).unwrap());
+        let mut reserve = CuVector::<f32>::zero(rnn.get_training_reserve_len(&cudnn, SEQUENCE_LEN, &input_desc).unwrap());
+
+        let reference = HostLstm::new(&cudnn, &rnn, &input_desc[0], &weights_desc.link(&weights).unwrap());
+        let (expected_output, expected_hy, expected_cy) = reference.forward(&to_f64(&weights_host), &to_f64(&input_host),
+                                                                            &to_f64(&hx_host), &to_f64(&cx_host));
+
+        rnn.forward_inference(&mut cudnn, &input_desc.link(&input).unwrap(),
+                              &state_desc.link(&hx).unwrap(), Some(&state_desc.link(&cx).unwrap()),
+                              &weights_desc.link(&weights).unwrap(),
+                              &mut output_desc.link_mut(&mut output).unwrap(),
+                              &mut state_desc.link_mut(&mut hy).unwrap(), Some(&mut state_desc.link_mut(&mut cy).unwrap()),
+                              &mut workspace).unwrap();
+        assert_close("lstm inference output", &output, &to_f32(&expected_output));
+        assert_close("lstm inference hy", &hy, &to_f32(&expected_hy));
+        assert_close("lstm inference cy", &cy, &to_f32(&expected_cy));
+
+        let mut output = CuVector::<f32>::zero(output_desc.data_len());
+        rnn.forward_training(&mut cudnn, &input_desc.link(&input).unwrap(),
+                             &state_desc.link(&hx).unwrap(), Some(&state_desc.link(&cx).unwrap()),
+                             &weights_desc.link(&weights).unwrap(),
+                             &mut output_desc.link_mut(&mut output).unwrap(),
+                             &mut state_desc.link_mut(&mut hy).unwrap(), None,
+                             &mut workspace, &mut reserve).unwrap();
+        assert_close("lstm training output", &output, &to_f32(&expected_output));
+    }
+
+    #[test]
+    fn state_dimensions() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let rnn = lstm(&cudnn);
+        assert_eq!(rnn.hidden_size(), HIDDEN_SIZE as usize);
+
+        let input_desc = sequence(INPUT_SIZE);
+        let output_desc = sequence(HIDDEN_SIZE);
+        let state_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, BATCH, HIDDEN_SIZE]).unwrap();
+        let weights_desc = rnn.get_params_descriptor(&cudnn, &input_desc[0]).unwrap();
+
+        let input = CuVector::<f32>::zero(input_desc.data_len());
+        let weights = CuVector::<f32>::zero(weights_desc.data_len());
+        let hx = CuVector::<f32>::zero(state_desc.data_len());
+        let mut output = CuVector::<f32>::zero(output_desc.data_len());
+        let mut hy = CuVector::<f32>::zero(state_desc.data_len());
+        let mut workspace = CuVector::<f32>::zero(rnn.get_workspace_len(&cudnn, SEQUENCE_LEN, &input_desc).unwrap());
+
+        for wrong_dims in &[[2, BATCH, HIDDEN_SIZE], [1, BATCH + 1, HIDDEN_SIZE], [1, BATCH, HIDDEN_SIZE + 1]] {
+            let wrong_desc = CuTensorDescriptor::<f32>::fully_packed(wrong_dims).unwrap();
+            let mut cy = CuVector::<f32>::zero(wrong_desc.data_len());
+            let error = rnn.forward_inference(&mut cudnn, &input_desc.link(&input).unwrap(),
+                                              &state_desc.link(&hx).unwrap(), None,
+                                              &weights_desc.link(&weights).unwrap(),
+                                              &mut output_desc.link_mut(&mut output).unwrap(),
+                                              &mut state_desc.link_mut(&mut hy).unwrap(), Some(&mut wrong_desc.link_mut(&mut cy).unwrap()),
+                                              &mut workspace).unwrap_err();
+            assert_eq!(error.status(), CudnnStatus::BadParam);
+            assert_eq!(error.function(), "CuRNNDescriptor::forward_inference");
+        }
+    }
+
+    #[test]
+    fn lstm_lin_layers() {
+        let cudnn = Cudnn::new().unwrap();
+        let rnn = lstm(&cudnn);
+        assert_eq!(rnn.nb_pseudo_layers(), 1);
+        assert_eq!(rnn.nb_lin_layers(), 8);
+
+        let input_desc = sequence(INPUT_SIZE);
+        let weights_desc = rnn.get_params_descriptor(&cudnn, &input_desc[0]).unwrap();
+        let weights = CuVector::<f32>::zero(weights_desc.data_len());
+        let weights = weights_desc.link(&weights).unwrap();
+
+        let mut covered = vec![false; weights_desc.data_len()];
+        for lin_layer_id in 0..rnn.nb_lin_layers() {
+            let matrix = rnn.get_lin_layer_matrix_params(&cudnn, &input_desc[0], &weights, 0, lin_layer_id).unwrap();
+            let bias = rnn.get_lin_layer_bias_params(&cudnn, &input_desc[0], &weights, 0, lin_layer_id).unwrap();
+            let columns = if lin_layer_id < 4 { INPUT_SIZE } else { HIDDEN_SIZE };
+            assert_eq!(matrix.descriptor.data_len(), (HIDDEN_SIZE * columns) as usize);
+            assert_eq!(bias.descriptor.data_len(), HIDDEN_SIZE as usize);
+            for params in &[matrix, bias] {
+                for i in params.offset..params.offset + params.descriptor.data_len() {
+                    assert!(!covered[i]);
+                    covered[i] = true;
+                }
+            }
+        }
+        assert!(covered.iter().all(|x| *x));
+
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(rnn.get_lin_layer_matrix_params(&cudnn, &input_desc[0], &weights, 1, 0).is_err());
+            assert!(rnn.get_lin_layer_bias_params(&cudnn, &input_desc[0], &weights, 0, 8).is_err());
+        }
+    }
+
+    #[test]
+    fn lstm_backward() {
+        let mut cudnn = Cudnn::new().unwrap();
+        let rnn = lstm(&cudnn);
+
+        let input_desc = sequence(INPUT_SIZE);
+        let output_desc = sequence(HIDDEN_SIZE);
+        let state_desc = CuTensorDescriptor::<f32>::fully_packed(&[1, BATCH, HIDDEN_SIZE]).unwrap();
+        let weights_desc = rnn.get_params_descriptor(&cudnn, &input_desc[0]).unwrap();
+
+        let input_host = host_data(input_desc.data_len(), 1);
+        let weights_host = host_data(weights_desc.data_len(), 5);
+        let hx_host = host_data(state_desc.data_len(), 2);
+        let cx_host = host_data(state_desc.data_len(), 3);
+        let output_signal_host = host_data(output_desc.data_len(), 4);
+        let hy_signal_host = host_data(state_desc.data_len(), 6);
+        let input = CuVector::<f32>::from_host_data(&input_host);
+        let weights = CuVector::<f32>::from_host_data(&weights_host);
+        let hx = CuVector::<f32>::from_host_data(&hx_host);
+        let cx = CuVector::<f32>::from_host_data(&cx_host);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_host);
+        let hy_signal = CuVector::<f32>::from_host_data(&hy_signal_host);
+        let mut output = CuVector::<f32>::zero(output_desc.data_len());
+        let mut hy = CuVector::<f32>::zero(state_desc.data_len());
+        let mut input_signal = CuVector::<f32>::new(1.0, input_desc.data_len());
+        let mut hx_signal = CuVector::<f32>::zero(state_desc.data_len());
+        let mut cx_signal = CuVector::<f32>::zero(state_desc.data_len());
+        let mut weights_signal = CuVector::<f32>::zero(weights_desc.data_len());
+        let mut workspace = CuVector::<f32>::zero(rnn.get_workspace_len(&cudnn, SEQUENCE_LEN, &input_desc).unwrap());
+        let mut reserve = CuVector::<f32>::zero(rnn.get_training_reserve_len(&cudnn, SEQUENCE_LEN, &input_desc).unwrap());
+
+        rnn.forward_training(&mut cudnn, &input_desc.link(&input).unwrap(),
+                             &state_desc.link(&hx).unwrap(), Some(&state_desc.link(&cx).unwrap()),
+                             &weights_desc.link(&weights).unwrap(),
+                             &mut output_desc.link_mut(&mut output).unwrap(),
+                             &mut state_desc.link_mut(&mut hy).unwrap(), None,
+                             &mut workspace, &mut reserve).unwrap();
+        rnn.backward_data(&mut cudnn, &output_desc.link(&output).unwrap(), &output_desc.link(&output_signal).unwrap(),
+                          &state_desc.link(&hy_signal).unwrap(), None,
+                          &weights_desc.link(&weights).unwrap(),
+                          &state_desc.link(&hx).unwrap(), Some(&state_desc.link(&cx).unwrap()),
+                          &mut input_desc.link_mut(&mut input_signal).unwrap(),
+                          &mut state_desc.link_mut(&mut hx_signal).unwrap(), Some(&mut state_desc.link_mut(&mut cx_signal).unwrap()),
+                          &mut workspace, &mut reserve).unwrap();
+        rnn.backward_weights(&mut cudnn, &input_desc.link(&input).unwrap(), &state_desc.link(&hx).unwrap(),
+                             &output_desc.link(&output).unwrap(), &mut workspace,
+                             &mut weights_desc.link_mut(&mut weights_signal).unwrap(), &mut reserve).unwrap();
 
-        rnn.get_workspace_size(&cudnn, 1, &input_desc);
-        rnn.get_training_reserve_size(&cudnn, 1, &input_desc);
+        let reference = HostLstm::new(&cudnn, &rnn, &input_desc[0], &weights_desc.link(&weights).unwrap());
+        let (w, x, h, c) = (to_f64(&weights_host), to_f64(&input_host), to_f64(&hx_host), to_f64(&cx_host));
+        let (dy, dhy) = (to_f64(&output_signal_host), to_f64(&hy_signal_host));
+        assert_close("lstm input signal", &input_signal, &numerical_gradient(&x, |x| reference.loss(&w, x, &h, &c, &dy, &dhy)));
+        assert_close("lstm hx signal", &hx_signal, &numerical_gradient(&h, |h| reference.loss(&w, &x, h, &c, &dy, &dhy)));
+        assert_close("lstm cx signal", &cx_signal, &numerical_gradient(&c, |c| reference.loss(&w, &x, &h, c, &dy, &dhy)));
+        let expected_weights_signal = numerical_gradient(&w, |w| reference.loss(w, &x, &h, &c, &dy, &dhy));
+        assert_close("lstm weights signal", &weights_signal, &expected_weights_signal);
 
+        rnn.backward_weights(&mut cudnn, &input_desc.link(&input).unwrap(), &state_desc.link(&hx).unwrap(),
+                             &output_desc.link(&output).unwrap(), &mut workspace,
+                             &mut weights_desc.link_mut(&mut weights_signal).unwrap(), &mut reserve).unwrap();
+        assert_close("lstm accumulated weights signal", &weights_signal, &expected_weights_signal.iter().map(|x| 2.0 * x).collect::<Vec<_>>());
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,107 @@
+
+use super::*;
+use super::ffi::*;
+use std::os::raw::c_void;
+
+
+
+/// Softmax of `input`, written to `output`.
+pub fn softmax_forward<T: CudnnType>(cudnn: &Cudnn, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode,
+                                      input: &CuTensorDeref<T>, input_scale: T::Scaling, output: &mut CuTensorDeref<T>, output_scale: T::Scaling) -> Result<(), CudnnError> {
+    cudnn_softmax_forward(cudnn.handle, algo, mode,
+                          &input_scale as *const T::Scaling as *const c_void, input.descriptor.data, input.data as *const c_void,
+                          &output_scale as *const T::Scaling as *const c_void, output.descriptor.data, output.data as *mut c_void)
+}
+
+/// Gradient of the input of a softmax, from its `output` and the gradient of the output.
+pub fn softmax_backward<T: CudnnType>(cudnn: &Cudnn, algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, alpha: T::Scaling, beta: T::Scaling,
+                                       output: &CuTensorDeref<T>,
+                                       output_signal: &CuTensorDeref<T>,
+                                       input_signal: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+    cudnn_softmax_backward(cudnn.handle, algo, mode,
+                           (&alpha) as *const T::Scaling as *const c_void,
+                           output.descriptor.data, output.data as *const c_void,
+                           output_signal.descriptor.data, output_signal.data as *const c_void,
+                           (&beta) as *const T::Scaling as *const c_void,
+                           input_signal.descriptor.data, input_signal.data as *mut c_void)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::*;
+    use test_utils::assert_close;
+
+    fn test_softmax(algo: CudnnSoftmaxAlgorithm, mode: CudnnSoftmaxMode, input_data: &[f32]) {
+        let cudnn = Cudnn::new().unwrap();
+        let dims = [2, 3, 2, 2];
+
+        let tensor_descriptor = CuTensorDescriptor::<f32>::fully_packed(&dims).unwrap();
+        let output_signal_data = (0..input_data.len()).map(|i| (i % 5) as f32 - 2.0).collect::<Vec<_>>();
+        let input = CuVector::<f32>::from_host_data(input_data);
+        let output_signal = CuVector::<f32>::from_host_data(&output_signal_data);
+        let mut output = CuVector::<f32>::zero(tensor_descriptor.data_len());
+        let mut input_signal = CuVector::<f32>::zero(tensor_descriptor.data_len());
+
+        softmax_forward(&cudnn, algo, mode, &tensor_descriptor.link(&input).unwrap(), 1.0,
+                        &mut tensor_descriptor.link_mut(&mut output).unwrap(), 0.0).unwrap();
+        let mut expected_output = vec![0.0; input_data.len()];
+        reference::softmax_forward(algo, mode, 1.0, &dims, input_data, 0.0, &mut expected_output);
+        assert_close("softmax forward", &output, &expected_output);
+
+        softmax_backward(&cudnn, algo, mode, 1.0, 0.0,
+                         &tensor_descriptor.link(&output).unwrap(),
+                         &tensor_descriptor.link(&output_signal).unwrap(),
+                         &mut tensor_descriptor.link_mut(&mut input_signal).unwrap()).unwrap();
+        let mut expected = vec![0.0; input_data.len()];
+        reference::softmax_backward(algo, mode, 1.0, &dims, &expected_output, &output_signal_data, 0.0, &mut expected);
+        assert_close("softmax backward", &input_signal, &expected);
+    }
+
+    fn moderate_input() -> Vec<f32> {
+        (0..24).map(|i| ((i * 7) % 11) as f32 / 3.0 - 1.5).collect()
+    }
+
+    fn extreme_input() -> Vec<f32> {
+        (0..24).map(|i| match i % 4 {
+            0 => 1e4,
+            1 => -1e4,
+            2 => 88.5,
+            _ => (i as f32) * 100.0,
+        }).collect()
+    }
+
+    #[test]
+    fn fast() {
+        test_softmax(CudnnSoftmaxAlgorithm::Fast, CudnnSoftmaxMode::Instance, &moderate_input());
+        test_softmax(CudnnSoftmaxAlgorithm::Fast, CudnnSoftmaxMode::Channel, &moderate_input());
+    }
+
+    #[test]
+    fn accurate() {
+        test_softmax(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Instance, &moderate_input());
+        test_softmax(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Channel, &moderate_input());
+    }
+
+    #[test]
+    fn log() {
+        test_softmax(CudnnSoftmaxAlgorithm::Log, CudnnSoftmaxMode::Instance, &moderate_input());
+        test_softmax(CudnnSoftmaxAlgorithm::Log, CudnnSoftmaxMode::Channel, &moderate_input());
+    }
+
+    #[test]
+    fn accurate_extreme() {
+        test_softmax(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Instance, &extreme_input());
+        test_softmax(CudnnSoftmaxAlgorithm::Accurate, CudnnSoftmaxMode::Channel, &extreme_input());
+    }
+
+    #[test]
+    fn log_extreme() {
+        test_softmax(CudnnSoftmaxAlgorithm::Log, CudnnSoftmaxMode::Instance, &extreme_input());
+        test_softmax(CudnnSoftmaxAlgorithm::Log, CudnnSoftmaxMode::Channel, &extreme_input());
+    }
+
+}
@@ -0,0 +1,57 @@
+
+use ffi::CudnnError;
+
+
+
+/// Checks that a tensor of dimensions `src` can be broadcast to a tensor of dimensions `dst`,
+/// as cuDNN does it: same number of dimensions, and each dimension of `src` is either 1 or equal to the one of `dst`.
+#[cfg_attr(feature = "disable_checks", allow(dead_code))]
+pub(crate) fn check_broadcast(function: &'static str, name: &str, src: &[i32], dst: &[i32]) -> Result<(), CudnnError> {
+    let valid = src.len() == dst.len() && src.iter().zip(dst.iter()).all(|(&s, &d)| s == 1 || s == d);
+    if valid {
+        Ok(())
+    } else {
+        Err(CudnnError::bad_param(function, format!("{} dimensions {:?} can't be broadcast to {:?}", name, src, dst)))
+    }
+}
+
+/// Checks that the tensor `name`, of dimensions `dims`, has the `expected` dimensions.
+#[cfg_attr(feature = "disable_checks", allow(dead_code))]
+pub(crate) fn check_same_dims(function: &'static str, name: &str, dims: &[i32], expected: &[i32]) -> Result<(), CudnnError> {
+    if dims == expected {
+        Ok(())
+    } else {
+        Err(CudnnError::bad_param(function, format!("{} dimensions {:?} don't match {:?}", name, dims, expected)))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn broadcast() {
+        assert!(check_broadcast("test", "b", &[2, 3, 4, 5], &[2, 3, 4, 5]).is_ok());
+        assert!(check_broadcast("test", "b", &[1, 3, 1, 1], &[2, 3, 4, 5]).is_ok());
+        assert!(check_broadcast("test", "b", &[1, 1, 1, 1], &[2, 3, 4, 5]).is_ok());
+        assert!(check_broadcast("test", "b", &[2, 1, 4, 1], &[2, 3, 4, 5]).is_ok());
+
+        assert!(check_broadcast("test", "b", &[1, 2, 1, 1], &[2, 3, 4, 5]).is_err());
+        assert!(check_broadcast("test", "b", &[1, 3, 1], &[2, 3, 4, 5]).is_err());
+        let error = check_broadcast("test", "b", &[2, 3, 4, 5], &[1, 3, 1, 1]).unwrap_err();
+        assert_eq!(error.function(), "test");
+        assert_eq!(error.details(), Some("b dimensions [2, 3, 4, 5] can't be broadcast to [1, 3, 1, 1]"));
+    }
+
+    #[test]
+    fn same_dims() {
+        assert!(check_same_dims("test", "z", &[2, 3, 4, 5], &[2, 3, 4, 5]).is_ok());
+        assert!(check_same_dims("test", "z", &[2, 3, 4], &[2, 3, 4, 5]).is_err());
+        let error = check_same_dims("test", "z", &[1, 3, 4, 5], &[2, 3, 4, 5]).unwrap_err();
+        assert_eq!(error.details(), Some("z dimensions [1, 3, 4, 5] don't match [2, 3, 4, 5]"));
+    }
+
+}
@@ -2,12 +2,21 @@
 
 mod tensor;
 mod tensor_array;
+mod tensor_buf;
 mod tensor_descriptor;
 mod tensor_descriptor_array;
+mod tensor_view;
+mod transform;
+mod broadcast;
 
 pub use self::tensor::*;
 pub use self::tensor_array::*;
+pub use self::tensor_buf::*;
 pub use self::tensor_descriptor::*;
 pub use self::tensor_descriptor_array::*;
+pub use self::tensor_view::*;
+pub use self::transform::*;
+#[cfg_attr(feature = "disable_checks", allow(unused_imports))]
+pub(crate) use self::broadcast::*;
 
 
@@ -1,58 +1,227 @@
 
 use ffi::*;
 use cudnn::*;
+use cudnn_type::CudnnType;
 use super::{CuTensorDescriptor};
+#[cfg_attr(feature = "disable_checks", allow(unused_imports))]
+use super::check_broadcast;
+use std::any::Any;
+use std::borrow::Cow;
 use std::ops::{Deref, DerefMut};
 use std::os::raw::c_void;
-use cumath::{CuDataType, CuVector};
+use cumath::{CuDataType, CuVectorDeref};
 
 
 
-pub struct CuTensorDeref<'a, T: CuDataType + 'a> {
-    pub(crate) descriptor: &'a CuTensorDescriptor<T>,
+/// A tensor, given to the operations. It either borrows its descriptor and data, when made by
+/// `CuTensorDescriptor::link`, or owns them, as inside a `CuTensorBuf`.
+pub struct CuTensorDeref<'a, T: CudnnType + 'a> {
+    pub(crate) descriptor: Cow<'a, CuTensorDescriptor<T>>,
     pub(crate) data: *mut T,
+    /// Keeps the memory behind `data` alive when the tensor owns it, so that it moves with the tensor
+    pub(crate) owner: Option<Box<dyn Any>>,
 }
 
-impl<'a, T: CuDataType + 'a> CuTensorDeref<'a, T> {
+impl<'a, T: CudnnType + 'a> CuTensorDeref<'a, T> {
+
+    /// A tensor borrowing `descriptor`, for the `data` the caller borrows
+    pub(crate) fn new(descriptor: &'a CuTensorDescriptor<T>, data: *mut T) -> CuTensorDeref<'a, T> {
+        CuTensorDeref { descriptor: Cow::Borrowed(descriptor), data, owner: None }
+    }
 
     pub fn descriptor(&self) -> &CuTensorDescriptor<T> {
-        self.descriptor
+        &self.descriptor
+    }
+
+    pub fn init(&mut self, cudnn: &Cudnn, value: T) -> Result<(), CudnnError> {
+        cudnn_set_tensor(cudnn.handle, self.descriptor.data, self.data as *mut c_void, &value as *const T as *const c_void)
+    }
+
+    /// Computes `self = alpha * a + beta * self`.
+    /// `a` may have dimensions of size 1 which are broadcast, e.g. a 1xCx1x1 bias.
+    pub fn add(&mut self, cudnn: &Cudnn, alpha: T::Scaling, a: &CuTensorDeref<T>, beta: T::Scaling) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            check_broadcast("CuTensorDeref::add", "a", &a.descriptor.get_info()?.dimensions, &self.descriptor.get_info()?.dimensions)?;
+        }
+        cudnn_add_tensor(cudnn.handle,
+                         &alpha as *const T::Scaling as *const c_void, a.descriptor.data, a.data as *const c_void,
+                         &beta as *const T::Scaling as *const c_void, self.descriptor.data, self.data as *mut c_void)
     }
 
-    pub fn init(&mut self, cudnn: &Cudnn, value: T) {
-        cudnn_set_tensor(cudnn.handle, self.descriptor.data, self.data as *mut c_void, &value as *const T as *const c_void);
+    /// Multiplies every element by `alpha`
+    pub fn scale(&mut self, cudnn: &Cudnn, alpha: T::Scaling) -> Result<(), CudnnError> {
+        cudnn_scale_tensor(cudnn.handle, self.descriptor.data, self.data as *mut c_void, &alpha as *const T::Scaling as *const c_void)
     }
 
 }
 
 
-pub struct CuTensor<'a, T: CuDataType + 'a> {
+/// A read-only tensor, made by `CuTensorDescriptor::link`.
+///
+/// It can't be written to, neither directly:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let cudnn = Cudnn::new().unwrap();
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let mut tensor = descriptor.link(&data).unwrap();
+/// tensor.init(&cudnn, 0.0).unwrap();
+/// ```
+///
+/// while a tensor made by `link_mut` can:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let cudnn = Cudnn::new().unwrap();
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let mut tensor = descriptor.link_mut(&mut data).unwrap();
+/// tensor.init(&cudnn, 0.0).unwrap();
+/// ```
+///
+/// It can't be the output of an operation either:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let cudnn = Cudnn::new().unwrap();
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let mut tensor = descriptor.link(&data).unwrap();
+/// CuActivationDescriptor::relu().unwrap().forward_inplace(&cudnn, &mut tensor, 1.0, 0.0).unwrap();
+/// ```
+///
+/// but it can be the input of one:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let cudnn = Cudnn::new().unwrap();
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let mut output = CuVector::<f32>::zero(descriptor.data_len());
+/// let tensor = descriptor.link(&data).unwrap();
+/// CuActivationDescriptor::relu().unwrap().forward(&cudnn, &tensor, 1.0, &mut descriptor.link_mut(&mut output).unwrap(), 0.0).unwrap();
+/// ```
+pub struct CuTensor<'a, T: CudnnType + 'a> {
     pub(crate) deref: CuTensorDeref<'a, T>,
 }
-impl<'a, T: CuDataType + 'a> Deref for CuTensor<'a, T> {
+impl<'a, T: CudnnType + 'a> Deref for CuTensor<'a, T> {
     type Target = CuTensorDeref<'a, T>;
     fn deref(&self) -> &CuTensorDeref<'a, T> { &self.deref }
 }
-impl<'a, T: CuDataType + 'a> CuTensor<'a, T> {
+impl<'a, T: CudnnType + CuDataType + 'a> CuTensor<'a, T> {
 
-    pub fn new(descriptor: &'a CuTensorDescriptor<T>, data: &'a CuVector<T>) -> CuTensor<'a, T> {
-        CuTensor {
-            deref: CuTensorDeref {
-                data: data.as_ptr() as *mut T,
-                descriptor,
-            }
-        }
+    /// Same as `descriptor.link(data)`
+    pub fn new(descriptor: &'a CuTensorDescriptor<T>, data: &'a CuVectorDeref<T>) -> Result<CuTensor<'a, T>, CudnnError> {
+        descriptor.link(data)
     }
 
 }
 
-pub struct CuTensorMut<'a, T: CuDataType + 'a> {
+/// A mutable tensor, made by `CuTensorDescriptor::link_mut`.
+///
+/// It borrows its data mutably, so the data can't be linked twice:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let input = descriptor.link(&data).unwrap();
+/// let output = descriptor.link_mut(&mut data).unwrap();
+/// drop((input, output));
+/// ```
+///
+/// Once the first link is dropped, the data can be linked again:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap();
+/// let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let input = descriptor.link(&data).unwrap();
+/// drop(input);
+/// let output = descriptor.link_mut(&mut data).unwrap();
+/// drop(output);
+/// ```
+pub struct CuTensorMut<'a, T: CudnnType + 'a> {
     pub(crate) deref: CuTensorDeref<'a, T>,
 }
-impl<'a, T: CuDataType + 'a> Deref for CuTensorMut<'a, T> {
+impl<'a, T: CudnnType + 'a> Deref for CuTensorMut<'a, T> {
     type Target = CuTensorDeref<'a, T>;
     fn deref(&self) -> &CuTensorDeref<'a, T> { &self.deref }
 }
-impl<'a, T: CuDataType + 'a> DerefMut for CuTensorMut<'a, T> {
+impl<'a, T: CudnnType + 'a> DerefMut for CuTensorMut<'a, T> {
     fn deref_mut(&mut self) -> &mut CuTensorDeref<'a, T> { &mut self.deref }
-}
\ No newline at end of file
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::CuVector;
+
+    #[test]
+    fn add_bias() {
+        let cudnn = Cudnn::new().unwrap();
+        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 2, 2]).unwrap();
+        let bias_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 3, 1, 1]).unwrap();
+        let bias = CuVector::<f32>::from_host_data(&[1.0, 2.0, 3.0]);
+        let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
+
+        descriptor.link_mut(&mut data).unwrap().add(&cudnn, 2.0, &bias_descriptor.link(&bias).unwrap(), 0.5).unwrap();
+        let mut output = vec![0.0; descriptor.data_len()];
+        data.clone_to_host(&mut output);
+        assert_eq!(output, (0..24).map(|i| 2.0 * (i / 4 % 3 + 1) as f32 + 0.5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn add_invalid_broadcast() {
+        let cudnn = Cudnn::new().unwrap();
+        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 2, 2]).unwrap();
+        let a_descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 1, 1]).unwrap();
+        let a = CuVector::<f32>::zero(a_descriptor.data_len());
+        let mut data = CuVector::<f32>::zero(descriptor.data_len());
+
+        let error = descriptor.link_mut(&mut data).unwrap().add(&cudnn, 1.0, &a_descriptor.link(&a).unwrap(), 1.0).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+    #[test]
+    fn scale() {
+        let cudnn = Cudnn::new().unwrap();
+        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 2, 2]).unwrap();
+        let input = (0..24).map(|x| x as f32).collect::<Vec<_>>();
+        let mut data = CuVector::<f32>::from_host_data(&input);
+
+        descriptor.link_mut(&mut data).unwrap().scale(&cudnn, -0.5).unwrap();
+        let mut output = vec![0.0; descriptor.data_len()];
+        data.clone_to_host(&mut output);
+        assert_eq!(output, input.iter().map(|x| -0.5 * x).collect::<Vec<_>>());
+    }
+
+}
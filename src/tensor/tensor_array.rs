@@ -1,30 +1,136 @@
-
-use std::ops::{Deref, DerefMut};
-use cumath::CuDataType;
+use std::ops::Deref;
+use std::marker::PhantomData;
+use cudnn_type::CudnnType;
 use ffi::_TensorDescriptorStruct;
+use super::CuTensorDescriptor;
 
 
-pub struct CuTensorArrayDeref<T: CuDataType> {
+/// A sequence of tensors laid out one after the other in a single vector, as taken by the RNN functions.
+/// It borrows both its descriptors and its data, and only gives read access to the data.
+pub struct CuTensorArrayDeref<'a, T: CudnnType + 'a> {
     pub(crate) descriptors: Box<[*const _TensorDescriptorStruct]>,
-    pub(crate) data: *mut T,
+    pub(crate) data: *const T,
+    pub(crate) _phantom: PhantomData<&'a CuTensorDescriptor<T>>,
 }
 
 
-pub struct CuTensorArray<T: CuDataType> {
-    pub(crate) deref: CuTensorArrayDeref<T>,
+/// A read-only tensor array, made by `CuTensorDescriptorArray::link`.
+///
+/// It can't be given where a mutable array is expected:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// fn write(_array: &mut CuTensorArrayMut<f32>) {}
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// let data = CuVector::<f32>::new(1.0, descriptors.data_len());
+/// let mut array = descriptors.link(&data).unwrap();
+/// write(&mut array);
+/// ```
+///
+/// unlike an array made by `link_mut`:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// fn write(_array: &mut CuTensorArrayMut<f32>) {}
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
+/// let mut array = descriptors.link_mut(&mut data).unwrap();
+/// write(&mut array);
+/// ```
+pub struct CuTensorArray<'a, T: CudnnType + 'a> {
+    pub(crate) deref: CuTensorArrayDeref<'a, T>,
 }
-impl<T: CuDataType> Deref for CuTensorArray<T> {
-    type Target = CuTensorArrayDeref<T>;
-    fn deref(&self) -> &CuTensorArrayDeref<T> { &self.deref }
+impl<'a, T: CudnnType + 'a> Deref for CuTensorArray<'a, T> {
+    type Target = CuTensorArrayDeref<'a, T>;
+    fn deref(&self) -> &CuTensorArrayDeref<'a, T> { &self.deref }
 }
 
-pub struct CuTensorArrayMut<T: CuDataType> {
-    pub(crate) deref: CuTensorArrayDeref<T>,
+/// A mutable tensor array, made by `CuTensorDescriptorArray::link_mut`.
+///
+/// It borrows its data mutably, so the data can't be linked twice:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
+/// let first = descriptors.link_mut(&mut data).unwrap();
+/// let second = descriptors.link_mut(&mut data).unwrap();
+/// drop((first, second));
+/// ```
+///
+/// unless the first link is dropped before:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
+/// let first = descriptors.link_mut(&mut data).unwrap();
+/// drop(first);
+/// let second = descriptors.link_mut(&mut data).unwrap();
+/// drop(second);
+/// ```
+///
+/// and it can't outlive its data:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// let array = {
+///     let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
+///     descriptors.link_mut(&mut data).unwrap()
+/// };
+/// drop(array);
+/// ```
+///
+/// so it must be dropped in the scope of its data:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap()];
+/// {
+///     let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
+///     let array = descriptors.link_mut(&mut data).unwrap();
+///     drop(array);
+/// }
+/// ```
+pub struct CuTensorArrayMut<'a, T: CudnnType + 'a> {
+    pub(crate) deref: CuTensorArrayDeref<'a, T>,
 }
-impl<T: CuDataType> Deref for CuTensorArrayMut<T> {
-    type Target = CuTensorArrayDeref<T>;
-    fn deref(&self) -> &CuTensorArrayDeref<T> { &self.deref }
+impl<'a, T: CudnnType + 'a> Deref for CuTensorArrayMut<'a, T> {
+    type Target = CuTensorArrayDeref<'a, T>;
+    fn deref(&self) -> &CuTensorArrayDeref<'a, T> { &self.deref }
 }
-impl<T: CuDataType> DerefMut for CuTensorArrayMut<T> {
-    fn deref_mut(&mut self) -> &mut CuTensorArrayDeref<T> { &mut self.deref }
+impl<'a, T: CudnnType + 'a> CuTensorArrayMut<'a, T> {
+
+    /// Pointer to the data, which was borrowed mutably by `link_mut`
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut T {
+        self.deref.data as *mut T
+    }
+
 }
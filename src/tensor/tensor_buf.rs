@@ -0,0 +1,165 @@
+
+use std::borrow::Cow;
+use std::ops::{Deref, DerefMut};
+use cumath::{CuDataType, CuVector};
+use cudnn_type::CudnnType;
+use cudnn::Cudnn;
+use ffi::*;
+use super::*;
+
+
+
+/// A tensor owning both its descriptor and its device memory.
+///
+/// It derefs into a `CuTensorDeref`, so it can be given to any operation, as an input or as an output.
+/// The inner `CuTensorDeref` owns the descriptor and the memory, so it stays valid even if it's swapped
+/// out of the buffer.
+pub struct CuTensorBuf<T: CudnnType + CuDataType> {
+    deref: CuTensorDeref<'static, T>,
+}
+
+impl<T: CudnnType + CuDataType> Deref for CuTensorBuf<T> {
+    type Target = CuTensorDeref<'static, T>;
+    fn deref(&self) -> &CuTensorDeref<'static, T> { &self.deref }
+}
+
+impl<T: CudnnType + CuDataType> DerefMut for CuTensorBuf<T> {
+    fn deref_mut(&mut self) -> &mut CuTensorDeref<'static, T> { &mut self.deref }
+}
+
+impl<T: CudnnType + CuDataType> CuTensorBuf<T> {
+
+    fn from_parts(descriptor: CuTensorDescriptor<T>, mut data: CuVector<T>) -> CuTensorBuf<T> {
+        let pointer = data.as_mut_ptr();
+        CuTensorBuf { deref: CuTensorDeref { descriptor: Cow::Owned(descriptor), data: pointer, owner: Some(Box::new(data)) } }
+    }
+
+    /// A tensor described by `descriptor` with every element set to `value`
+    pub fn filled(descriptor: CuTensorDescriptor<T>, value: T) -> CuTensorBuf<T> {
+        let data = CuVector::<T>::new(value, descriptor.data_len());
+        Self::from_parts(descriptor, data)
+    }
+
+    /// A tensor described by `descriptor`, initialized with the host `data`
+    pub fn from_host(descriptor: CuTensorDescriptor<T>, data: &[T]) -> Result<CuTensorBuf<T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != descriptor.data_len() {
+                return Err(CudnnError::bad_param("CuTensorBuf::from_host", "data.len() != descriptor.data_len()"))
+            }
+        }
+        Ok(Self::from_parts(descriptor, CuVector::<T>::from_host_data(data)))
+    }
+
+    /// The memory of the tensor. Panics if the inner tensor was swapped with one which doesn't own its memory.
+    pub fn data(&self) -> &CuVector<T> {
+        self.deref.owner.as_ref().and_then(|owner| owner.downcast_ref::<CuVector<T>>())
+            .expect("CuTensorBuf::data: the tensor doesn't own its memory")
+    }
+
+}
+
+impl<T: CudnnType + CuDataType + Default> CuTensorBuf<T> {
+
+    /// A tensor described by `descriptor` with every element set to zero
+    pub fn zeros(descriptor: CuTensorDescriptor<T>) -> CuTensorBuf<T> {
+        Self::filled(descriptor, T::default())
+    }
+
+    pub fn to_host(&self) -> Vec<T> {
+        let mut output = vec![T::default(); self.descriptor().data_len()];
+        self.data().clone_to_host(&mut output);
+        output
+    }
+
+    /// A copy of this 4d tensor with its elements laid out in `format`
+    pub fn to_format(&self, cudnn: &Cudnn, format: CudnnTensorFormat) -> Result<CuTensorBuf<T>, CudnnError> {
+        let dimensions = self.descriptor().get_info()?.dimensions;
+        #[cfg(not(feature = "disable_checks"))] {
+            if dimensions.len() != 4 {
+                return Err(CudnnError::bad_param("CuTensorBuf::to_format", format!("only 4d tensors have a format, dimensions are {:?}", dimensions)))
+            }
+        }
+        let descriptor = CuTensorDescriptor::<T>::new_4d(format, dimensions[0], dimensions[1], dimensions[2], dimensions[3])?;
+        let mut output = Self::zeros(descriptor);
+        transform(cudnn, 1.0.into(), self, 0.0.into(), &mut output)?;
+        Ok(output)
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cudnn::Cudnn;
+    use activation_descriptor::CuActivationDescriptor;
+
+    #[test]
+    fn constructors() {
+        let zeros = CuTensorBuf::zeros(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4]).unwrap());
+        assert_eq!(zeros.to_host(), vec![0.0; 24]);
+        assert_eq!(zeros.descriptor().data_len(), 24);
+
+        let filled = CuTensorBuf::filled(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4]).unwrap(), 1.5);
+        assert_eq!(filled.to_host(), vec![1.5; 24]);
+
+        let data = (0..24).map(|x| x as f32).collect::<Vec<_>>();
+        let tensor = CuTensorBuf::from_host(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4]).unwrap(), &data).unwrap();
+        assert_eq!(tensor.to_host(), data);
+        assert_eq!(tensor.data().len(), 24);
+
+        #[cfg(not(feature = "disable_checks"))] {
+            let error = CuTensorBuf::from_host(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 5]).unwrap(), &data).err().unwrap();
+            assert_eq!(error.status(), CudnnStatus::BadParam);
+        }
+    }
+
+    #[test]
+    fn operations() {
+        let cudnn = Cudnn::new().unwrap();
+        let activation = CuActivationDescriptor::relu().unwrap();
+        let data = (0..24).map(|x| x as f32 - 12.0).collect::<Vec<_>>();
+        let input = CuTensorBuf::from_host(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4]).unwrap(), &data).unwrap();
+        let mut output = CuTensorBuf::zeros(input.descriptor().clone());
+
+        activation.forward(&cudnn, &input, 1.0, &mut output, 0.0).unwrap();
+        assert_eq!(output.to_host(), data.iter().map(|x| x.max(0.0)).collect::<Vec<_>>());
+
+        // The buffer can be moved around without invalidating its descriptor
+        let moved = vec![output];
+        let mut doubled = CuTensorBuf::zeros(moved[0].descriptor().clone());
+        activation.forward(&cudnn, &moved[0], 2.0, &mut doubled, 0.0).unwrap();
+        assert_eq!(doubled.to_host(), data.iter().map(|x| 2.0 * x.max(0.0)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn swap() {
+        // The inner tensors own their descriptor and memory, so they stay valid once swapped
+        let mut first = CuTensorBuf::filled(CuTensorDescriptor::<f32>::fully_packed(&[1, 2, 3]).unwrap(), 1.0);
+        let mut second = CuTensorBuf::filled(CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 2]).unwrap(), 2.0);
+        ::std::mem::swap(&mut *first, &mut *second);
+        drop(second);
+        assert_eq!(first.descriptor().data_len(), 2);
+        assert_eq!(first.to_host(), vec![2.0; 2]);
+    }
+
+    #[test]
+    fn to_format() {
+        let cudnn = Cudnn::new().unwrap();
+        let data = (0..24).map(|x| x as f32).collect::<Vec<_>>();
+        let nchw = CuTensorBuf::from_host(CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nchw, 1, 2, 3, 4).unwrap(), &data).unwrap();
+
+        let nhwc = nchw.to_format(&cudnn, CudnnTensorFormat::Nhwc).unwrap();
+        assert_eq!(nhwc.descriptor().get_info().unwrap().strides, vec![24, 1, 8, 2]);
+        assert_eq!(nhwc.to_host(), (0..24).map(|i| ((i % 2) * 12 + i / 2) as f32).collect::<Vec<_>>());
+        assert_eq!(nhwc.to_format(&cudnn, CudnnTensorFormat::Nchw).unwrap().to_host(), data);
+
+        #[cfg(not(feature = "disable_checks"))] {
+            let tensor = CuTensorBuf::from_host(CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4]).unwrap(), &data).unwrap();
+            assert_eq!(tensor.to_format(&cudnn, CudnnTensorFormat::Nhwc).err().unwrap().status(), CudnnStatus::BadParam);
+        }
+    }
+
+}
@@ -1,6 +1,7 @@
 
 use std::{ptr, marker::PhantomData, fmt::{self, Debug}, mem::size_of};
 use cumath::{CuDataType, CuVectorDeref};
+use cudnn_type::CudnnType;
 use ffi::*;
 use super::*;
 
@@ -8,141 +9,138 @@ use super::*;
 
 // Descriptor
 
-pub struct CuTensorDescriptor<T: CuDataType> {
+pub struct CuTensorDescriptor<T: CudnnType> {
     _phantom: PhantomData<T>,
     pub(crate) data: *mut _TensorDescriptorStruct,
     nb_dims: i32,
     data_len: usize,
 }
 
-impl<T: CuDataType> Drop for CuTensorDescriptor<T> {
+impl<T: CudnnType> Drop for CuTensorDescriptor<T> {
     fn drop(&mut self) {
-        cudnn_destroy_tensor_descriptor(self.data);
+        let _ = cudnn_destroy_tensor_descriptor(self.data);
     }
 }
 
-impl<T: CuDataType> Debug for CuTensorDescriptor<T> {
+impl<T: CudnnType> Debug for CuTensorDescriptor<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "CuTensorDescriptor {{ nb_dims:{}, data_len:{} }}", self.nb_dims, self.data_len)
     }
 }
 
-impl<T: CuDataType> Clone for CuTensorDescriptor<T> {
+impl<T: CudnnType> Clone for CuTensorDescriptor<T> {
     fn clone(&self) -> CuTensorDescriptor<T> {
-        let info = self.get_info();
-        let mut data = ptr::null_mut();
-        cudnn_create_tensor_descriptor(&mut data);
-        cudnn_set_tensor_nd_descriptor(data, info.data_type, info.nb_dims, info.dimensions.as_ptr(), info.strides.as_ptr());
-        CuTensorDescriptor {
-            _phantom: PhantomData,
-            data,
-            nb_dims: self.nb_dims,
-            data_len: self.data_len,
-        }
+        self.try_clone().expect("Failed to clone CuTensorDescriptor")
     }
     fn clone_from(&mut self, other: &CuTensorDescriptor<T>) {
-        let info = other.get_info();
-        cudnn_set_tensor_nd_descriptor(self.data, info.data_type, info.nb_dims, info.dimensions.as_ptr(), info.strides.as_ptr());
+        let info = other.get_info().expect("Failed to clone CuTensorDescriptor");
+        cudnn_set_tensor_nd_descriptor(self.data, info.data_type, info.nb_dims, info.dimensions.as_ptr(), info.strides.as_ptr())
+            .expect("Failed to clone CuTensorDescriptor");
+        self.nb_dims = other.nb_dims;
         self.data_len = other.data_len;
     }
 }
 
-impl<T: CuDataType> CuTensorDescriptor<T> {
+impl<T: CudnnType> CuTensorDescriptor<T> {
 
-    pub fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> CuTensor<'a, T> {
-        #[cfg(not(feature = "disable_checks"))] {
-            assert_eq!(data.len(), self.data_len(), "data.len() != self.data_len()");
-        }
-        CuTensor { deref: CuTensorDeref { descriptor: self, data: data.as_ptr() as *mut T } }
+    pub(crate) fn create(nb_dims: i32) -> Result<CuTensorDescriptor<T>, CudnnError> {
+        let mut data = ptr::null_mut();
+        cudnn_create_tensor_descriptor(&mut data)?;
+        Ok(CuTensorDescriptor { _phantom: PhantomData, data, nb_dims, data_len: 0 })
     }
 
-    pub fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> CuTensorMut<'a, T> {
-        #[cfg(not(feature = "disable_checks"))] {
-            assert_eq!(data.len(), self.data_len(), "data.len() != self.data_len()");
-        }
-        CuTensorMut { deref: CuTensorDeref { descriptor: self, data: data.as_mut_ptr() } }
+    pub(crate) fn update_data_len(&mut self) -> Result<(), CudnnError> {
+        let mut data_len = 0;
+        cudnn_get_tensor_size_in_bytes(self.data, &mut data_len)?;
+        self.data_len = data_len / size_of::<T>();
+        Ok(())
+    }
+
+    pub fn try_clone(&self) -> Result<CuTensorDescriptor<T>, CudnnError> {
+        let info = self.get_info()?;
+        let mut output = Self::create(self.nb_dims)?;
+        cudnn_set_tensor_nd_descriptor(output.data, info.data_type, info.nb_dims, info.dimensions.as_ptr(), info.strides.as_ptr())?;
+        output.data_len = self.data_len;
+        Ok(output)
     }
 
     pub fn data_len(&self) -> usize {
         self.data_len
     }
 
-    pub fn get_info(&self) -> CuTensorDescriptorInfo {
+    pub fn get_info(&self) -> Result<CuTensorDescriptorInfo, CudnnError> {
         let mut data_type = CudnnDataType::Int8x4;
         let mut nb_dims = -1;
         let mut dimensions = vec![-1; self.nb_dims as usize];
         let mut strides = vec![-1; self.nb_dims as usize];
         cudnn_get_tensor_nd_descriptor(self.data, self.nb_dims,
-                                       &mut data_type, &mut nb_dims, dimensions.as_mut_ptr(), strides.as_mut_ptr());
-        CuTensorDescriptorInfo { data_type, nb_dims, dimensions, strides }
+                                       &mut data_type, &mut nb_dims, dimensions.as_mut_ptr(), strides.as_mut_ptr())?;
+        Ok(CuTensorDescriptorInfo { data_type, nb_dims, dimensions, strides })
     }
 
-}
-
-impl CuTensorDescriptor<f32> {
-
-    pub fn new(dimensions: &[i32], strides: &[i32]) -> CuTensorDescriptor<f32> {
+    pub fn new(dimensions: &[i32], strides: &[i32]) -> Result<CuTensorDescriptor<T>, CudnnError> {
         #[cfg(not(feature = "disable_checks"))] {
-            if dimensions.len() < 3 { panic!("dimensions.len() must be >= 3") }
-            assert_eq!(dimensions.len(), strides.len())
-        }
-        let mut data = ptr::null_mut();
-        cudnn_create_tensor_descriptor(&mut data);
-        cudnn_set_tensor_nd_descriptor(data, CudnnDataType::Float, dimensions.len() as i32, dimensions.as_ptr(), strides.as_ptr());
-        let mut data_len = 0;
-        cudnn_get_tensor_size_in_bytes(data, &mut data_len);
-        data_len /=  size_of::<f32>();
-
-        CuTensorDescriptor {
-            _phantom: PhantomData,
-            data,
-            nb_dims: dimensions.len() as i32,
-            data_len,
+            if dimensions.len() < 3 {
+                return Err(CudnnError::bad_param("CuTensorDescriptor::new", "dimensions.len() must be >= 3"))
+            }
+            if dimensions.len() != strides.len() {
+                return Err(CudnnError::bad_param("CuTensorDescriptor::new", "dimensions.len() != strides.len()"))
+            }
         }
+        let mut output = Self::create(dimensions.len() as i32)?;
+        cudnn_set_tensor_nd_descriptor(output.data, T::data_type(), dimensions.len() as i32, dimensions.as_ptr(), strides.as_ptr())?;
+        output.update_data_len()?;
+        Ok(output)
     }
 
-    pub fn fully_packed(dimensions: &[i32]) -> CuTensorDescriptor<f32> {
+    pub fn fully_packed(dimensions: &[i32]) -> Result<CuTensorDescriptor<T>, CudnnError> {
         #[cfg(not(feature = "disable_checks"))] {
-            if dimensions.len() < 3 { panic!("dimensions.len() must be >= 3") }
+            if dimensions.len() < 3 {
+                return Err(CudnnError::bad_param("CuTensorDescriptor::fully_packed", "dimensions.len() must be >= 3"))
+            }
         }
         let strides = get_fully_packed_strides(&dimensions);
-        let mut data = ptr::null_mut();
-        cudnn_create_tensor_descriptor(&mut data);
-        cudnn_set_tensor_nd_descriptor(data, CudnnDataType::Float, dimensions.len() as i32, dimensions.as_ptr(), strides.as_ptr());
-        let mut data_len = 0;
-        cudnn_get_tensor_size_in_bytes(data, &mut data_len);
-        data_len /=  size_of::<f32>();
-
-        CuTensorDescriptor {
-            _phantom: PhantomData,
-            data,
-            nb_dims: dimensions.len() as i32,
-            data_len,
-        }
+        let mut output = Self::create(dimensions.len() as i32)?;
+        cudnn_set_tensor_nd_descriptor(output.data, T::data_type(), dimensions.len() as i32, dimensions.as_ptr(), strides.as_ptr())?;
+        output.update_data_len()?;
+        Ok(output)
     }
 
     // Nhcw => strides = [w*h*c, 1, w*h, h]
     // Nchw => strides = [w*h*c, w*h, w, 1]
-    pub fn new_4d(format: CudnnTensorFormat, n: i32, c: i32, h: i32, w: i32) -> CuTensorDescriptor<f32> {
-        let mut data = ptr::null_mut();
-        cudnn_create_tensor_descriptor(&mut data);
-        cudnn_set_tensor4d_descriptor(data, format, CudnnDataType::Float, n, c, h, w);
-        let mut data_len = 0;
-        cudnn_get_tensor_size_in_bytes(data, &mut data_len);
-        data_len /=  size_of::<f32>();
-
-        CuTensorDescriptor {
-            _phantom: PhantomData,
-            nb_dims: 4,
-            data_len,
-            data,
+    pub fn new_4d(format: CudnnTensorFormat, n: i32, c: i32, h: i32, w: i32) -> Result<CuTensorDescriptor<T>, CudnnError> {
+        let mut output = Self::create(4)?;
+        cudnn_set_tensor4d_descriptor(output.data, format, T::data_type(), n, c, h, w)?;
+        output.update_data_len()?;
+        Ok(output)
+    }
+
+}
+
+impl<T: CudnnType + CuDataType> CuTensorDescriptor<T> {
+
+    pub fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> Result<CuTensor<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuTensorDescriptor::link", "data.len() != self.data_len()"))
+            }
         }
+        Ok(CuTensor { deref: CuTensorDeref::new(self, data.as_ptr() as *mut T) })
+    }
+
+    pub fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> Result<CuTensorMut<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuTensorDescriptor::link_mut", "data.len() != self.data_len()"))
+            }
+        }
+        Ok(CuTensorMut { deref: CuTensorDeref::new(self, data.as_mut_ptr()) })
     }
 
 }
 
 
-fn get_fully_packed_strides(dims: &[i32]) -> Vec<i32> {
+pub(crate) fn get_fully_packed_strides(dims: &[i32]) -> Vec<i32> {
     use std::collections::VecDeque;
     let mut output = VecDeque::with_capacity(dims.len());
     output.push_front(1);
@@ -157,6 +155,7 @@ fn get_fully_packed_strides(dims: &[i32]) -> Vec<i32> {
 
 // Descriptor Info
 
+#[derive(PartialEq, Eq, Hash, Clone)]
 pub struct CuTensorDescriptorInfo {
     pub data_type: CudnnDataType,
     pub nb_dims: i32,
@@ -177,14 +176,15 @@ impl Debug for CuTensorDescriptorInfo {
 mod tests {
 
     use super::*;
+    use cudnn_type::Half;
     use cumath::CuVector;
 
-    fn assert_validity(descriptor: &CuTensorDescriptor<f32>) {
+    fn assert_validity<T: CudnnType>(descriptor: &CuTensorDescriptor<T>) {
         println!("Asserting Tensor descriptor {:?}", descriptor);
 
-        let info = descriptor.get_info();
+        let info = descriptor.get_info().unwrap();
         println!("    Info = {:?}", info);
-        assert_eq!(info.data_type, CudnnDataType::Float);
+        assert_eq!(info.data_type, T::data_type());
         assert_eq!(info.nb_dims, descriptor.nb_dims);
         assert_eq!(info.nb_dims, info.dimensions.len() as i32);
         assert_eq!(info.nb_dims, info.strides.len() as i32);
@@ -192,21 +192,43 @@ mod tests {
 
     #[test]
     fn init() {
-        assert_validity(&CuTensorDescriptor::<f32>::fully_packed(&[7, 1, 5, 3]));
-        assert_validity(&CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nhwc, 7, 2, 5, 3));
-        assert_validity(&CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nchw, 7, 1, 5, 3));
+        assert_validity(&CuTensorDescriptor::<f32>::fully_packed(&[7, 1, 5, 3]).unwrap());
+        assert_validity(&CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nhwc, 7, 2, 5, 3).unwrap());
+        assert_validity(&CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nchw, 7, 1, 5, 3).unwrap());
+    }
+
+    #[test]
+    fn init_data_types() {
+        assert_validity(&CuTensorDescriptor::<f64>::fully_packed(&[7, 1, 5, 3]).unwrap());
+        assert_validity(&CuTensorDescriptor::<Half>::new(&[7, 1, 5, 3], &[15, 15, 3, 1]).unwrap());
+        assert_validity(&CuTensorDescriptor::<i8>::new_4d(CudnnTensorFormat::Nhwc, 7, 4, 5, 3).unwrap());
+        assert_validity(&CuTensorDescriptor::<u8>::new_4d(CudnnTensorFormat::Nchw, 7, 1, 5, 3).unwrap());
+        assert_eq!(CuTensorDescriptor::<f64>::fully_packed(&[7, 1, 5, 3]).unwrap().data_len(), 105);
+        assert_eq!(CuTensorDescriptor::<Half>::fully_packed(&[7, 1, 5, 3]).unwrap().data_len(), 105);
+    }
+
+    #[test]
+    fn init_invalid() {
+        let error = CuTensorDescriptor::<f32>::fully_packed(&[7, 1]).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+        assert_eq!(error.function(), "CuTensorDescriptor::fully_packed");
+        let error = CuTensorDescriptor::<f32>::new(&[7, 1, 5, 3], &[15, 15, 3]).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
     }
 
     #[test]
     fn link() {
 
-        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 10]);
+        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 10]).unwrap();
         let mut data = CuVector::<f32>::new(1.0, descriptor.data_len());
 
         {
-            let _tensor = descriptor.link(&data);
+            let _tensor = descriptor.link(&data).unwrap();
         }
-        let _tensor = descriptor.link_mut(&mut data);
+        let _tensor = descriptor.link_mut(&mut data).unwrap();
+
+        let wrong_data = CuVector::<f32>::new(1.0, descriptor.data_len() + 1);
+        assert!(descriptor.link(&wrong_data).is_err());
 
     }
 
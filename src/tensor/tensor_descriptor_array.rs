@@ -1,5 +1,7 @@
 
+use std::marker::PhantomData;
 use cumath::{CuDataType, CuVectorDeref};
+use cudnn_type::CudnnType;
 use ffi::*;
 use super::*;
 
@@ -9,35 +11,47 @@ use super::*;
 
 // Descriptor array
 
-pub trait CuTensorDescriptorArray<T: CuDataType> {
+pub trait CuTensorDescriptorArray<T: CudnnType + CuDataType> {
     fn data_len(&self) -> usize;
-    fn link(&self, data: &CuVectorDeref<T>) -> CuTensorArray<T>;
-    fn link_mut(&self, data: &mut CuVectorDeref<T>) -> CuTensorArrayMut<T>;
+    fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> Result<CuTensorArray<'a, T>, CudnnError>;
+    fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> Result<CuTensorArrayMut<'a, T>, CudnnError>;
 }
 
 // Impl for [CuTensorDescriptor]
 
-impl<T: CuDataType> CuTensorDescriptorArray<T> for [CuTensorDescriptor<T>] {
+impl<T: CudnnType + CuDataType> CuTensorDescriptorArray<T> for [CuTensorDescriptor<T>] {
     fn data_len(&self) -> usize {
         self.iter().fold(0, |acc, x| acc + x.data_len())
     }
 
-    fn link(&self, data: &CuVectorDeref<T>) -> CuTensorArray<T> {
-        CuTensorArray {
+    fn link<'a>(&'a self, data: &'a CuVectorDeref<T>) -> Result<CuTensorArray<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuTensorDescriptorArray::link", "data.len() != self.data_len()"))
+            }
+        }
+        Ok(CuTensorArray {
             deref: CuTensorArrayDeref {
                 descriptors: self.iter().map(|x| x.data as *const _TensorDescriptorStruct).collect::<Vec<_>>().into_boxed_slice(),
-                data: data.as_ptr() as *mut T,
+                data: data.as_ptr(),
+                _phantom: PhantomData,
             }
-        }
+        })
     }
 
-    fn link_mut(&self, data: &mut CuVectorDeref<T>) -> CuTensorArrayMut<T> {
-        CuTensorArrayMut {
+    fn link_mut<'a>(&'a self, data: &'a mut CuVectorDeref<T>) -> Result<CuTensorArrayMut<'a, T>, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if data.len() != self.data_len() {
+                return Err(CudnnError::bad_param("CuTensorDescriptorArray::link_mut", "data.len() != self.data_len()"))
+            }
+        }
+        Ok(CuTensorArrayMut {
             deref: CuTensorArrayDeref {
                 descriptors: self.iter().map(|x| x.data as *const _TensorDescriptorStruct).collect::<Vec<_>>().into_boxed_slice(),
-                data: data.as_ptr() as *mut T,
+                data: data.as_mut_ptr() as *const T,
+                _phantom: PhantomData,
             }
-        }
+        })
     }
 }
 
@@ -52,13 +66,13 @@ mod tests {
     #[test]
     fn link_array() {
 
-        let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 10]), CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 20])];
+        let descriptors = [CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 10]).unwrap(), CuTensorDescriptor::<f32>::fully_packed(&[1, 1, 4, 20]).unwrap()];
         let mut data = CuVector::<f32>::new(1.0, descriptors.data_len());
 
         {
-            let _tensor = &descriptors.link(&data);
+            let _tensor = &descriptors.link(&data).unwrap();
         }
-        let _tensor = &descriptors.link_mut(&mut data);
+        let _tensor = &descriptors.link_mut(&mut data).unwrap();
 
     }
 
@@ -0,0 +1,336 @@
+
+use std::marker::PhantomData;
+use cudnn_type::CudnnType;
+use ffi::*;
+use super::*;
+use super::tensor_descriptor::get_fully_packed_strides;
+
+
+
+/// A read-only view on the data of another tensor, with its own descriptor.
+/// It is given to the operations through `tensor`, and a `CuTensorViewMut` through `tensor_mut`.
+///
+/// A view borrows the tensor it was made from, so it can only be used while that tensor lives:
+///
+/// ```
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let cudnn = Cudnn::new().unwrap();
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 5]).unwrap();
+/// let data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let tensor = descriptor.link(&data).unwrap();
+/// let view = tensor.narrow(0, 1, 2).unwrap();
+/// let mut output = CuTensorBuf::zeros(view.descriptor().clone());
+/// CuActivationDescriptor::relu().unwrap().forward(&cudnn, &view.tensor(), 1.0, &mut output, 0.0).unwrap();
+/// ```
+///
+/// It can't outlive that tensor:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 5]).unwrap();
+/// let data = CuVector::<f32>::new(1.0, descriptor.data_len());
+/// let view = {
+///     let tensor = descriptor.link(&data).unwrap();
+///     tensor.narrow(0, 1, 2).unwrap()
+/// };
+/// drop(view);
+/// ```
+///
+/// nor the buffer it was taken from:
+///
+/// ```compile_fail
+/// extern crate cumath;
+/// extern crate cumath_nn;
+/// use cumath::CuVector;
+/// use cumath_nn::*;
+///
+/// let view = {
+///     let buffer = CuTensorBuf::<f32>::zeros(CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 5]).unwrap());
+///     buffer.narrow(0, 1, 2).unwrap()
+/// };
+/// drop(view);
+/// ```
+pub struct CuTensorView<'a, T: CudnnType + 'a> {
+    descriptor: CuTensorDescriptor<T>,
+    data: *mut T,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T: CudnnType + 'a> CuTensorView<'a, T> {
+
+    pub fn descriptor(&self) -> &CuTensorDescriptor<T> {
+        &self.descriptor
+    }
+
+    pub fn tensor<'b>(&'b self) -> CuTensor<'b, T> {
+        CuTensor { deref: CuTensorDeref::new(&self.descriptor, self.data) }
+    }
+
+}
+
+
+/// A mutable view on the data of another tensor, with its own descriptor.
+pub struct CuTensorViewMut<'a, T: CudnnType + 'a> {
+    descriptor: CuTensorDescriptor<T>,
+    data: *mut T,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: CudnnType + 'a> CuTensorViewMut<'a, T> {
+
+    pub fn descriptor(&self) -> &CuTensorDescriptor<T> {
+        &self.descriptor
+    }
+
+    pub fn tensor<'b>(&'b self) -> CuTensor<'b, T> {
+        CuTensor { deref: CuTensorDeref::new(&self.descriptor, self.data) }
+    }
+
+    pub fn tensor_mut<'b>(&'b mut self) -> CuTensorMut<'b, T> {
+        CuTensorMut { deref: CuTensorDeref::new(&self.descriptor, self.data) }
+    }
+
+}
+
+
+impl<'a, T: CudnnType + 'a> CuTensorDeref<'a, T> {
+
+    fn view<'b>(&'b self, layout: ViewLayout) -> Result<CuTensorView<'b, T>, CudnnError> {
+        let descriptor = CuTensorDescriptor::<T>::new(&layout.dimensions, &layout.strides)?;
+        Ok(CuTensorView { descriptor, data: self.data.wrapping_add(layout.offset), _phantom: PhantomData })
+    }
+
+    fn view_mut<'b>(&'b mut self, layout: ViewLayout) -> Result<CuTensorViewMut<'b, T>, CudnnError> {
+        let descriptor = CuTensorDescriptor::<T>::new(&layout.dimensions, &layout.strides)?;
+        Ok(CuTensorViewMut { descriptor, data: self.data.wrapping_add(layout.offset), _phantom: PhantomData })
+    }
+
+    /// The `len` elements of dimension `dim` starting at `start`
+    pub fn narrow<'b>(&'b self, dim: usize, start: i32, len: i32) -> Result<CuTensorView<'b, T>, CudnnError> {
+        self.view(ViewLayout::narrow(&self.descriptor.get_info()?, dim, start, len)?)
+    }
+
+    pub fn narrow_mut<'b>(&'b mut self, dim: usize, start: i32, len: i32) -> Result<CuTensorViewMut<'b, T>, CudnnError> {
+        let layout = ViewLayout::narrow(&self.descriptor.get_info()?, dim, start, len)?;
+        self.view_mut(layout)
+    }
+
+    /// The slice at `index` along dimension `dim`, which is removed
+    pub fn select<'b>(&'b self, dim: usize, index: i32) -> Result<CuTensorView<'b, T>, CudnnError> {
+        self.view(ViewLayout::select(&self.descriptor.get_info()?, dim, index)?)
+    }
+
+    pub fn select_mut<'b>(&'b mut self, dim: usize, index: i32) -> Result<CuTensorViewMut<'b, T>, CudnnError> {
+        let layout = ViewLayout::select(&self.descriptor.get_info()?, dim, index)?;
+        self.view_mut(layout)
+    }
+
+    /// The same elements with new `dimensions`. The tensor must be fully packed.
+    pub fn reshape<'b>(&'b self, dimensions: &[i32]) -> Result<CuTensorView<'b, T>, CudnnError> {
+        self.view(ViewLayout::reshape(&self.descriptor.get_info()?, dimensions)?)
+    }
+
+    pub fn reshape_mut<'b>(&'b mut self, dimensions: &[i32]) -> Result<CuTensorViewMut<'b, T>, CudnnError> {
+        let layout = ViewLayout::reshape(&self.descriptor.get_info()?, dimensions)?;
+        self.view_mut(layout)
+    }
+
+    /// The dimensions reordered so that dimension `i` of the view is dimension `order[i]` of the tensor
+    pub fn permute<'b>(&'b self, order: &[usize]) -> Result<CuTensorView<'b, T>, CudnnError> {
+        self.view(ViewLayout::permute(&self.descriptor.get_info()?, order)?)
+    }
+
+    pub fn permute_mut<'b>(&'b mut self, order: &[usize]) -> Result<CuTensorViewMut<'b, T>, CudnnError> {
+        let layout = ViewLayout::permute(&self.descriptor.get_info()?, order)?;
+        self.view_mut(layout)
+    }
+
+}
+
+
+/// Dimensions and strides of a view, starting `offset` elements after the data of the viewed tensor
+#[derive(Debug, PartialEq)]
+struct ViewLayout {
+    dimensions: Vec<i32>,
+    strides: Vec<i32>,
+    offset: usize,
+}
+
+impl ViewLayout {
+
+    #[cfg_attr(feature = "disable_checks", allow(unused_variables))]
+    fn check_dim(function: &'static str, info: &CuTensorDescriptorInfo, dim: usize) -> Result<(), CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            if dim >= info.dimensions.len() {
+                return Err(CudnnError::bad_param(function, format!("dimension {} out of {}", dim, info.dimensions.len())))
+            }
+        }
+        Ok(())
+    }
+
+    fn narrow(info: &CuTensorDescriptorInfo, dim: usize, start: i32, len: i32) -> Result<ViewLayout, CudnnError> {
+        Self::check_dim("CuTensorDeref::narrow", info, dim)?;
+        #[cfg(not(feature = "disable_checks"))] {
+            // start is checked first, so that the subtraction can't overflow
+            if start < 0 || len < 1 || len > info.dimensions[dim] - start {
+                return Err(CudnnError::bad_param("CuTensorDeref::narrow", format!("{} elements from {} out of dimension {} of size {}",
+                                                                                   len, start, dim, info.dimensions[dim])))
+            }
+        }
+        let mut dimensions = info.dimensions.clone();
+        dimensions[dim] = len;
+        Ok(ViewLayout { dimensions, strides: info.strides.clone(), offset: (start * info.strides[dim]) as usize })
+    }
+
+    fn select(info: &CuTensorDescriptorInfo, dim: usize, index: i32) -> Result<ViewLayout, CudnnError> {
+        Self::check_dim("CuTensorDeref::select", info, dim)?;
+        #[cfg(not(feature = "disable_checks"))] {
+            if index < 0 || index >= info.dimensions[dim] {
+                return Err(CudnnError::bad_param("CuTensorDeref::select", format!("index {} out of dimension {} of size {}",
+                                                                                   index, dim, info.dimensions[dim])))
+            }
+            if info.dimensions.len() <= 3 {
+                return Err(CudnnError::bad_param("CuTensorDeref::select", "tensors must keep at least 3 dimensions, narrow instead"))
+            }
+        }
+        let mut dimensions = info.dimensions.clone();
+        let mut strides = info.strides.clone();
+        dimensions.remove(dim);
+        let stride = strides.remove(dim);
+        Ok(ViewLayout { dimensions, strides, offset: (index * stride) as usize })
+    }
+
+    #[cfg_attr(feature = "disable_checks", allow(unused_variables))]
+    fn reshape(info: &CuTensorDescriptorInfo, dimensions: &[i32]) -> Result<ViewLayout, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            let packed = get_fully_packed_strides(&info.dimensions);
+            if info.dimensions.iter().zip(info.strides.iter().zip(packed.iter())).any(|(&dim, (stride, packed))| dim != 1 && stride != packed) {
+                return Err(CudnnError::bad_param("CuTensorDeref::reshape", format!("can't reshape a tensor which isn't fully packed, strides are {:?}", info.strides)))
+            }
+            if dimensions.iter().product::<i32>() != info.dimensions.iter().product::<i32>() {
+                return Err(CudnnError::bad_param("CuTensorDeref::reshape", format!("can't reshape {:?} into {:?}", info.dimensions, dimensions)))
+            }
+        }
+        Ok(ViewLayout { dimensions: dimensions.to_vec(), strides: get_fully_packed_strides(dimensions), offset: 0 })
+    }
+
+    fn permute(info: &CuTensorDescriptorInfo, order: &[usize]) -> Result<ViewLayout, CudnnError> {
+        #[cfg(not(feature = "disable_checks"))] {
+            let mut sorted = order.to_vec();
+            sorted.sort();
+            if sorted != (0..info.dimensions.len()).collect::<Vec<_>>() {
+                return Err(CudnnError::bad_param("CuTensorDeref::permute", format!("{:?} isn't a permutation of the {} dimensions", order, info.dimensions.len())))
+            }
+        }
+        Ok(ViewLayout {
+            dimensions: order.iter().map(|&i| info.dimensions[i]).collect(),
+            strides: order.iter().map(|&i| info.strides[i]).collect(),
+            offset: 0,
+        })
+    }
+
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cudnn::Cudnn;
+    use cumath::CuVector;
+
+    fn info(dimensions: &[i32]) -> CuTensorDescriptorInfo {
+        CuTensorDescriptorInfo {
+            data_type: CudnnDataType::Float,
+            nb_dims: dimensions.len() as i32,
+            dimensions: dimensions.to_vec(),
+            strides: get_fully_packed_strides(dimensions),
+        }
+    }
+
+    #[test]
+    fn narrow_layout() {
+        assert_eq!(ViewLayout::narrow(&info(&[4, 3, 5]), 0, 1, 2).unwrap(),
+                   ViewLayout { dimensions: vec![2, 3, 5], strides: vec![15, 5, 1], offset: 15 });
+        assert_eq!(ViewLayout::narrow(&info(&[4, 3, 5]), 2, 3, 2).unwrap(),
+                   ViewLayout { dimensions: vec![4, 3, 2], strides: vec![15, 5, 1], offset: 3 });
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 3, 0, 1).is_err());
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 1, 2, 2).is_err());
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 1, -1, 2).is_err());
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 1, 0, 0).is_err());
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 1, ::std::i32::MAX, 1).is_err());
+            assert!(ViewLayout::narrow(&info(&[4, 3, 5]), 1, 1, ::std::i32::MAX).is_err());
+        }
+    }
+
+    #[test]
+    fn select_layout() {
+        assert_eq!(ViewLayout::select(&info(&[4, 3, 5, 2]), 1, 2).unwrap(),
+                   ViewLayout { dimensions: vec![4, 5, 2], strides: vec![30, 2, 1], offset: 20 });
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(ViewLayout::select(&info(&[4, 3, 5, 2]), 1, 3).is_err());
+            assert!(ViewLayout::select(&info(&[4, 3, 5]), 0, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn reshape_layout() {
+        assert_eq!(ViewLayout::reshape(&info(&[4, 3, 5]), &[2, 2, 15]).unwrap(),
+                   ViewLayout { dimensions: vec![2, 2, 15], strides: vec![30, 15, 1], offset: 0 });
+        let narrowed = ViewLayout::narrow(&info(&[4, 3, 5]), 0, 1, 1).unwrap();
+        let narrowed = CuTensorDescriptorInfo { data_type: CudnnDataType::Float, nb_dims: 3, dimensions: narrowed.dimensions, strides: narrowed.strides };
+        assert!(ViewLayout::reshape(&narrowed, &[1, 15, 1]).is_ok());
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(ViewLayout::reshape(&info(&[4, 3, 5]), &[4, 3, 4]).is_err());
+            let narrowed = ViewLayout::narrow(&info(&[4, 3, 5]), 2, 0, 2).unwrap();
+            let narrowed = CuTensorDescriptorInfo { data_type: CudnnDataType::Float, nb_dims: 3, dimensions: narrowed.dimensions, strides: narrowed.strides };
+            assert!(ViewLayout::reshape(&narrowed, &[4, 6, 1]).is_err());
+        }
+    }
+
+    #[test]
+    fn permute_layout() {
+        assert_eq!(ViewLayout::permute(&info(&[4, 3, 5]), &[2, 0, 1]).unwrap(),
+                   ViewLayout { dimensions: vec![5, 4, 3], strides: vec![1, 15, 5], offset: 0 });
+        #[cfg(not(feature = "disable_checks"))] {
+            assert!(ViewLayout::permute(&info(&[4, 3, 5]), &[0, 1]).is_err());
+            assert!(ViewLayout::permute(&info(&[4, 3, 5]), &[0, 1, 1]).is_err());
+        }
+    }
+
+    #[test]
+    fn views() {
+        let cudnn = Cudnn::new().unwrap();
+        let descriptor = CuTensorDescriptor::<f32>::fully_packed(&[4, 3, 5]).unwrap();
+        let mut data = CuVector::<f32>::zero(descriptor.data_len());
+        {
+            let mut tensor = descriptor.link_mut(&mut data).unwrap();
+            let mut batch = tensor.narrow_mut(0, 1, 2).unwrap();
+            assert_eq!(batch.descriptor().get_info().unwrap().dimensions, vec![2, 3, 5]);
+            batch.tensor_mut().init(&cudnn, 1.0).unwrap();
+            let mut channel = tensor.narrow_mut(1, 2, 1).unwrap();
+            channel.tensor_mut().init(&cudnn, 2.0).unwrap();
+        }
+        let mut output = vec![0.0; descriptor.data_len()];
+        data.clone_to_host(&mut output);
+        let expected = (0..60).map(|i| if (i / 5) % 3 == 2 { 2.0 } else if i >= 15 && i < 45 { 1.0 } else { 0.0 }).collect::<Vec<_>>();
+        assert_eq!(output, expected);
+
+        let tensor = descriptor.link(&data).unwrap();
+        let reshaped = tensor.reshape(&[2, 6, 5]).unwrap();
+        let reshaped = reshaped.tensor();
+        let permuted = reshaped.permute(&[2, 1, 0]).unwrap();
+        assert_eq!(permuted.descriptor().get_info().unwrap().strides, vec![1, 5, 30]);
+    }
+
+}
@@ -0,0 +1,67 @@
+
+use std::os::raw::c_void;
+use cudnn::Cudnn;
+use cudnn_type::CudnnType;
+use ffi::*;
+use super::*;
+
+
+
+/// Computes `dst = alpha * src + beta * dst`, copying the elements of `src` into the layout of `dst`.
+/// Both tensors must have the same dimensions, their strides can be anything, e.g. NCHW and NHWC.
+pub fn transform<T: CudnnType>(cudnn: &Cudnn, alpha: T::Scaling, src: &CuTensorDeref<T>,
+                               beta: T::Scaling, dst: &mut CuTensorDeref<T>) -> Result<(), CudnnError> {
+    #[cfg(not(feature = "disable_checks"))] {
+        let src_dims = src.descriptor.get_info()?.dimensions;
+        let dst_dims = dst.descriptor.get_info()?.dimensions;
+        if src_dims != dst_dims {
+            return Err(CudnnError::bad_param("transform", format!("src dimensions {:?} != dst dimensions {:?}", src_dims, dst_dims)))
+        }
+    }
+    cudnn_transform_tensor(cudnn.handle,
+                           &alpha as *const T::Scaling as *const c_void, src.descriptor.data, src.data as *const c_void,
+                           &beta as *const T::Scaling as *const c_void, dst.descriptor.data, dst.data as *mut c_void)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use cumath::CuVector;
+
+    #[test]
+    fn nchw_to_nhwc() {
+        let cudnn = Cudnn::new().unwrap();
+        let (n, c, h, w) = (2, 3, 4, 5);
+        let nchw = CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nchw, n, c, h, w).unwrap();
+        let nhwc = CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nhwc, n, c, h, w).unwrap();
+        let input_data = (0..nchw.data_len()).map(|x| x as f32).collect::<Vec<_>>();
+        let input = CuVector::<f32>::from_host_data(&input_data);
+        let mut output = CuVector::<f32>::new(1.0, nhwc.data_len());
+
+        transform(&cudnn, 2.0, &nchw.link(&input).unwrap(), 1.0, &mut nhwc.link_mut(&mut output).unwrap()).unwrap();
+
+        let mut expected = vec![0.0; input_data.len()];
+        for (i, &x) in input_data.iter().enumerate() {
+            let (ni, ci, hi, wi) = (i / (c*h*w) as usize, i / (h*w) as usize % c as usize, i / w as usize % h as usize, i % w as usize);
+            expected[((ni * h as usize + hi) * w as usize + wi) * c as usize + ci] = 2.0 * x + 1.0;
+        }
+        let mut output_data = vec![0.0; expected.len()];
+        output.clone_to_host(&mut output_data);
+        assert_eq!(output_data, expected);
+    }
+
+    #[test]
+    fn invalid_dimensions() {
+        let cudnn = Cudnn::new().unwrap();
+        let src = CuTensorDescriptor::<f32>::fully_packed(&[2, 3, 4, 5]).unwrap();
+        let dst = CuTensorDescriptor::<f32>::new_4d(CudnnTensorFormat::Nhwc, 2, 3, 5, 4).unwrap();
+        let input = CuVector::<f32>::zero(src.data_len());
+        let mut output = CuVector::<f32>::zero(dst.data_len());
+        let error = transform(&cudnn, 1.0, &src.link(&input).unwrap(), 0.0, &mut dst.link_mut(&mut output).unwrap()).unwrap_err();
+        assert_eq!(error.status(), CudnnStatus::BadParam);
+    }
+
+}
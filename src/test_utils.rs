@@ -0,0 +1,20 @@
+
+//! Helpers shared by the tests of the descriptors.
+
+use cumath::*;
+
+
+
+/// Deterministic values in [-1, 1], different for each seed.
+pub fn host_data(len: usize, seed: usize) -> Vec<f32> {
+    (0..len).map(|i| ((i * 7 + seed) % 13) as f32 / 6.0 - 1.0).collect()
+}
+
+/// Asserts that the device vector `output` matches `expected` up to a relative error of 1e-4.
+pub fn assert_close(name: &str, output: &CuVector<f32>, expected: &[f32]) {
+    let mut buffer = vec![0.0; expected.len()];
+    output.clone_to_host(&mut buffer);
+    for i in 0..expected.len() {
+        assert!((buffer[i] - expected[i]).abs() < 1e-4 * expected[i].abs().max(1.0), "{} : {:?} != {:?}", name, buffer, expected);
+    }
+}